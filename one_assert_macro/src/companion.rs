@@ -0,0 +1,171 @@
+//! Companion macros to `assert!` for checks that can't be expressed as a simple condition
+
+use crate::*;
+
+mod all_close;
+mod bits;
+mod contains;
+mod converges;
+mod count;
+mod drop_count;
+mod empty;
+mod eq_by;
+mod eq_ignoring;
+mod err_contains;
+mod expand;
+mod fields_eq;
+mod files_eq;
+mod glob;
+mod hash;
+mod impl_check;
+mod layout;
+mod maintains;
+mod matrix_close;
+mod monotonic;
+mod one_of;
+mod order;
+mod output_contains;
+mod ready;
+mod returning;
+mod same_elements;
+mod same_output;
+mod should_panic;
+mod similar;
+mod starts_with;
+mod subsequence;
+mod success;
+mod that;
+mod try_assert;
+mod unchanged;
+mod within_steps;
+
+pub(crate) use all_close::*;
+pub(crate) use bits::*;
+pub(crate) use contains::*;
+pub(crate) use converges::*;
+pub(crate) use count::*;
+pub(crate) use drop_count::*;
+pub(crate) use empty::*;
+pub(crate) use eq_by::*;
+pub(crate) use eq_ignoring::*;
+pub(crate) use err_contains::*;
+pub(crate) use expand::*;
+pub(crate) use fields_eq::*;
+pub(crate) use files_eq::*;
+pub(crate) use glob::*;
+pub(crate) use hash::*;
+pub(crate) use impl_check::*;
+pub(crate) use layout::*;
+pub(crate) use maintains::*;
+pub(crate) use matrix_close::*;
+pub(crate) use monotonic::*;
+pub(crate) use one_of::*;
+pub(crate) use order::*;
+pub(crate) use output_contains::*;
+pub(crate) use ready::*;
+pub(crate) use returning::*;
+pub(crate) use same_elements::*;
+pub(crate) use same_output::*;
+pub(crate) use should_panic::*;
+pub(crate) use similar::*;
+pub(crate) use starts_with::*;
+pub(crate) use subsequence::*;
+pub(crate) use success::*;
+pub(crate) use that::*;
+pub(crate) use try_assert::*;
+pub(crate) use unchanged::*;
+pub(crate) use within_steps::*;
+
+/// Parse a fixed list of comma-separated expressions, followed by an optional message.
+///
+/// `names` are the names of the expected expressions, used for error messages.
+pub(crate) fn parse_exprs(
+    input: syn::parse::ParseStream,
+    names: &[&str],
+) -> syn::Result<(Vec<syn::Expr>, TokenStream)> {
+    let mut exprs = vec![];
+    for (i, name) in names.iter().enumerate() {
+        if input.is_empty() {
+            let msg = format!("missing parameter `{name}`");
+            return Err(syn::Error::new(Span::call_site(), msg)); // checked in tests/fail/companion.rs
+        }
+        exprs.push(input.parse()?);
+        if input.is_empty() {
+            continue;
+        }
+        if let Err(e) = input.parse::<syn::Token![,]>() {
+            let msg = if i + 1 < names.len() {
+                format!("expected a comma before the `{}` parameter", names[i + 1])
+            } else {
+                "expected a comma before the message".to_owned()
+            };
+            return Err(syn::Error::new(e.span(), msg)); // checked in tests/fail/companion.rs
+        }
+    }
+    let format = input.parse()?;
+    Ok((exprs, format))
+}
+
+/// Parse optional `name = value` parameters in front of the message. Each of `names` can be given at most
+/// once, in any order.
+pub(crate) fn parse_named_params(
+    input: syn::parse::ParseStream,
+    names: &[&str],
+) -> syn::Result<(Vec<(String, syn::Expr)>, TokenStream)> {
+    let mut params: Vec<(String, syn::Expr)> = vec![];
+    // a message has to start with a string literal or a macro, so `ident =` can only be a parameter
+    while input.peek(syn::Ident) && input.peek2(syn::Token![=]) && !input.peek2(syn::Token![==]) {
+        let ident = input.parse::<syn::Ident>()?;
+        let name = ident.to_string();
+        if !names.contains(&name.as_str()) {
+            let expected = utils::list_items(names, |n| format!("`{n}`"));
+            let msg = format!("unknown parameter `{name}`. Expected {expected}");
+            return Err(syn::Error::new_spanned(ident, msg)); // checked in tests/fail/companion.rs
+        }
+        if params.iter().any(|(existing, _)| *existing == name) {
+            let msg = format!("parameter `{name}` was specified more than once");
+            return Err(syn::Error::new_spanned(ident, msg));
+        }
+        input.parse::<syn::Token![=]>()?;
+        params.push((name, input.parse()?));
+
+        if input.is_empty() {
+            break;
+        }
+        if let Err(e) = input.parse::<syn::Token![,]>() {
+            let msg = "expected a comma before the message";
+            return Err(syn::Error::new(e.span(), msg)); // checked in tests/fail/companion.rs
+        }
+    }
+    Ok((params, input.parse()?))
+}
+
+/// Generate the `panic!` call for a failed companion assertion.
+///
+/// The message has the form "assertion \`{header}\` failed: {message}{details}", where `header` has to be
+/// escaped for a format string already (see [`printable_expr_string`]) and `details` is an expression
+/// evaluating to a `String` with the `name: value` lines. `details` is only evaluated if the assertion fails.
+pub(crate) fn panic_call(header: &str, format: &TokenStream, details: TokenStream) -> TokenStream {
+    let mut format_message = format!("assertion `{header}` failed");
+    let mut args = vec![];
+    if !format.is_empty() {
+        format_message += ": {}";
+        args.push(quote! { ::std::format_args!(#format) });
+    }
+    format_message += "{}";
+    args.push(details);
+    panic_with_message(&format_message, &args)
+}
+
+/// Get the value of a `name = value` parameter
+pub(crate) fn named_arg(expr: syn::Expr, name: &str) -> Result<syn::Expr> {
+    match expr {
+        syn::Expr::Assign(syn::ExprAssign { left, right, .. }) if matches!(&*left, syn::Expr::Path(path) if path.path.is_ident(name)) => {
+            Ok(*right)
+        }
+        expr => {
+            let msg = format!("expected `{name} = ...`");
+            Error::err_spanned(expr, msg) // checked in tests/fail/companion.rs
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use super::*;
+
+/// `assert_bits_eq!(left, right, message...)`
+pub(crate) fn assert_bits_eq_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["left", "right"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [left, right] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} bitwise equals {}",
+        printable_expr_string(&left),
+        printable_expr_string(&right)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    // the `FloatBits` bound of `bits_eq_failure` restricts the operands to `f32` and `f64`
+    Ok(quote! {
+        match (&(#left), &(#right)) {
+            (__one_assert_left, __one_assert_right) => {
+                if let ::std::option::Option::Some(__one_assert_details) =
+                    ::one_assert::__macro_utilities::bits_eq_failure(*__one_assert_left, *__one_assert_right)
+                {
+                    #panic
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+/// `assert_contains!(haystack, needle, message...)`
+pub(crate) fn assert_contains_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["haystack", "needle"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [haystack, needle] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} contains {}",
+        printable_expr_string(&haystack),
+        printable_expr_string(&needle)
+    );
+    let panic = panic_call(
+        &header,
+        &format,
+        quote! {
+            ::one_assert::__macro_utilities::contains_failure(
+                __one_assert_haystack,
+                __one_assert_needle,
+                __one_assert_haystack.one_assert_near_match(__one_assert_needle),
+            )
+        },
+    );
+
+    // borrow both sides, same as `assert_eq!`, so that they are still usable afterwards
+    Ok(quote! {
+        match (&(#haystack), &(#needle)) {
+            (__one_assert_haystack, __one_assert_needle) => {
+                #[allow(unused_imports)]
+                use ::one_assert::OneAssertContains as _;
+                if __one_assert_haystack.one_assert_contains(__one_assert_needle) {
+                } else {
+                    #panic
+                }
+            }
+        }
+    })
+}
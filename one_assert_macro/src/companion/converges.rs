@@ -0,0 +1,36 @@
+use super::*;
+
+/// `assert_converges!(step, initial, max_steps = N, done = predicate, message...)`
+pub(crate) fn assert_converges_internal(input: TokenStream) -> Result<TokenStream> {
+    let names = ["step", "initial", "max_steps", "done"];
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &names);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [step, initial, max_steps, done] =
+        <[syn::Expr; 4]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+    let max_steps = named_arg(max_steps, "max_steps")?;
+    let done = named_arg(done, "done")?;
+
+    let header = format!(
+        "{} reaches {} from {} within {} steps",
+        printable_expr_string(&step),
+        printable_expr_string(&done),
+        printable_expr_string(&initial),
+        printable_expr_string(&max_steps)
+    );
+    let panic = panic_call(
+        &header,
+        &format,
+        quote! {
+            ::one_assert::__macro_utilities::converge_failure(__one_assert_steps, &__one_assert_state)
+        },
+    );
+
+    Ok(quote! {
+        match ::one_assert::__macro_utilities::converge(#initial, #max_steps, #step, #done) {
+            ::std::result::Result::Ok(_) => {}
+            ::std::result::Result::Err((__one_assert_steps, __one_assert_state)) => {
+                #panic
+            }
+        }
+    })
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+/// `assert_count!(iter, expected, message...)`
+pub(crate) fn assert_count_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["iter", "expected"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [iter, expected] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} yields {} items",
+        printable_expr_string(&iter),
+        printable_expr_string(&expected)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::iter_count_failure(#iter, #expected)
+        {
+            #panic
+        }
+    })
+}
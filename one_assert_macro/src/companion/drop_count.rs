@@ -0,0 +1,23 @@
+use super::*;
+
+/// `assert_drop_count!(counter, expected, message...)`
+pub(crate) fn assert_drop_count_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["counter", "expected"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [counter, expected] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} counted {} drops",
+        printable_expr_string(&counter),
+        printable_expr_string(&expected)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::drop_count_failure(&#counter, #expected)
+        {
+            #panic
+        }
+    })
+}
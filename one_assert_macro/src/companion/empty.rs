@@ -0,0 +1,20 @@
+use super::*;
+
+/// `assert_empty!(source, message...)`
+pub(crate) fn assert_empty_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["source"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [source] = <[syn::Expr; 1]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!("{} is empty", printable_expr_string(&source));
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) = {
+            use ::one_assert::__macro_utilities::{LeftoverExhaustible as _, LeftoverIterator as _};
+            (&&::one_assert::__macro_utilities::Leftover::new(#source)).__one_assert_leftover()
+        } {
+            #panic
+        }
+    })
+}
@@ -0,0 +1,26 @@
+use super::*;
+
+/// `assert_eq_by!(left, right, normalize, message...)`
+pub(crate) fn assert_eq_by_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser =
+        |input: syn::parse::ParseStream| parse_exprs(input, &["left", "right", "normalize"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [left, right, normalize] =
+        <[syn::Expr; 3]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} equals {} after {}",
+        printable_expr_string(&left),
+        printable_expr_string(&right),
+        printable_expr_string(&normalize)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::eq_by_failure(&#left, &#right, #normalize)
+        {
+            #panic
+        }
+    })
+}
@@ -0,0 +1,58 @@
+use super::*;
+
+/// `assert_eq_ignoring!(left, right, [fields...], message...)`
+pub(crate) fn assert_eq_ignoring_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["left", "right", "ignored"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [left, right, ignored] =
+        <[syn::Expr; 3]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let syn::Expr::Array(syn::ExprArray { elems, .. }) = ignored else {
+        let msg = "expected a list of ignored fields in brackets: `[a, b, c]`";
+        return Error::err_spanned(ignored, msg); // checked in tests/fail/companion.rs
+    };
+    let mut fields = vec![];
+    for elem in elems {
+        let name = match &elem {
+            syn::Expr::Path(syn::ExprPath { path, .. }) if path.get_ident().is_some() => {
+                path.get_ident().unwrap().to_string()
+            }
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(i),
+                ..
+            }) if i.suffix().is_empty() => i.base10_digits().to_owned(),
+            _ => {
+                let msg = "expected the name of a field, or the index of a tuple struct field";
+                return Error::err_spanned(elem, msg); // checked in tests/fail/companion.rs
+            }
+        };
+        fields.push(name);
+    }
+
+    let mut header = format!(
+        "{} == {}",
+        printable_expr_string(&left),
+        printable_expr_string(&right)
+    );
+    if !fields.is_empty() {
+        header += &format!(" ignoring {}", fields.join(", "));
+    }
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    // borrowed, same as with `assert_eq!`
+    Ok(quote! {
+        match (&(#left), &(#right)) {
+            (__one_assert_left, __one_assert_right) => {
+                if let ::std::option::Option::Some(__one_assert_details) =
+                    ::one_assert::__macro_utilities::fields_failure(
+                        __one_assert_left,
+                        __one_assert_right,
+                        &[#(#fields),*],
+                    )
+                {
+                    #panic
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+/// `assert_err_contains!(result, needle, message...)`
+pub(crate) fn assert_err_contains_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["result", "needle"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [result, needle] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} is an error containing {}",
+        printable_expr_string(&result),
+        printable_expr_string(&needle)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::err_contains_failure(&#result, &#needle)
+        {
+            #panic
+        }
+    })
+}
@@ -0,0 +1,8 @@
+use super::*;
+
+/// `assert_expand!(condition, options..., message...)`
+pub(crate) fn assert_expand_internal(input: Args) -> Result<TokenStream> {
+    // errors are reported as usual, since there would be no code to show anyway
+    let code = assert_internal(input, DisplayReplacements::default())?.to_string();
+    Ok(quote! { #code })
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+/// `assert_matches_glob!(string, pattern, message...)`
+pub(crate) fn assert_matches_glob_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["string", "pattern"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [string, pattern] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} matches the glob {}",
+        printable_expr_string(&string),
+        printable_expr_string(&pattern)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::glob_failure(&#string, &#pattern)
+        {
+            #panic
+        }
+    })
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+/// `assert_hash_eq!(left, right, message...)`
+pub(crate) fn assert_hash_eq_internal(input: TokenStream) -> Result<TokenStream> {
+    hash_comparison(input, true)
+}
+
+/// `assert_hash_ne!(left, right, message...)`
+pub(crate) fn assert_hash_ne_internal(input: TokenStream) -> Result<TokenStream> {
+    hash_comparison(input, false)
+}
+
+/// Shared implementation of [`assert_hash_eq_internal`] and [`assert_hash_ne_internal`]
+fn hash_comparison(input: TokenStream, equal: bool) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["left", "right"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [left, right] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} hashes {} {}",
+        printable_expr_string(&left),
+        if equal { "equal to" } else { "different from" },
+        printable_expr_string(&right)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::hash_failure(&#left, &#right, #equal)
+        {
+            #panic
+        }
+    })
+}
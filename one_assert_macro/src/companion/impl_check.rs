@@ -0,0 +1,42 @@
+use super::*;
+
+/// `assert_impl!(Type: Bound + Bound...)`
+pub(crate) fn assert_impl_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| {
+        if input.is_empty() {
+            let msg = "missing type to check: `assert_impl!(Type: Trait + Trait)`";
+            return Err(syn::Error::new(Span::call_site(), msg)); // checked in tests/fail/companion.rs
+        }
+        let ty = input.parse::<syn::Type>()?;
+        if let Err(e) = input.parse::<syn::Token![:]>() {
+            let msg = "expected a `:` between the type and its traits: `Type: Trait + Trait`";
+            return Err(syn::Error::new(e.span(), msg)); // checked in tests/fail/companion.rs
+        }
+        let bounds = syn::punctuated::Punctuated::<syn::TypeParamBound, syn::Token![+]>::parse_separated_nonempty(input)?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        Ok((ty, bounds))
+    };
+    let (ty, bounds) = syn::parse::Parser::parse2(parser, input)?;
+
+    // unsized types are allowed, unless the bounds already say so
+    let maybe_sized = bounds.iter().any(|bound| {
+        matches!(
+            bound,
+            syn::TypeParamBound::Trait(syn::TraitBound {
+                modifier: syn::TraitBoundModifier::Maybe(_),
+                ..
+            })
+        )
+    });
+    let unsized_bound = (!maybe_sized).then(|| quote! { ?::std::marker::Sized + });
+
+    // the call gets the span of the type, so that an unsatisfied bound points at the type
+    let span = utils::FullSpan::from_spanned(&ty).start();
+    let check = quote_spanned! {span=> __one_assert_impl::<#ty>(); };
+    Ok(quote! {
+        const _: fn() = || {
+            fn __one_assert_impl<T: #unsized_bound #bounds>() {}
+            #check
+        };
+    })
+}
@@ -0,0 +1,38 @@
+use super::*;
+
+/// `assert_size!(Type, size)`
+pub(crate) fn assert_size_internal(input: TokenStream) -> Result<TokenStream> {
+    layout_check(input, quote! { size_of })
+}
+
+/// `assert_align!(Type, alignment)`
+pub(crate) fn assert_align_internal(input: TokenStream) -> Result<TokenStream> {
+    layout_check(input, quote! { align_of })
+}
+
+/// Compare `std::mem::#function::<Type>()` to the expected value at compile time
+fn layout_check(input: TokenStream, function: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| {
+        if input.is_empty() {
+            let msg = "missing type to check";
+            return Err(syn::Error::new(Span::call_site(), msg)); // checked in tests/fail/companion.rs
+        }
+        let ty = input.parse::<syn::Type>()?;
+        if let Err(e) = input.parse::<syn::Token![,]>() {
+            let msg = "expected a comma between the type and the expected value";
+            return Err(syn::Error::new(e.span(), msg)); // checked in tests/fail/companion.rs
+        }
+        let expected = input.parse::<syn::Expr>()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        Ok((ty, expected))
+    };
+    let (ty, expected) = syn::parse::Parser::parse2(parser, input)?;
+
+    // Formatting numbers in a const panic is not possible, but comparing the lengths of two arrays makes the
+    // compiler print both values: "expected an array with a size of 16, found one with a size of 24"
+    let span = utils::FullSpan::from_spanned(&ty).start();
+    let actual = quote_spanned! {span=> [(); ::std::mem::#function::<#ty>()] };
+    Ok(quote! {
+        const _: [(); #expected] = #actual;
+    })
+}
@@ -0,0 +1,29 @@
+use super::*;
+
+/// `assert_maintains!(invariant, body, message...)`
+pub(crate) fn assert_maintains_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["invariant", "body"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [invariant, body] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let invariant_str = printable_expr_string(&invariant);
+    let body_str = printable_expr_string(&body);
+    let check = |point: &str| {
+        // every checkpoint is a full assertion of its own, so both get the analysis of `assert!`
+        let mut state = State::new(Options::default(), DisplayReplacements::default());
+        state.start(
+            &format!("{invariant_str} holds {point} {body_str}"),
+            &format,
+        );
+        eval_expr(invariant.clone(), state)
+    };
+    let before = check("before")?;
+    let after = check("after")?;
+
+    Ok(quote! {{
+        #before
+        let __one_assert_result = #body;
+        #after
+        __one_assert_result
+    }})
+}
@@ -0,0 +1,26 @@
+use super::*;
+
+/// `assert_matrix_close!(left, right, tolerance, message...)`
+pub(crate) fn assert_matrix_close_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser =
+        |input: syn::parse::ParseStream| parse_exprs(input, &["left", "right", "tolerance"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [left, right, tolerance] =
+        <[syn::Expr; 3]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} is element-wise within {} of {}",
+        printable_expr_string(&left),
+        printable_expr_string(&tolerance),
+        printable_expr_string(&right)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::matrix_close_failure(&#left, &#right, #tolerance)
+        {
+            #panic
+        }
+    })
+}
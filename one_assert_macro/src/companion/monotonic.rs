@@ -0,0 +1,62 @@
+use super::*;
+
+/// `assert_monotonic!(sequence, strict = bool, direction = "...", message...)`, where the parameters are optional
+pub(crate) fn assert_monotonic_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["sequence"]);
+    let (exprs, rest) = syn::parse::Parser::parse2(parser, input)?;
+    let [sequence] = <[syn::Expr; 1]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let parser =
+        |input: syn::parse::ParseStream| parse_named_params(input, &["strict", "direction"]);
+    let (params, format) = syn::parse::Parser::parse2(parser, rest)?;
+    let mut strict = false;
+    let mut decreasing = false;
+    for (name, value) in params {
+        match (name.as_str(), value) {
+            (
+                "strict",
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Bool(value),
+                    ..
+                }),
+            ) => strict = value.value,
+            (
+                "direction",
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }),
+            ) if value.value() == "increasing" || value.value() == "decreasing" => {
+                decreasing = value.value() == "decreasing"
+            }
+            ("strict", value) => {
+                let msg = "expected `strict = true` or `strict = false`";
+                return Error::err_spanned(value, msg); // checked in tests/fail/companion.rs
+            }
+            (_, value) => {
+                let msg = r#"expected `direction = "increasing"` or `direction = "decreasing"`"#;
+                return Error::err_spanned(value, msg); // checked in tests/fail/companion.rs
+            }
+        }
+    }
+
+    let header = format!(
+        "{} is {}{}",
+        printable_expr_string(&sequence),
+        if strict { "strictly " } else { "" },
+        if decreasing {
+            "decreasing"
+        } else {
+            "increasing"
+        }
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::monotonic_violation(#sequence, #decreasing, #strict)
+        {
+            #panic
+        }
+    })
+}
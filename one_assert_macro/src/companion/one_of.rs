@@ -0,0 +1,53 @@
+use super::*;
+
+/// `assert_one_of!(value, [allowed...], message...)`
+pub(crate) fn assert_one_of_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["value", "allowed"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [value, allowed] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    // the elements have to be known, so that they can be listed in the header and compared one by one
+    let syn::Expr::Array(syn::ExprArray {
+        elems,
+        bracket_token,
+        ..
+    }) = allowed
+    else {
+        let msg = "expected a list of allowed values in brackets: `[a, b, c]`";
+        return Error::err_spanned(allowed, msg); // checked in tests/fail/companion.rs
+    };
+    if elems.is_empty() {
+        let msg = "the list of allowed values is empty, so the assertion would always fail";
+        return Error::err(bracket_token.span.join(), msg); // checked in tests/fail/companion.rs
+    }
+    let elems = elems.into_iter().collect::<Vec<_>>();
+
+    let header = format!(
+        "{} is one of {}",
+        printable_expr_string(&value),
+        utils::list_items(&elems, printable_expr_string)
+    );
+    let names = (0..elems.len())
+        .map(|i| syn::Ident::new(&format!("__one_assert_allowed_{i}"), Span::call_site()))
+        .collect::<Vec<_>>();
+    let panic = panic_call(
+        &header,
+        &format,
+        quote! {
+            ::one_assert::__macro_utilities::one_of_failure(__one_assert_value, &[#(#names),*])
+        },
+    );
+
+    // a tuple instead of an array, so that the allowed values don't have to be of the same type.
+    // Everything is borrowed, same as with `assert_eq!`
+    Ok(quote! {
+        match (&(#value), #(&(#elems)),*) {
+            (__one_assert_value, #(#names),*) => {
+                if #(__one_assert_value == #names)||* {
+                } else {
+                    #panic
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+/// `assert_order!(code, [steps...], message...)`
+pub(crate) fn assert_order_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["code", "steps"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [code, steps] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    if !matches!(code, syn::Expr::Closure(_)) {
+        let msg = "expected a closure that marks the steps it reaches: `|step| { ... }`";
+        return Error::err_spanned(code, msg); // checked in tests/fail/companion.rs
+    }
+
+    let header = format!(
+        "{} reaches the steps {} in order",
+        printable_expr_string(&code),
+        printable_expr_string(&steps)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    // the closure is passed to a function instead of being called directly, so that its parameter has a type
+    Ok(quote! {
+        match ::one_assert::__macro_utilities::record_order(#code) {
+            (__one_assert_result, __one_assert_steps) => {
+                if let ::std::option::Option::Some(__one_assert_details) =
+                    ::one_assert::__macro_utilities::order_failure(&__one_assert_steps, &#steps)
+                {
+                    #panic
+                }
+                __one_assert_result
+            }
+        }
+    })
+}
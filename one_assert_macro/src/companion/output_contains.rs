@@ -0,0 +1,37 @@
+use super::*;
+
+/// `assert_output_contains!(code, expected, message...)`
+pub(crate) fn assert_output_contains_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["code", "expected"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [code, expected] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    if !matches!(code, syn::Expr::Closure(_)) {
+        let msg = "expected a closure that writes its output to the given buffer: `|out| { ... }`";
+        return Error::err_spanned(code, msg);
+    }
+
+    let header = format!(
+        "output of {} contains {}",
+        printable_expr_string(&code),
+        printable_expr_string(&expected)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    // the closure is passed to a function instead of being called directly, so that its parameter has a type
+    Ok(quote! {
+        match ::one_assert::__macro_utilities::capture_output(#code) {
+            (__one_assert_result, __one_assert_output) => {
+                if let ::std::option::Option::Some(__one_assert_details) =
+                    ::one_assert::__macro_utilities::output_contains_failure(
+                        &__one_assert_output,
+                        &#expected,
+                    )
+                {
+                    #panic
+                }
+                __one_assert_result
+            }
+        }
+    })
+}
@@ -0,0 +1,21 @@
+use super::*;
+
+/// `assert_ready!(future, message...)`
+pub(crate) fn assert_ready_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["future"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [future] = <[syn::Expr; 1]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!("{} is ready", printable_expr_string(&future));
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        match ::one_assert::__macro_utilities::poll_once(#future) {
+            ::std::task::Poll::Ready(__one_assert_value) => __one_assert_value,
+            ::std::task::Poll::Pending => {
+                let __one_assert_details = ::one_assert::__macro_utilities::pending_failure();
+                #panic
+            }
+        }
+    })
+}
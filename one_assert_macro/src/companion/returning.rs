@@ -0,0 +1,28 @@
+use super::*;
+
+/// `assert_returning!(name = value; condition, options..., message...)`
+pub(crate) fn assert_returning_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| {
+        let fork = input.fork();
+        if fork.parse::<syn::Ident>().is_err() || fork.parse::<syn::Token![=]>().is_err() {
+            let msg = "expected a binding for the value in front of the condition: `name = value; condition`";
+            return Err(syn::Error::new(input.span(), msg)); // checked in tests/fail/companion.rs
+        }
+        let name = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![=]>()?;
+        let value = input.parse::<syn::Expr>()?;
+        if let Err(e) = input.parse::<syn::Token![;]>() {
+            let msg = "expected a `;` between the value and the condition";
+            return Err(syn::Error::new(e.span(), msg)); // checked in tests/fail/companion.rs
+        }
+        Ok((name, value, input.parse::<Args>()?))
+    };
+    let (name, value, args) = syn::parse::Parser::parse2(parser, input)?;
+
+    let assertion = assert_internal(args, DisplayReplacements::default())?;
+    Ok(quote! {{
+        let #name = #value;
+        #assertion
+        #name
+    }})
+}
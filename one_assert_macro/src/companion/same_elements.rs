@@ -0,0 +1,23 @@
+use super::*;
+
+/// `assert_same_elements!(left, right, message...)`
+pub(crate) fn assert_same_elements_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["left", "right"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [left, right] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} has the same elements as {}",
+        printable_expr_string(&left),
+        printable_expr_string(&right)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::same_elements_failure(#left, #right)
+        {
+            #panic
+        }
+    })
+}
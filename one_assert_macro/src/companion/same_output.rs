@@ -0,0 +1,24 @@
+use super::*;
+
+/// `assert_same_output!(f, g, inputs, message...)`
+pub(crate) fn assert_same_output_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["f", "g", "inputs"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [f, g, inputs] = <[syn::Expr; 3]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} and {} produce the same output for {}",
+        printable_expr_string(&f),
+        printable_expr_string(&g),
+        printable_expr_string(&inputs)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::first_divergence(#inputs, #f, #g)
+        {
+            #panic
+        }
+    })
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+/// `should_panic_with!(expr, expected_message, message...)`
+pub(crate) fn should_panic_with_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["expr", "expected_message"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [expr, expected] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} panics with {}",
+        printable_expr_string(&expr),
+        printable_expr_string(&expected)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    // `expr` is placed in statement position, so that `assert!` and other statement-like macros work
+    Ok(quote! {
+        {
+            let __one_assert_result = ::std::panic::catch_unwind(
+                ::std::panic::AssertUnwindSafe(|| { #expr; })
+            );
+            let __one_assert_expected = &(#expected);
+            if let ::std::option::Option::Some(__one_assert_details) =
+                ::one_assert::__macro_utilities::check_panic_message(
+                    __one_assert_result,
+                    ::std::convert::AsRef::<::std::primitive::str>::as_ref(__one_assert_expected),
+                )
+            {
+                #panic
+            }
+        }
+    })
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+/// The `max_distance` of `assert_similar!` if none is given
+const DEFAULT_MAX_DISTANCE: usize = 2;
+
+/// `assert_similar!(left, right, max_distance = N, message...)`, where `max_distance` is optional
+pub(crate) fn assert_similar_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["left", "right"]);
+    let (exprs, rest) = syn::parse::Parser::parse2(parser, input)?;
+    let [left, right] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let parser = |input: syn::parse::ParseStream| parse_named_params(input, &["max_distance"]);
+    let (mut params, format) = syn::parse::Parser::parse2(parser, rest)?;
+    let (max_distance, max_distance_str) = match params.pop() {
+        Some((_, value)) => {
+            let value_str = printable_expr_string(&value);
+            (value.to_token_stream(), value_str)
+        }
+        None => (
+            quote! { #DEFAULT_MAX_DISTANCE },
+            DEFAULT_MAX_DISTANCE.to_string(),
+        ),
+    };
+
+    let header = format!(
+        "{} is within edit distance {} of {}",
+        printable_expr_string(&left),
+        max_distance_str,
+        printable_expr_string(&right)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::similar_failure(&#left, &#right, #max_distance)
+        {
+            #panic
+        }
+    })
+}
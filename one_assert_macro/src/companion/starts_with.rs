@@ -0,0 +1,23 @@
+use super::*;
+
+/// `assert_starts_with!(haystack, prefix, message...)`
+pub(crate) fn assert_starts_with_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["haystack", "prefix"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [haystack, prefix] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} starts with {}",
+        printable_expr_string(&haystack),
+        printable_expr_string(&prefix)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::starts_with_failure(&#haystack, &#prefix)
+        {
+            #panic
+        }
+    })
+}
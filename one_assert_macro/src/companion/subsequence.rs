@@ -0,0 +1,23 @@
+use super::*;
+
+/// `assert_subsequence!(haystack, needle, message...)`
+pub(crate) fn assert_subsequence_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["haystack", "needle"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [haystack, needle] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "{} contains the subsequence {}",
+        printable_expr_string(&haystack),
+        printable_expr_string(&needle)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::subsequence_failure(&#haystack, &#needle)
+        {
+            #panic
+        }
+    })
+}
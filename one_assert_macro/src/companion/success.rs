@@ -0,0 +1,18 @@
+use super::*;
+
+/// `assert_success!(command, message...)`
+pub(crate) fn assert_success_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["command"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [command] = <[syn::Expr; 1]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!("{} exits successfully", printable_expr_string(&command));
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    Ok(quote! {
+        match ::one_assert::__macro_utilities::command_success(#command) {
+            ::std::result::Result::Ok(__one_assert_output) => __one_assert_output,
+            ::std::result::Result::Err(__one_assert_details) => #panic,
+        }
+    })
+}
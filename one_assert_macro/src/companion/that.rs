@@ -0,0 +1,23 @@
+use super::*;
+
+/// `assert_that!(value, predicate, message...)`
+pub(crate) fn assert_that_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["value", "predicate"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [value, predicate] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let mut state = State::new(Options::default(), DisplayReplacements::default());
+    let header = format!(
+        "{} satisfies {}",
+        printable_expr_string(&value),
+        printable_expr_string(&predicate)
+    );
+    state.start(&header, &format);
+
+    let value = state.add_var(value, "value", "value");
+    // the predicate only borrows the value, so that it can be printed afterwards. Going through a function
+    // gives the closure an expected signature, which an immediately called closure wouldn't have
+    Ok(state.finish(quote! {
+        ::one_assert::__macro_utilities::apply_predicate(&#value, #predicate)
+    }))
+}
@@ -0,0 +1,120 @@
+use super::*;
+use std::collections::HashMap;
+use syn::visit_mut::VisitMut;
+
+/// `try_assert!(condition, options..., message...)`
+pub(crate) fn try_assert_internal(mut input: Args) -> Result<TokenStream> {
+    let mut counter = TryCounter(0);
+    counter.visit_expr_mut(&mut input.expr.clone());
+    let mut annotator = TryAnnotator {
+        assertion: utils::tidy_path_spacing(&input.expr.to_token_stream().to_string()),
+        replacements: DisplayReplacements::default(),
+        position: 0,
+        operators: counter.0,
+    };
+    annotator.visit_expr_mut(&mut input.expr);
+    input.options.return_error = true;
+    assert_internal(input, annotator.replacements)
+}
+
+/// Generated code in the expression, mapped to the original code that it replaced.
+///
+/// The generated code is stored as `syn::Expr::Verbatim`, so that it can be identified by its tokens.
+#[derive(Default)]
+pub(crate) struct DisplayReplacements(HashMap<String, syn::Expr>);
+
+impl DisplayReplacements {
+    /// Undo all replacements in `expr`
+    pub fn restore(&self, expr: &syn::Expr) -> syn::Expr {
+        let mut expr = expr.clone();
+        if !self.0.is_empty() {
+            let mut visitor = self;
+            visitor.visit_expr_mut(&mut expr);
+        }
+        expr
+    }
+
+    /// Checks if `tokens` are generated code that replaced part of the expression
+    pub fn contains(&self, tokens: &TokenStream) -> bool {
+        self.0.contains_key(&tokens.to_string())
+    }
+}
+
+impl VisitMut for &DisplayReplacements {
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        if let syn::Expr::Verbatim(tokens) = expr {
+            if let Some(original) = self.0.get(&tokens.to_string()) {
+                *expr = original.clone();
+                return;
+            }
+        }
+        syn::visit_mut::visit_expr_mut(self, expr);
+    }
+}
+
+/// Counts the `?`s in the condition that [`TryAnnotator`] rewrites
+struct TryCounter(usize);
+
+impl VisitMut for TryCounter {
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        match expr {
+            syn::Expr::Closure(_) | syn::Expr::Async(_) | syn::Expr::TryBlock(_) => {}
+            syn::Expr::Try(_) => {
+                self.0 += 1;
+                syn::visit_mut::visit_expr_mut(self, expr);
+            }
+            _ => syn::visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _: &mut syn::Item) {}
+}
+
+/// Rewrites every `expr?` in the condition to
+/// `Result::map_err(expr, |error| TryAssertError { ... })?`, so that a short-circuiting error carries
+/// the assertion it came from.
+struct TryAnnotator {
+    /// The entire condition, as written by the user
+    assertion: String,
+    /// The `map_err` calls that replaced the operands of the `?`s
+    replacements: DisplayReplacements,
+    /// Number of `?`s that were rewritten so far
+    position: usize,
+    /// Number of `?`s in the condition, see [`TryCounter`]
+    operators: usize,
+}
+
+impl VisitMut for TryAnnotator {
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        match expr {
+            // a `?` in these would return from the closure/block instead of the function
+            syn::Expr::Closure(_) | syn::Expr::Async(_) | syn::Expr::TryBlock(_) => {}
+            syn::Expr::Try(syn::ExprTry { expr: inner, .. }) => {
+                let original = (**inner).clone();
+                // `?`s in the operand come first in the source code, like the first one in `a?.b()?`
+                self.visit_expr_mut(inner);
+                self.position += 1;
+
+                let assertion = &self.assertion;
+                let expression = utils::tidy_path_spacing(&original.to_token_stream().to_string());
+                let (position, operators) = (self.position, self.operators);
+                let mapped = quote! {
+                    ::std::result::Result::map_err(#inner, |error| ::one_assert::TryAssertError {
+                        assertion: #assertion,
+                        expression: #expression,
+                        position: #position,
+                        operators: #operators,
+                        error,
+                    })
+                };
+                self.replacements.0.insert(mapped.to_string(), original);
+                **inner = syn::Expr::Verbatim(mapped);
+            }
+            _ => syn::visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _: &mut syn::Item) {
+        // nested items are not part of the assertion
+    }
+}
@@ -0,0 +1,32 @@
+use super::*;
+
+/// `assert_unchanged!(expression, observing = state, message...)`
+pub(crate) fn assert_unchanged_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["expression", "observing"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [expression, observing] =
+        <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+    let observing = named_arg(observing, "observing")?;
+
+    let mut state = State::new(Options::default(), DisplayReplacements::default());
+    let header = format!(
+        "{} leaves {} unchanged",
+        printable_expr_string(&expression),
+        printable_expr_string(&observing)
+    );
+    state.start(&header, &format);
+
+    // the state is always stored, even if it is a plain variable, since it has to be a snapshot
+    let snapshot = |state: &mut State, expr: syn::Expr, identifier: &str| {
+        let var = state.create_ident(identifier);
+        state.setup.extend(quote! { let #var = #expr; });
+        var.into_token_stream()
+    };
+    let before = state.add_var_bound_with(observing.clone(), "before", "before", snapshot, false);
+    state.setup.extend(quote! { let _ = #expression; });
+    let after = state.add_var_bound_with(observing, "after", "after", snapshot, false);
+    state.add_option_diff(&before, &after, ("before", "after"));
+    state.add_map_diff(&before, &after, ("before", "after"));
+
+    Ok(state.finish(quote! { #before == #after }))
+}
@@ -0,0 +1,28 @@
+use super::*;
+
+/// `assert_within_steps!(max, block, message...)`
+pub(crate) fn assert_within_steps_internal(input: TokenStream) -> Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| parse_exprs(input, &["max", "block"]);
+    let (exprs, format) = syn::parse::Parser::parse2(parser, input)?;
+    let [max, block] = <[syn::Expr; 2]>::try_from(exprs).unwrap_or_else(|_| unreachable!());
+
+    let header = format!(
+        "computation takes at most {} steps",
+        printable_expr_string(&max)
+    );
+    let panic = panic_call(&header, &format, quote! { __one_assert_details });
+
+    // the counter is declared with the call site span, so that the code in the block can access it
+    let counter = syn::Ident::new("one_assert_steps", Span::call_site());
+    Ok(quote! {{
+        #[allow(unused_mut)]
+        let mut #counter: usize = 0;
+        let __one_assert_result = #block;
+        if let ::std::option::Option::Some(__one_assert_details) =
+            ::one_assert::__macro_utilities::steps_failure(#counter, #max)
+        {
+            #panic
+        }
+        __one_assert_result
+    }})
+}
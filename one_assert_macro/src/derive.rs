@@ -0,0 +1,111 @@
+//! Derive macros for the traits that are used by some of the options
+
+use crate::*;
+
+/// Integer types that are valid in a `#[repr(...)]` attribute of an enum
+const REPR_TYPES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// `#[derive(OneAssertDiscriminant)]`
+pub(crate) fn discriminant_internal(input: syn::DeriveInput) -> Result<TokenStream> {
+    let syn::Data::Enum(data) = &input.data else {
+        let msg = "OneAssertDiscriminant can only be derived for enums";
+        return Error::err_spanned(&input.ident, msg); // checked in tests/fail/derive.rs
+    };
+
+    let mut errors = Error::builder();
+    let mut arms = vec![];
+    let repr = discriminant_type(&input.attrs)?;
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            let msg = "OneAssertDiscriminant can only be derived for enums without fields";
+            errors.with_spanned(&variant.fields, msg); // checked in tests/fail/derive.rs
+            continue;
+        }
+        let name = &variant.ident;
+        arms.push(quote! { Self::#name => Self::#name as #repr });
+    }
+    errors.ok_or_build()?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::one_assert::OneAssertDiscriminant for #ident #ty_generics #where_clause {
+            type Discriminant = #repr;
+            fn discriminant_value(&self) -> #repr {
+                match *self {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
+
+/// The integer type from the `#[repr(...)]` attribute, or `isize` (the default of the compiler)
+fn discriminant_type(attrs: &[syn::Attribute]) -> Result<syn::Ident> {
+    let mut repr = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if REPR_TYPES.contains(&ident.to_string().as_str()) {
+                    repr = Some(ident.clone());
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(repr.unwrap_or_else(|| syn::Ident::new("isize", Span::call_site())))
+}
+
+/// `#[derive(OneAssertFields)]`
+pub(crate) fn fields_internal(input: syn::DeriveInput) -> Result<TokenStream> {
+    let syn::Data::Struct(data) = &input.data else {
+        let msg = "OneAssertFields can only be derived for structs";
+        return Error::err_spanned(&input.ident, msg); // checked in tests/fail/derive.rs
+    };
+
+    let mut names = vec![];
+    let mut comparisons = vec![];
+    for (i, field) in data.fields.iter().enumerate() {
+        let (name, member) = match &field.ident {
+            Some(ident) => (ident.to_string(), syn::Member::Named(ident.clone())),
+            None => (i.to_string(), syn::Member::Unnamed(i.into())),
+        };
+        comparisons.push(quote! {
+            if !ignored.contains(&#name) && self.#member != other.#member {
+                differences.push((
+                    #name,
+                    ::std::format!("{:?}", self.#member),
+                    ::std::format!("{:?}", other.#member),
+                ));
+            }
+        });
+        names.push(name);
+    }
+
+    // the fields of generic types can only be compared if the parameters can be
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::std::cmp::PartialEq));
+        param.bounds.push(syn::parse_quote!(::std::fmt::Debug));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::one_assert::OneAssertFields for #ident #ty_generics #where_clause {
+            const FIELD_NAMES: &'static [&'static str] = &[#(#names),*];
+            #[allow(unused_mut, unused_variables)]
+            fn differing_fields(
+                &self,
+                other: &Self,
+                ignored: &[&str],
+            ) -> ::std::vec::Vec<(&'static str, ::std::string::String, ::std::string::String)> {
+                let mut differences = ::std::vec::Vec::new();
+                #(#comparisons)*
+                differences
+            }
+        }
+    })
+}
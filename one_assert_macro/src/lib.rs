@@ -0,0 +1,3966 @@
+#![deny(
+    missing_docs,
+    missing_debug_implementations,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_import_braces,
+    unused_qualifications,
+    rustdoc::broken_intra_doc_links,
+    rustdoc::private_intra_doc_links,
+    rustdoc::missing_crate_level_docs,
+    rustdoc::invalid_codeblock_attributes,
+    rustdoc::bare_urls
+)]
+
+//! Procedural macros for the [`one_assert`](https://docs.rs/one_assert) crate.
+//!
+//! This crate is not meant to be used directly. Use the `one_assert` crate instead.
+
+use proc_macro::TokenStream as TokenStream1;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use std::rc::Rc;
+
+mod companion;
+mod derive;
+mod error;
+mod options;
+mod utils;
+mod wording;
+
+use companion::DisplayReplacements;
+use error::*;
+use options::*;
+use utils::ValueFormat;
+
+/// Parsed arguments for the `assert` macro
+struct Args {
+    /// condition to evaluate
+    expr: syn::Expr,
+    /// optional keywords between the condition and the message
+    options: Options,
+    /// optional message to display if the condition is false
+    format: TokenStream,
+}
+
+impl syn::parse::Parse for Args {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            let msg = "missing condition to check";
+            return Err(syn::Error::new(Span::call_site(), msg)); // checked in tests/fail/missing_params.rs
+        }
+        let mut options = Options {
+            label: parse_group_label(input)?,
+            ..Options::default()
+        };
+        let span_source: TokenStream = input.fork().parse().unwrap(); // unwrap: parsing a TokenStream can't fail
+        let labeled = parse_labeled_comparison(input)?;
+        if labeled.is_some() {
+            options.operand_labels = Some(("actual", "expected"));
+        }
+        let expr = match labeled.map_or_else(|| input.parse(), Ok) {
+            Ok(expr) => expr,
+            Err(e) => {
+                let with_hint =
+                    |msg: String, tokens: &TokenStream| match utils::malformed_expr_hint(tokens) {
+                        Some(hint) => format!("{msg}. Hint: {hint}"),
+                        None => msg,
+                    };
+                let err = if input.is_empty() {
+                    // syn's error would use call_site instead of pointing at the broken expression
+                    let msg = with_hint(format!("incomplete expression: {}", e), &span_source);
+                    syn::Error::new_spanned(span_source, msg) // checked in tests/fail/malformed_expr.rs
+                } else if let Ok(comma) = input.parse::<syn::Token![,]>() {
+                    // syn's error would point at the ',' saying "expected an expression"
+                    let before_comma = span_source
+                        .into_iter()
+                        .take_while(|t| !matches!(t, proc_macro2::TokenTree::Punct(p) if p.as_char() == ','))
+                        .collect();
+                    let msg = format!("Expression before the comma is incomplete: {}", e);
+                    let msg = with_hint(msg, &before_comma);
+                    syn::Error::new_spanned(comma, msg) // checked in tests/fail/malformed_expr.rs
+                } else if let Some(hint) = utils::malformed_expr_hint(&span_source) {
+                    syn::Error::new(e.span(), format!("{e}. Hint: {hint}")) // checked in tests/fail/malformed_expr.rs
+                } else {
+                    e
+                };
+                return Err(err);
+            }
+        };
+
+        let format;
+        if input.is_empty() {
+            format = TokenStream::new();
+        } else if let Err(e) = input.parse::<syn::Token![,]>() {
+            let msg = "condition has to be followed by a comma, if a message is provided";
+            return Err(syn::Error::new(e.span(), msg)); // checked in tests/fail/malformed_parameters.rs
+        } else if options.operand_labels.is_none() && peek_comparison_label(input) {
+            let msg = "`actual = ...` and `expected = ...` replace the condition and can't be combined with one";
+            return Err(syn::Error::new(input.span(), msg)); // checked in tests/fail/malformed_parameters.rs
+        } else {
+            options.parse_all(input)?;
+            format = input.parse()?;
+            utils::check_format_args(&format)?;
+            if let Some(capture) = &options.capture {
+                let max_args = utils::max_call_args(&expr);
+                if let Some((position, span)) = capture.iter().find(|(i, _)| *i >= max_args) {
+                    let msg = match max_args {
+                        0 => format!("capture position {position} is out of range: the condition contains no calls with arguments"),
+                        1 => format!("capture position {position} is out of range: the calls in the condition only have 1 argument"),
+                        n => format!("capture position {position} is out of range: the calls in the condition have at most {n} arguments"),
+                    };
+                    return Err(syn::Error::new(*span, msg)); // checked in tests/fail/options.rs
+                }
+            }
+            if let Some(first) = options.captures.first() {
+                if !utils::contains_call(&expr) {
+                    let msg = "option `captures` needs a call in the condition, like `assert!(closure(), captures = [x])`";
+                    return Err(syn::Error::new_spanned(first, msg)); // checked in tests/fail/options.rs
+                }
+            }
+        }
+
+        Ok(Args {
+            expr,
+            options,
+            format,
+        })
+    }
+}
+
+/// Parse the optional `label:` in front of the condition. A single `:` after an identifier can't start an
+/// expression (unlike `path::to::item`, and type ascription is not part of the language), so this doesn't
+/// clash with any condition
+fn parse_group_label(input: syn::parse::ParseStream) -> syn::Result<Option<syn::Ident>> {
+    if !(input.peek(syn::Ident) && input.peek2(syn::Token![:]) && !input.peek2(syn::Token![::])) {
+        return Ok(None);
+    }
+    let label = input.parse::<syn::Ident>()?;
+    input.parse::<syn::Token![:]>()?;
+    if input.is_empty() {
+        let msg = format!("missing condition after the label `{label}:`");
+        return Err(syn::Error::new_spanned(label, msg)); // checked in tests/fail/missing_params.rs
+    }
+    Ok(Some(label))
+}
+
+/// Checks if the next tokens are `actual = ...` or `expected = ...`
+fn peek_comparison_label(input: syn::parse::ParseStream) -> bool {
+    let fork = input.fork();
+    matches!(fork.parse::<syn::Ident>(), Ok(name) if name == "actual" || name == "expected")
+        && fork.peek(syn::Token![=])
+        && !fork.peek(syn::Token![==])
+}
+
+/// Parse the `actual = ..., expected = ...` form of the `assert` macro (in any order) into the condition
+/// `actual == expected`. Returns `None` if the input doesn't start with it
+fn parse_labeled_comparison(input: syn::parse::ParseStream) -> syn::Result<Option<syn::Expr>> {
+    if !peek_comparison_label(input) {
+        return Ok(None);
+    }
+    let mut actual = None;
+    let mut expected = None;
+    while actual.is_none() || expected.is_none() {
+        let is_first = actual.is_none() && expected.is_none();
+        if !is_first && (input.parse::<syn::Token![,]>().is_err() || !peek_comparison_label(input))
+        {
+            let missing = if actual.is_none() {
+                "actual"
+            } else {
+                "expected"
+            };
+            let msg = format!("expected `{missing} = ...`, since `actual = ...` and `expected = ...` replace the condition and can't be combined with one");
+            let span = if input.is_empty() {
+                Span::call_site()
+            } else {
+                input.span()
+            };
+            return Err(syn::Error::new(span, msg)); // checked in tests/fail/malformed_parameters.rs
+        }
+        let name = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![=]>()?;
+        let value = input.parse::<syn::Expr>()?;
+        let slot = if name == "actual" {
+            &mut actual
+        } else {
+            &mut expected
+        };
+        if slot.is_some() {
+            let msg = format!("`{name}` was specified more than once");
+            return Err(syn::Error::new_spanned(name, msg)); // checked in tests/fail/malformed_parameters.rs
+        }
+        *slot = Some(value);
+    }
+
+    // `a || b` as one side has to stay a single operand of the `==`
+    let operand = |expr: syn::Expr| {
+        let needs_parens = match &expr {
+            syn::Expr::Binary(syn::ExprBinary { op, .. }) => {
+                is_comparison(op) || matches!(op, syn::BinOp::And(_) | syn::BinOp::Or(_))
+            }
+            syn::Expr::Assign(_) | syn::Expr::Range(_) | syn::Expr::Closure(_) => true,
+            _ => false,
+        };
+        Box::new(if needs_parens {
+            syn::parse_quote!((#expr))
+        } else {
+            expr
+        })
+    };
+    Ok(Some(syn::Expr::Binary(syn::ExprBinary {
+        attrs: vec![],
+        left: operand(actual.unwrap()),
+        op: syn::BinOp::Eq(Default::default()),
+        right: operand(expected.unwrap()),
+    })))
+}
+
+/// The main macro that is used to check a condition and panic if it is false.
+///
+/// # Syntax
+/// ```text
+/// assert!(condition: expression);
+/// assert!(condition: expression, message: format_string, args...: format_args);
+/// assert!(condition: expression, options..., message: format_string, args...: format_args);
+/// assert!(actual = expression, expected = expression, options..., message: format_string, args...: format_args);
+/// ```
+/// Parameters:
+/// - `condition`: The condition that should be checked. If it evaluates to `false`, the assertion fails.
+///   Can be any expression that evaluates to `bool`.
+///   A comparison of two integer or bool literals (like `1 > 2`) is evaluated at compile time: If it is
+///   always false, a compile error is generated, since that is most likely a mistake.
+/// - `options`: Optional keywords of the form `name` or `name = value` that change the behavior of the
+///   assertion. See [Options](#options) below.
+/// - `message`: An optional message that is displayed if the assertion fails. This message can contain `{}`
+///   placeholders for dynamic arguments. See [`format_args`] for more information.
+/// - `args`: Arguments that are only evaluated if the assertion fails. These arguments are passed to
+///   `format_args` to replace the `{}` placeholders in the message.
+///   A `{condition}` placeholder in the message is replaced with the code of the condition, as it appears in
+///   the first line: `assert!(a == b, "{condition} broke in round {}", i)`. Write `{{condition}}` for the
+///   literal text.
+/// - `label:`: An optional identifier with a colon in front of the condition, like `assert!(invariant: a == b)`.
+///   The label is added in front of the message, e.g. ``[invariant] assertion `a == b` failed``, to tell
+///   related assertions apart in a log.
+/// - `actual = ..., expected = ...`: Instead of a condition, the two sides of an equality check can be given
+///   separately (in any order). `assert!(actual = computed, expected = 42)` checks `computed == 42`, but
+///   prints the sides as `actual` and `expected` instead of `left` and `right`. Notes about the values
+///   (like the keys that differ between two maps) still call them left and right.
+///
+/// # Options
+/// - `auto_context`: Print every local variable that the condition reads, named after the variable, in
+///   addition to the usual values. Only plain names like `count` are captured, not paths like `config::MAX` or
+///   names that start with an uppercase letter, and not the variables inside of closures, blocks or `match`
+///   arms. The variables are printed as they were right before the condition was evaluated, and have to
+///   implement `Debug`.
+/// - `capture = [0, 2]`: Only print the arguments at these positions of function and method calls, e.g. to
+///   skip the uninteresting arguments of a call with many parameters. The other arguments are still evaluated in
+///   order. Receivers of method calls are always printed. A position that is out of range for every call in the
+///   condition is a compile error.
+/// - `captures = [x, y]`: Print the given variables next to the arguments of a function call, for closures
+///   whose result depends on the values they captured: `assert!(is_valid(), captures = [min, max])`. The macro
+///   can't see what a closure captures, so the variables have to be listed by hand. Only names and fields like
+///   `config.limit` are accepted, and they are printed with the values they have after the call.
+/// - `clean`: Shorten `PhantomData<...>` in the printed values to just `PhantomData`, which removes a lot
+///   of noise from the output for generic-heavy code. Strings and chars in the values are not modified.
+/// - `discriminant`: Print the numeric discriminant of enums next to the two sides of a comparison, e.g.
+///   `left: B (2)`. Only works for enums that derive [`OneAssertDiscriminant`](derive@OneAssertDiscriminant),
+///   other values are printed as usual.
+/// - `deref`: For captured values that are smart pointers, like `Box<Box<T>>`, `Rc<T>` or the `Ref` returned by
+///   `RefCell::borrow`, add a note with the innermost value, e.g. `**left is 5`. Up to three levels of `Deref`
+///   are followed, as far as the target implements `Debug`. The values are only borrowed for this.
+/// - `dedup`: Collapse consecutive values with the same output and numbered names into a single line, like
+///   `arg 2..9: 0 (×8)` instead of eight lines of `arg N: 0`. Values that differ from their neighbors are
+///   printed as usual.
+/// - `fingerprint`: Add a hash of both sides of `==` and `!=` comparisons, e.g. `left fingerprint: 0x1a2b...`,
+///   to quickly see if two large values are identical without reading their full `Debug` output. The hashes
+///   are computed with `DefaultHasher` when the assertion fails, and are left out if the values don't
+///   implement `Hash`.
+/// - `fmt_with = path::to::function`: Print the captured values with a custom function instead of `Debug`,
+///   e.g. to show timestamps in ISO-8601. The function is called as `function(&value)` and has to return a
+///   `String`, so it has to accept every captured value of the assertion (usually by being generic). To change
+///   the formatting of a single operand, put a `#[fmt_with(path::to::function)]` attribute on it instead, which
+///   also takes precedence over the option: `assert!(#[fmt_with(hex)] a == b)`. A `#[display]` attribute
+///   prints its operand with `Display` (`{}`) instead, e.g. `assert!(#[display] path == expected)`. Additional
+///   output like the `pretty_assertions` diff still uses `Debug`.
+/// - `layout = "pretty_assertions"`: Render failed `==` comparisons as a colored line-by-line diff of the
+///   pretty-printed (`{:#?}`) values, in the style of the [`pretty_assertions`](https://docs.rs/pretty_assertions)
+///   crate. All other assertions keep the default layout. `layout = "default"` selects the default layout.
+/// - `level = "critical"` / `level = "warning"`: Tag the assertion with a severity, which is added in front
+///   of the message, e.g. ``[warning] assertion `a == b` failed``. Critical assertions panic as usual, while
+///   failed warnings only print their message to stderr and let the program continue. Useful for invariant
+///   checks in production code, where not every violation should bring down the program.
+/// - `message_position = "cause"`: Print the message after the innermost "caused by" line, which belongs to
+///   the check that actually failed, instead of after the first line. Useful for nested `if`/`match` conditions,
+///   where the message describes one of the branches. `message_position = "header"` is the default.
+///   Each branch gets the message at its own innermost cause. Without any cause, the message stays on the first line.
+/// - `module`: Add the module path of the assertion to the first line of the message, e.g.
+///   ``assertion `a == b` failed [in my_crate::tests]``. Helps to tell apart failures in similarly named
+///   tests in CI logs.
+/// - `precision = N`: Print `f32` and `f64` values (and references to them) with `N` decimal places (`{:.N?}`)
+///   instead of their full precision. Values of other types, including collections of floats, are printed as usual.
+/// - `redact`: Replace the code of the assertion in the first line and the "caused by" lines with
+///   `<redacted>`, e.g. for checks on secrets whose expression reveals too much in shared CI logs.
+///   `redact = "values"` keeps the code, but prints every captured value as `<redacted>` and leaves out the
+///   notes and diffs that are made from the values. `redact = "all"` does both, and `redact = "expression"` is the
+///   same as `redact`. This also hides the code everywhere else in the message: in the names of the values
+///   (``condition `...` ``, `pair.0`, the names from `auto_context` or `captures`), in the notes and in a
+///   `{condition}` in the custom message. Generic names like `left` or `arg 0` are still printed.
+/// - `show_result`: Print the value that the condition itself evaluated to (`result: false`), before any `!`
+///   around it is applied. Useful for conditions with overloaded operators like `a + b` with a custom `Add`
+///   impl, whose output is otherwise not visible next to the printed operands. For `!(a + b)`, the result of
+///   `a + b` is printed.
+/// - `simplify_negation`: Display a negated comparison like `!(a == b)` as its opposite (`a != b`) in the
+///   message, instead of adding an "assertion negated" line. Only the message changes, the condition is
+///   still evaluated as written.
+/// - `spans`: Print the source location of every captured value next to its name, e.g. `arg 5 (col 34-41): 0`.
+///   Helps to find the right argument in calls with many arguments. Requires Rust 1.88 or newer, older
+///   compilers don't provide the locations, so the names are printed as usual.
+/// - `types`: Print the type of the receiver of method calls (`self type`). Useful if a method is provided
+///   by several traits and it is not clear which one was called. The trait itself can't be determined by
+///   the macro, but the type narrows it down.
+/// - `verbose`: Print every intermediate object of a field access chain like `config.server.tls.enabled`,
+///   named after its code (`config`, `config.server`, `config.server.tls`). Applies to the whole condition and
+///   to the operands of comparisons like `config.port.unwrap() == 80`. Method calls in the chain are
+///   supported and still only called once. If the chain calls `unwrap()` or `expect(...)` on a `None` or `Err`,
+///   the assertion fails with a note about it, instead of panicking inside of the call.
+/// - `warn_duplicates`: Emit a compiler warning for comparisons with the same code on both sides that calls
+///   something, like `compute() == compute()`. Both sides are evaluated separately, so any side effects happen
+///   twice. Since proc macros can't emit their own warnings, this shows up as the use of a deprecated function,
+///   and can be silenced with `#[allow(deprecated)]`.
+/// - `warn_unused`: Don't place `#[allow(unused)]` on the generated code. By default, all unused-warnings
+///   inside of the assertion are silenced, which also hides genuine warnings in the condition itself.
+///
+/// # Special Cases
+/// Some expressions get additional output beyond the values of their parts:
+/// - `a == b` where both sides are `Option`s: A note says if one side is `None` and the other is `Some`,
+///   or shows just the inner values if both are `Some`. Nested `Option`s are unwrapped as far as both sides
+///   are `Some`.
+/// - `a == b` where both sides are `Poll`s, and `poll.is_ready()` or `poll.is_pending()`: A note says if a
+///   `Poll` is `Pending` but `Ready(_)` was expected or the other way around, like `left is Pending, right is
+///   Ready(_)` or `self is Pending, expected Ready(_)`.
+/// - `a == b` where both sides are `HashMap`s or `BTreeMap`s: Notes list the keys that are only in one of
+///   the maps and the keys whose values differ, like `key "a": left=1 right=2`. See
+///   [`OneAssertMapLike`](https://docs.rs/one_assert/latest/one_assert/trait.OneAssertMapLike.html) for other maps.
+/// - `a == b` where both sides are arrays, slices or `Vec`s of `f32` or `f64`: Since `NaN` is never equal to
+///   itself, a collection with a `NaN` is never equal to anything, even if both sides print the same. A note
+///   flags every index where both sides are `NaN`, like `index 4: left=NaN right=NaN, NaN never equals itself`.
+/// - Tuple fields as operands of a binary operator, like `pair.0 == pair.1`: The operand is printed with its
+///   code as the name (`pair.0: 1`) instead of `left`/`right`.
+/// - `a && b && c` and `a || b || c`: Each operand is printed as `operand 1`, `operand 2`, etc. in source
+///   order. Comparisons print both of their sides instead, like `operand 1 left` and `operand 1 right` for
+///   `a == b && c != d`, and nested chains with the other operator or in parentheses are numbered further, like
+///   `operand 2.1` in `a || b && c`. A note says which operand of an `&&` chain was the first to be `false`, like
+///   `operand 2 of 3 (b < c) was false`, or that all operands of an `||` chain were `false`. Note that all
+///   operands are evaluated, see the limitations in the crate documentation.
+/// - Calls to `min`, `max` and `clamp`, like `std::cmp::max(a, b)` or `x.clamp(lo, hi)`: The inputs are printed
+///   before the result, named after their code, e.g. `a: 3` and `b: 7`. Literal inputs are skipped.
+/// - Chains of `Ordering`s like `a.cmp(&b).then(c.cmp(&d)).then_with(|| e.cmp(&f))`: Each tier is printed
+///   before the combined result, named after its code, e.g. `a.cmp(& b): Equal`. `then_with` closures are
+///   still only called if the previous tiers are `Equal`, and print as `<not evaluated>` otherwise.
+/// - `match` arms with a guard like `_ if y < limit`: If the assertion of the arm fails, the variables from
+///   outside of the arm that the guard compares (`y` and `limit`, and fields like `config.max`) are printed
+///   below the "caused by" line of the arm. Bindings of the pattern are left out.
+/// - `x.is_finite()`, `x.is_normal()`, `x.is_nan()` and `x.is_infinite()` on `f32` and `f64`: A note adds the
+///   category of the value, like `self is inf (infinite)` or `self is 1e-310 (subnormal)`.
+/// - `mutex.lock().unwrap()`, `rwlock.read().unwrap()` and `rwlock.write().unwrap()` (or `.expect(...)`): If the
+///   lock is poisoned, the assertion fails with a note like `` `mutex` is poisoned, so `lock().unwrap` would panic ``
+///   instead of panicking inside of `unwrap`. Otherwise, the guarded value is printed as usual.
+/// - `iter.all(|x| ...)` and `iter.any(|x| ...)`: The element that decided the result is printed along
+///   with its index. The elements have to be `Clone + Debug` for this, otherwise only the index is printed.
+///   The predicate is still only called once per element, by the original `all` or `any` method, so this also
+///   works for receivers that are not an `Iterator`, like a parallel iterator or a type with its own `all`.
+/// - `ptr.is_null()` and `ptr.is_aligned()` on raw pointers: The pointer is printed as its address (`self: 0x0`),
+///   it is never dereferenced. For `is_aligned`, a note adds the alignment that the type requires and the
+///   actual alignment of the address, like `required alignment: 4` and `actual alignment: 2`.
+/// - Operands that index into a `serde_json::Value`, like `value["key"] == 1`, and type predicates like
+///   `value["key"].is_string()`: Notes add the keys (or the length) of the indexed value and the actual type
+///   of the value, like `keys of value: ["name", "version"]` and `self is null, expected a string`. Only
+///   with the `serde_json` feature of `one_assert`.
+/// - Comparisons of two `Instant`s or two `SystemTime`s, like `end > start`: Their `Debug` output says little,
+///   so a note adds the time between them, like `end - start = 1.2s` (or `-1.2s` if `end` is earlier).
+/// - Syntax that isn't supported yet (mostly new or unstable language features): The condition is checked
+///   as-is and the message only contains the expression, along with a note that no values were captured.
+///
+/// # Examples
+/// See the crate-level documentation for examples.
+#[proc_macro]
+pub fn assert(input: TokenStream1) -> TokenStream1 {
+    let input = syn::parse_macro_input!(input as Args);
+    match assert_internal(input, DisplayReplacements::default()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a string contains a substring, or that a collection contains an element.
+///
+/// # Syntax
+/// ```text
+/// assert_contains!(haystack, needle);
+/// assert_contains!(haystack, needle, "message {}", args...);
+/// ```
+/// Both `haystack` and `needle` are borrowed, same as with `assert_eq!`, and have to implement `Debug`.
+///
+/// The check is done through the [`OneAssertContains`](https://docs.rs/one_assert/latest/one_assert/trait.OneAssertContains.html)
+/// trait, which is implemented for `str` and `String` (with `&str`, `String` and `char` needles) and for
+/// all collections that can be iterated by reference (`Vec`, slices, arrays, sets, ...).
+///
+/// On failure, the haystack (truncated if it is very long) and the needle are printed. For strings, the
+/// position in the haystack that is closest to the needle is printed as well.
+#[proc_macro]
+pub fn assert_contains(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_contains_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a `Result` is an error whose message, or the message of one of its sources, contains a substring.
+///
+/// # Syntax
+/// ```text
+/// assert_err_contains!(result, needle);
+/// assert_err_contains!(result, needle, "message {}", args...);
+/// ```
+/// `result` is borrowed and has to be a `Result<T, E>` where `E` implements [`std::error::Error`]. The error
+/// and every error in its [`source`](std::error::Error::source) chain are printed with `Display`, and the
+/// assertion passes if any of them contains `needle`, which can be any string type that implements
+/// [`OneAssertStrLike`](https://docs.rs/one_assert/latest/one_assert/trait.OneAssertStrLike.html). Useful to
+/// check the cause of an error without depending on how it is wrapped.
+///
+/// On failure, the whole chain is printed, or that the result is `Ok`:
+/// ```text
+/// assertion `load() is an error containing "not found"` failed
+///        error: failed to load the config
+///     source 1: failed to read "config.toml"
+///     source 2: permission denied
+/// ```
+#[proc_macro]
+pub fn assert_err_contains(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_err_contains_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a drop counter reached an exact number of drops.
+///
+/// # Syntax
+/// ```text
+/// assert_drop_count!(counter, expected);
+/// assert_drop_count!(counter, expected, "message {}", args...);
+/// ```
+/// A helper for tests of RAII types: `counter` is an [`AtomicUsize`](std::sync::atomic::AtomicUsize) (or
+/// anything that dereferences to one, like an `Arc<AtomicUsize>`) that a guard type increments in its `Drop`
+/// implementation, and `expected` is the number of drops that should have happened so far. The counter is
+/// borrowed and read with `SeqCst` ordering.
+///
+/// On failure, both counts are printed, along with how many drops are missing or too many:
+/// ```text
+/// assertion `DROPS counted 3 drops` failed
+///        dropped: 2
+///       expected: 3
+///     difference: 1 missing
+/// ```
+#[proc_macro]
+pub fn assert_drop_count(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_drop_count_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that an iterator yields an exact number of items.
+///
+/// # Syntax
+/// ```text
+/// assert_count!(iter, expected);
+/// assert_count!(iter, expected, "message {}", args...);
+/// ```
+/// `iter` is anything that implements [`IntoIterator`], and `expected` is the number of items it should
+/// yield. Unlike comparing `len()`, this also works for lazy iterators without
+/// [`ExactSizeIterator`], like `filter` or a stream of messages.
+///
+/// **Note:** The iterator is consumed to count its items, even if the assertion passes. Pass `iter.by_ref()`
+/// or `&collection` to keep using it afterwards. An infinite iterator never finishes counting.
+///
+/// On failure, both counts are printed, along with how many items are missing or too many:
+/// ```text
+/// assertion `lines.filter(| l | l.is_empty()) yields 3 items` failed
+///        counted: 5
+///       expected: 3
+///     difference: 2 too many
+/// ```
+#[proc_macro]
+pub fn assert_count(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_count_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that an iterator or channel has no more elements.
+///
+/// # Syntax
+/// ```text
+/// assert_empty!(source);
+/// assert_empty!(source, "message {}", args...);
+/// ```
+/// `source` can be any iterator, or a type that implements
+/// [`OneAssertExhaustible`](https://docs.rs/one_assert/latest/one_assert/trait.OneAssertExhaustible.html),
+/// like the `Receiver` of a `std::sync::mpsc` channel (checked with `try_recv`, so it never blocks).
+/// `source` is taken by value, so pass `&mut iter` or `&receiver` to keep using it afterwards.
+///
+/// **Note:** The check takes the next element from the source. If the assertion fails, that element is
+/// consumed and printed in the message. The elements have to implement `Debug`.
+/// ```text
+/// assertion `rx is empty` failed
+///     leftover: "unexpected message"
+/// ```
+#[proc_macro]
+pub fn assert_empty(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_empty_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two values are equal after normalizing both of them with the same function.
+///
+/// # Syntax
+/// ```text
+/// assert_eq_by!(left, right, normalize);
+/// assert_eq_by!(left, right, normalize, "message {}", args...);
+/// ```
+/// Useful to compare values up to some canonical form, like text with different whitespace or casing, or
+/// collections in a different order. `normalize` is usually a closure like `|s| s.trim().to_lowercase()`, and is
+/// called exactly once with a reference to each side, so both sides have to be of the same type. The sides
+/// have to implement `Debug`, and the results of `normalize` have to implement `PartialEq + Debug`.
+///
+/// On failure, both the original and the normalized values are printed:
+/// ```text
+/// assertion `a equals b after | s | s.trim().to_lowercase()` failed
+///                 left: "  Hello World"
+///                right: "hello  world"
+///      normalized left: "hello world"
+///     normalized right: "hello  world"
+/// ```
+#[proc_macro]
+pub fn assert_eq_by(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_eq_by_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two structs are equal, except for some fields that are skipped in the comparison.
+///
+/// # Syntax
+/// ```text
+/// assert_eq_ignoring!(left, right, [fields...]);
+/// assert_eq_ignoring!(left, right, [fields...], "message {}", args...);
+/// ```
+/// Useful for values with fields that are different in every run, like timestamps or generated ids. Both
+/// sides have to be of the same type, which has to derive
+/// [`OneAssertFields`](derive@OneAssertFields) to allow comparing it field by field. The remaining fields
+/// are compared with `==` and have to implement `Debug`. The fields of a tuple struct are named by their
+/// index, e.g. `[0, 2]`. Both sides are borrowed, same as with `assert_eq!`.
+///
+/// On failure, every compared field that differs is printed. Naming a field that doesn't exist also fails,
+/// since the field would never be ignored.
+/// ```text
+/// assertion `a == b ignoring timestamp, id` failed
+///      left.name: "start"
+///     right.name: "stop"
+/// ```
+#[proc_macro]
+pub fn assert_eq_ignoring(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_eq_ignoring_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two structs are equal, comparing them field by field.
+///
+/// # Syntax
+/// ```text
+/// assert_fields_eq!(left, right);
+/// assert_fields_eq!(left, right, "message {}", args...);
+/// ```
+/// Same as [`assert_eq_ignoring!`] without any ignored fields: Both sides have to be of the same type, which
+/// has to derive [`OneAssertFields`](derive@OneAssertFields), and are borrowed.
+///
+/// Unlike `assert!(a == b)`, which only prints both values as a whole, the message lists every field that
+/// differs, which makes the difference easy to spot in structs with many fields:
+/// ```text
+/// assertion `a == b field by field` failed
+///      left.name: "start"
+///     right.name: "stop"
+///       left.id: 1
+///      right.id: 3
+/// ```
+#[proc_macro]
+pub fn assert_fields_eq(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_fields_eq_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that an invariant holds both before and after running a block of code.
+///
+/// # Syntax
+/// ```text
+/// assert_maintains!(invariant, { body });
+/// assert_maintains!(invariant, { body }, "message {}", args...);
+/// ```
+/// `invariant` is a condition like the one of [`assert!`]. It is evaluated before `body`, then `body` is run,
+/// and then `invariant` is evaluated again. The macro evaluates to the value of `body`.
+///
+/// Since `invariant` is evaluated twice, it has to be something that can be re-evaluated, like a check of
+/// some state: `v.is_sorted()` works, `iter.next().is_some()` would check two different elements. The state
+/// can't be borrowed mutably by `invariant`, because `body` has to modify it in between.
+///
+/// Both checkpoints are analyzed the same way as [`assert!`]. An invariant that doesn't hold in the first
+/// place is a failure as well, before `body` is run. The header of the message names the checkpoint:
+/// ```text
+/// assertion `queue.len() <= capacity holds after { queue.push(4) }` failed
+///      left: 4
+///     right: 3
+/// ```
+#[proc_macro]
+pub fn assert_maintains(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_maintains_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a piece of code reaches a list of steps in a certain order.
+///
+/// # Syntax
+/// ```text
+/// assert_order!(|step| { code }, [steps...]);
+/// assert_order!(|step| { code }, [steps...], "message {}", args...);
+/// ```
+/// The code is a closure that gets a `&dyn Fn(T)` to mark the steps it reaches, like `step("parsed")`. The
+/// macro only sees what is marked: Every side effect whose order should be checked has to call `step`, e.g.
+/// by passing it to callbacks or hooks that are called by the code under test. Each call is recorded in the
+/// order it happens, and the recorded steps have to be exactly the same as `steps` (anything that can be
+/// borrowed as a slice of `T`, where `T: PartialEq + Debug`). The closure is run exactly once, and the macro
+/// evaluates to its return value.
+/// ```ignore
+/// one_assert::assert_order!(|step| {
+///     let mut server = Server::new();
+///     server.on_start(|| step("start"));
+///     server.on_stop(|| step("stop"));
+///     server.run();
+/// }, ["start", "stop"]);
+/// ```
+///
+/// On failure, both lists of steps are printed, along with the first position where they differ:
+/// ```text
+/// assertion `| step | { ... } reaches the steps ["start", "stop"] in order` failed
+///             expected: ["start", "stop"]
+///               actual: ["stop", "start"]
+///     first difference: step 0: expected "start", found "stop"
+/// ```
+#[proc_macro]
+pub fn assert_order(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_order_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that each element of a sequence is greater (or less) than the previous one.
+///
+/// # Syntax
+/// ```text
+/// assert_monotonic!(sequence);
+/// assert_monotonic!(sequence, strict = true, direction = "decreasing");
+/// assert_monotonic!(sequence, strict = true, "message {}", args...);
+/// ```
+/// `sequence` can be anything that implements `IntoIterator`, with elements that implement `PartialOrd` and
+/// `Debug`. It is taken by value, so pass `&vec` to keep using a collection afterwards.
+///
+/// Parameters (both optional, in any order):
+/// - `direction = "increasing"` (default) or `direction = "decreasing"`
+/// - `strict = false` (default) allows equal neighbors (`<=`), `strict = true` doesn't (`<`)
+///
+/// Unlike a check for sorted order, the message names the first pair of neighbors that violates the
+/// relationship, along with their indices:
+/// ```text
+/// assertion `readings is strictly increasing` failed
+///   caused by: element 2 < element 3 is false
+///       element 2: 5
+///       element 3: 5
+/// ```
+#[proc_macro]
+pub fn assert_monotonic(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_monotonic_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a value is equal to one of a list of allowed values.
+///
+/// # Syntax
+/// ```text
+/// assert_one_of!(value, [allowed...]);
+/// assert_one_of!(value, [allowed...], "message {}", args...);
+/// ```
+/// The allowed values have to be written as a list in brackets, but don't have to be of the same type. `value`
+/// is compared to each of them with `==` until one matches, so `value` has to implement `PartialEq` for all of
+/// them. Everything is borrowed, same as with `assert_eq!`, and has to implement `Debug`.
+///
+/// On failure, the value and all allowed values are printed:
+/// ```text
+/// assertion `x is one of 1, 2, or 3` failed
+///       value: 5
+///     allowed: [1, 2, 3]
+/// ```
+#[proc_macro]
+pub fn assert_one_of(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_one_of_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that repeatedly applying a step function to a state reaches a condition within a number of steps.
+///
+/// # Syntax
+/// ```text
+/// assert_converges!(step, initial, max_steps = N, done = predicate);
+/// assert_converges!(step, initial, max_steps = N, done = predicate, "message {}", args...);
+/// ```
+/// Starting with `initial`, the state is checked with `done` (which gets a reference to the state and returns
+/// a `bool`), and replaced with `step(state)` as long as `done` returns `false`. `step` is called at most
+/// `max_steps` times, so the assertion fails if `done` is still `false` after that many steps. The state has
+/// to implement `Debug`.
+///
+/// On failure, the number of steps and the last state are printed:
+/// ```text
+/// assertion `| x | x / 2 reaches | x | * x == 1 from 100 within 3 steps` failed
+///          steps: 3
+///     last state: 12
+/// ```
+#[proc_macro]
+pub fn assert_converges(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_converges_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a computation stays within a budget of steps that it counts itself.
+///
+/// # Syntax
+/// ```text
+/// assert_within_steps!(max, { ... });
+/// assert_within_steps!(max, { ... }, "message {}", args...);
+/// ```
+/// The block is evaluated once, with a `usize` counter named `one_assert_steps` in scope that starts at `0`.
+/// The code in the block counts its own steps by incrementing it, like `one_assert_steps += 1;` in the body of
+/// a loop. Only these explicit increments are counted, the macro doesn't measure anything by itself. The
+/// assertion fails if the counter ends up larger than `max`, and otherwise evaluates to the value of the block.
+/// A variable named `one_assert_steps` from outside of the block is shadowed by the counter.
+///
+/// On failure, the counted steps are printed along with the budget:
+/// ```text
+/// assertion `computation takes at most 100 steps` failed
+///     steps: 128
+///       max: 100
+/// ```
+#[proc_macro]
+pub fn assert_within_steps(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_within_steps_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a future completes on its first poll, and returns its output.
+///
+/// # Syntax
+/// ```text
+/// assert_ready!(future);
+/// assert_ready!(future, "message {}", args...);
+/// ```
+/// `future` is polled exactly once, with a waker that does nothing. This is meant for futures that should
+/// complete without waiting for anything, like an `async fn` that only awaits values that are already
+/// available, and saves setting up an executor in a test. The output of the future is returned, so it can be
+/// checked further: `assert!(assert_ready!(load()) == 42)`.
+///
+/// If the future returns `Pending`, the assertion fails:
+/// ```text
+/// assertion `load() is ready` failed
+///     poll: Pending
+///   the future did not complete synchronously
+/// ```
+#[proc_macro]
+pub fn assert_ready(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_ready_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two collections contain the same elements the same number of times, in any order.
+///
+/// # Syntax
+/// ```text
+/// assert_same_elements!(left, right);
+/// assert_same_elements!(left, right, "message {}", args...);
+/// ```
+/// `left` and `right` can be anything that implements `IntoIterator`, like `&vec`, `&[1, 2, 3]` or an iterator,
+/// and have to produce the same type of elements, which has to implement `Eq + Hash + Debug`. Unlike comparing
+/// two sets, each element has to occur as often in `left` as in `right`, so `[1, 1, 2]` and `[1, 2, 2]` are not
+/// the same.
+///
+/// On failure, every element that occurs a different number of times is printed with its counts, in the order
+/// in which the elements first occurred. `right` is taken as the expected side:
+/// ```text
+/// assertion `& names has the same elements as & ["a", "b"]` failed
+///     "a": expected 1, found 2
+///     "c": expected 0, found 1
+///     "b": expected 1, found 0
+/// ```
+#[proc_macro]
+pub fn assert_same_elements(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_same_elements_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two functions produce the same output for each of a list of inputs.
+///
+/// # Syntax
+/// ```text
+/// assert_same_output!(f, g, inputs);
+/// assert_same_output!(f, g, inputs, "message {}", args...);
+/// ```
+/// `inputs` can be anything that implements `IntoIterator`, like an array `[a, b, c]` or a range `0..100`,
+/// and is evaluated once. `f` and `g` are called once per input with a reference to the input, until their
+/// outputs differ. The inputs have to implement `Debug`, and the outputs `PartialEq + Debug`.
+///
+/// On failure, the first input where the outputs differ and both outputs are printed:
+/// ```text
+/// assertion `| x | x * 2 and | x | x + x + 1 produce the same output for [1, 2, 3]` failed
+///     index: 0
+///     input: 1
+///      left: 2
+///     right: 3
+/// ```
+#[proc_macro]
+pub fn assert_same_output(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_same_output_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two strings differ in at most a few characters.
+///
+/// # Syntax
+/// ```text
+/// assert_similar!(left, right);
+/// assert_similar!(left, right, max_distance = N);
+/// assert_similar!(left, right, max_distance = N, "message {}", args...);
+/// ```
+/// The strings are compared by their [Levenshtein distance](https://en.wikipedia.org/wiki/Levenshtein_distance),
+/// which is the number of characters that have to be inserted, removed or replaced to turn one into the other.
+/// The assertion fails if the distance is larger than `max_distance`, which is 2 if it is not given. Useful
+/// for tests that should tolerate small formatting differences.
+///
+/// Both sides are borrowed and have to implement
+/// [`OneAssertStrLike`](https://docs.rs/one_assert/latest/one_assert/trait.OneAssertStrLike.html), which
+/// covers `str`, `String` and the common string wrappers. Computing the distance takes `O(n * m)` time for
+/// strings with `n` and `m` characters, so avoid it for very long texts.
+///
+/// On failure, both strings and the distance are printed:
+/// ```text
+/// assertion `a is within edit distance 1 of b` failed
+///             left: "kitten"
+///            right: "sitting"
+///         distance: 3
+///     max distance: 1
+/// ```
+#[proc_macro]
+pub fn assert_similar(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_similar_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two slices of numbers are equal within a tolerance, element by element.
+///
+/// # Syntax
+/// ```text
+/// assert_all_close!(left, right, tolerance);
+/// assert_all_close!(left, right, tolerance, "message {}", args...);
+/// ```
+/// Both sides are borrowed and can be anything that can be viewed as a slice (`AsRef<[T]>`), like arrays and
+/// `Vec`s. The elements have to be `Copy + PartialOrd + Sub + Debug`, which covers all the primitive number
+/// types, and `tolerance` has to be of the same type. Each pair of elements with the same index has to
+/// satisfy `|left[i] - right[i]| <= tolerance`, and both slices have to have the same length. `NaN` is never
+/// within the tolerance. Useful for numerical code, where results are rarely exactly equal to the expected values.
+///
+/// On failure, every index that exceeds the tolerance is printed with both elements and their difference:
+/// ```text
+/// assertion `signal is element-wise within 0.1 of expected` failed
+///     tolerance: 0.1
+///           [1]: left=2.5 right=2.0 diff=0.5
+///           [3]: left=NaN right=4.0 diff=NaN
+/// ```
+/// If the lengths differ, they are printed as well, and only the elements that exist in both slices are compared.
+#[proc_macro]
+pub fn assert_all_close(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_all_close_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two matrices of numbers are equal within a tolerance, element by element.
+///
+/// # Syntax
+/// ```text
+/// assert_matrix_close!(left, right, tolerance);
+/// assert_matrix_close!(left, right, tolerance, "message {}", args...);
+/// ```
+/// Same as [`assert_all_close!`], but for matrices that are stored as a list of rows, like `Vec<Vec<f64>>` or
+/// `[[f64; 3]; 2]`. Both sides are borrowed, and both the matrix and its rows have to be viewable as slices
+/// (`AsRef<[T]>`). Flat matrices can be compared with [`assert_all_close!`] instead.
+///
+/// On failure, every element that exceeds the tolerance is printed with its `[row, column]`:
+/// ```text
+/// assertion `rotated is element-wise within 0.001 of expected` failed
+///     tolerance: 0.001
+///        [0, 1]: left=-0.5 right=-1.0 diff=0.5
+///        [1, 0]: left=0.5 right=1.0 diff=0.5
+/// ```
+/// If the dimensions differ, they are printed before the elements: the number of rows if that differs, and the
+/// length of every row that has a different length on the other side. Only the elements that exist in both
+/// matrices are compared.
+#[proc_macro]
+pub fn assert_matrix_close(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_matrix_close_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a string matches a simple glob pattern with `*` and `?` wildcards.
+///
+/// # Syntax
+/// ```text
+/// assert_matches_glob!(string, pattern);
+/// assert_matches_glob!(string, pattern, "message {}", args...);
+/// ```
+/// A lightweight alternative to a regex for paths and file names: `*` matches any number of characters
+/// (including none, and including `/`), `?` matches exactly one character, and every other character matches
+/// itself. There is no way to escape the wildcards. The whole string has to match, not just a part of it.
+///
+/// Both sides are borrowed and have to implement
+/// [`OneAssertStrLike`](https://docs.rs/one_assert/latest/one_assert/trait.OneAssertStrLike.html), like
+/// `str` and `String`.
+///
+/// On failure, the string and the pattern are printed, along with the longest part of the pattern that could be
+/// matched and the remaining parts where the matching stopped:
+/// ```text
+/// assertion `path matches the glob "src/*.rs"` failed
+///       string: "src/lib.txt"
+///      pattern: "src/*.rs"
+///      matched: "src/lib." with "src/*."
+///     mismatch: "txt" against "rs"
+/// ```
+#[proc_macro]
+pub fn assert_matches_glob(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_matches_glob_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a slice starts with the elements of another slice.
+///
+/// # Syntax
+/// ```text
+/// assert_starts_with!(haystack, prefix);
+/// assert_starts_with!(haystack, prefix, "message {}", args...);
+/// ```
+/// Works like `starts_with` of `str`, but for anything that can be viewed as a slice (`AsRef<[T]>`), like
+/// arrays and `Vec`s. The elements are compared with `==`, so the elements of `haystack` have to implement
+/// `PartialEq` for the elements of `prefix`. Both sides are borrowed, and their elements have to implement
+/// `Debug`.
+///
+/// On failure, the index of the first difference and the elements around it are printed:
+/// ```text
+/// assertion `packet starts with header` failed
+///     first difference: index 2
+///       haystack[0..6]: [1, 2, 9, 4, 5, 6]
+///         prefix[0..3]: [1, 2, 3]
+/// ```
+#[proc_macro]
+pub fn assert_starts_with(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_starts_with_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that the elements of a slice appear in another slice in the same order, but not necessarily
+/// next to each other.
+///
+/// # Syntax
+/// ```text
+/// assert_subsequence!(haystack, needle);
+/// assert_subsequence!(haystack, needle, "message {}", args...);
+/// ```
+/// Useful to check that some events happened in a certain order, without caring about the events in between.
+/// The same types as with [`assert_starts_with!`] are accepted. Each element of `needle` is matched with the
+/// first fitting element of `haystack` after the previous match.
+///
+/// On failure, the message shows which elements were found and at which indices, the first element that
+/// was not found, and the part of `haystack` that was searched for it:
+/// ```text
+/// assertion `log contains the subsequence [Start, Write, Stop]` failed
+///             matched: 1 of 3 elements at indices [0]
+///     missing element: needle[1] = Write
+///       searched from: index 1
+///      haystack[1..3]: [Read, Stop]
+/// ```
+#[proc_macro]
+pub fn assert_subsequence(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_subsequence_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two files have the same contents. Requires the `fs` feature.
+///
+/// # Syntax
+/// ```text
+/// assert_files_eq!(left, right);
+/// assert_files_eq!(left, right, "message {}", args...);
+/// ```
+/// `left` and `right` can be anything that implements [`AsRef<Path>`](std::path::Path), like `&str`, `String` or
+/// `PathBuf`. Both files are read completely and compared byte by byte.
+///
+/// If one of the files can't be read, the message says so and shows the error, instead of reporting a
+/// difference in contents:
+/// ```text
+/// assertion `files "out.txt" and "expected.txt" have equal contents` failed
+///     left: couldn't read "out.txt": No such file or directory (os error 2)
+/// ```
+/// Otherwise, the message shows the sizes of both files, the offset of the first byte that differs, and the
+/// bytes around it:
+/// ```text
+/// assertion `files "out.txt" and "expected.txt" have equal contents` failed
+///            left size: 12 bytes
+///           right size: 12 bytes
+///     first difference: byte 6
+///          left[3..10]: b"lo worl"
+///         right[3..10]: b"lo Worl"
+/// ```
+#[proc_macro]
+pub fn assert_files_eq(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_files_eq_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a piece of code writes output that contains a substring. Requires the `output` feature.
+///
+/// # Syntax
+/// ```text
+/// assert_output_contains!(|out| { code }, expected);
+/// assert_output_contains!(|out| { code }, expected, "message {}", args...);
+/// ```
+/// The code is a closure that gets a `&mut Vec<u8>` to write its output into, which implements
+/// [`std::io::Write`], instead of writing to stdout or stderr. `expected` can be anything that implements
+/// `OneAssertStrLike`, like `&str` or `String`. The macro evaluates to the return value of the closure.
+/// ```ignore
+/// let status = one_assert::assert_output_contains!(|out| cli::run(&["--help"], out), "Usage:");
+/// ```
+/// Redirecting the real stdout and stderr of the process would affect every thread, so that the output of
+/// tests that run in parallel would end up in the wrong place. Because of that, only what is written into
+/// `out` is captured: Code that should be tested this way has to take the writer as a parameter, e.g. as
+/// `&mut dyn Write` or `impl Write`, and output of `println!` or from other threads is not captured, unless
+/// they are given the writer as well. Output that is not valid UTF-8 is converted lossily before searching.
+///
+/// On failure, the entire output is printed, without being shortened:
+/// ```text
+/// assertion `output of | out | greet("you", out) contains "world"` failed
+///     output: "Hello, you!\nBye\n"
+///     needle: "world"
+/// ```
+#[proc_macro]
+pub fn assert_output_contains(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_output_contains_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a process exits successfully. Requires the `process` feature.
+///
+/// # Syntax
+/// ```text
+/// assert_success!(command);
+/// assert_success!(command, "message {}", args...);
+/// ```
+/// `command` is a [`std::process::Command`], either by value or as the `&mut Command` returned by its builder
+/// methods. It is run exactly once with [`Command::output`](std::process::Command::output), which waits for it
+/// to finish and captures its stdout and stderr instead of passing them through. The macro evaluates to the
+/// captured [`Output`](std::process::Output), so that the output can be checked further:
+/// ```ignore
+/// let output = one_assert::assert_success!(Command::new("cargo").arg("--version"));
+/// one_assert::assert!(output.stdout.starts_with(b"cargo "));
+/// ```
+/// The assertion fails if the exit status is not zero, or if the command can't be started at all.
+///
+/// On failure, the exit code and the entire output are printed, without being shortened. Output that is not
+/// valid UTF-8 is converted lossily:
+/// ```text
+/// assertion `Command::new("git").arg("pull") exits successfully` failed
+///       command: "git" "pull"
+///     exit code: 1
+///        stdout: ""
+///        stderr: "fatal: not a git repository\n"
+/// ```
+#[proc_macro]
+pub fn assert_success(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_success_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that an expression panics with a specific message.
+///
+/// # Syntax
+/// ```text
+/// should_panic_with!(expr, expected_message);
+/// should_panic_with!(expr, expected_message, "message {}", args...);
+/// ```
+/// `expr` is run inside of [`catch_unwind`](std::panic::catch_unwind) and is usually a call to
+/// `one_assert::assert!` or a block containing one. `expected_message` can be anything that implements
+/// `AsRef<str>`, and has to be equal to the whole panic message. Both `&str` and `String` payloads are
+/// supported, which covers `panic!("literal")` and `panic!("{}", formatted)`.
+///
+/// The assertion fails if `expr` does not panic, or if the message does not match.
+///
+/// Note that the panic hook still runs for the caught panic, so the message is printed to stderr
+/// even if it matches.
+#[proc_macro]
+pub fn should_panic_with(input: TokenStream1) -> TokenStream1 {
+    match companion::should_panic_with_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that running an expression does not change some observed state.
+///
+/// # Syntax
+/// ```text
+/// assert_unchanged!(expression, observing = state);
+/// assert_unchanged!(expression, observing = state, "message {}", args...);
+/// ```
+/// `state` has to implement `PartialEq` and `Debug`. It is evaluated twice and stored each time, so it has
+/// to produce an owned value and should be cheap to evaluate, like `v.len()` or `config.clone()`. The order
+/// of evaluation is:
+/// 1. `state` is evaluated and stored as the "before" value, which is also printed right away
+/// 2. `expression` is evaluated exactly once, and its value is dropped
+/// 3. `state` is evaluated again and stored as the "after" value, which is printed as well
+/// 4. The two values are compared with `==`
+///
+/// If `expression` panics, the panic is not caught. Differences between `Option`s and maps get the same notes
+/// as with `assert!(a == b)`:
+/// ```text
+/// assertion `cache.lookup("a") leaves cache.len() unchanged` failed
+///     before: 2
+///      after: 3
+/// ```
+#[proc_macro]
+pub fn assert_unchanged(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_unchanged_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that a value satisfies a predicate, in the style of fluent assertion libraries.
+///
+/// # Syntax
+/// ```text
+/// assert_that!(value, predicate);
+/// assert_that!(value, predicate, "message {}", args...);
+/// ```
+/// `predicate` is called exactly once with a reference to `value`, and has to return a `bool`. It is usually
+/// a closure like `|v| v.is_empty()`, but can be any function that takes a `&T`. `value` has to implement `Debug`.
+///
+/// On failure, the source code of the predicate and the value are printed:
+/// ```text
+/// assertion `x satisfies | v | * v > 3` failed
+///     value: 2
+/// ```
+#[proc_macro]
+pub fn assert_that(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_that_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two values have the same hash.
+///
+/// # Syntax
+/// ```text
+/// assert_hash_eq!(left, right);
+/// assert_hash_eq!(left, right, "message {}", args...);
+/// ```
+/// Meant for tests of `Hash` implementations, which have to give equal values the same hash. Both values are
+/// borrowed and hashed with a [`DefaultHasher`](std::collections::hash_map::DefaultHasher) that always uses the
+/// same keys, and have to implement `Hash + Debug`. They don't have to be of the same type. See
+/// [`assert_hash_ne!`] for the opposite check.
+///
+/// On failure, both values and their hashes in hex are printed:
+/// ```text
+/// assertion `a hashes equal to b` failed
+///           left: Key { id: 1, name: "a" }
+///          right: Key { id: 1, name: "A" }
+///      left hash: 0x52f1a1dc8bb2b8e5
+///     right hash: 0x0d1bb51dc0d04e1b
+/// ```
+#[proc_macro]
+pub fn assert_hash_eq(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_hash_eq_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two values have different hashes.
+///
+/// # Syntax
+/// ```text
+/// assert_hash_ne!(left, right);
+/// assert_hash_ne!(left, right, "message {}", args...);
+/// ```
+/// The opposite of [`assert_hash_eq!`], with the same requirements and output. Different values are allowed to
+/// have the same hash, so this is mostly useful to check that a field is part of the hash at all.
+#[proc_macro]
+pub fn assert_hash_ne(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_hash_ne_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts that two floats have the exact same bit pattern.
+///
+/// # Syntax
+/// ```text
+/// assert_bits_eq!(left, right);
+/// assert_bits_eq!(left, right, "message {}", args...);
+/// ```
+/// Both sides have to be of the same type, either `f32` or `f64`. Unlike `==`, this distinguishes `-0.0` from
+/// `0.0`, and considers two `NaN`s equal only if their payloads are the same.
+///
+/// On failure, both values and their bit patterns in hex are printed, with a note if they only differ in the
+/// sign of zero or the payload of `NaN`:
+/// ```text
+/// assertion `x bitwise equals 0.0` failed
+///          left: -0.0
+///         right: 0.0
+///     left bits: 0x8000000000000000
+///    right bits: 0x0000000000000000
+///          note: sign of zero differs: -0.0 vs 0.0
+/// ```
+#[proc_macro]
+pub fn assert_bits_eq(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_bits_eq_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Expands to the code that [`assert!`] would generate for the same input, as a `&'static str`.
+///
+/// # Syntax
+/// Same as [`assert!`].
+///
+/// Meant for debugging surprising behavior of an assertion, or to see how it works:
+/// ```ignore
+/// let x = 1;
+/// println!("{}", one_assert::assert_expand!(x == 2));
+/// ```
+/// The condition is not evaluated, and nothing is checked. The tokens are printed with the spacing of the
+/// compiler's token printer, so the code is best read after running it through a formatter like `rustfmt`.
+///
+/// Invalid input produces the same errors as with [`assert!`]. Errors that are only detected by the
+/// compiler (like mismatched types) are not reported, since the code is never compiled.
+#[proc_macro]
+pub fn assert_expand(input: TokenStream1) -> TokenStream1 {
+    let input = syn::parse_macro_input!(input as Args);
+    match companion::assert_expand_internal(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts at compile time that a type implements some traits.
+///
+/// # Syntax
+/// ```text
+/// assert_impl!(Type: Trait + Trait...);
+/// ```
+/// Unlike the other macros, nothing is checked at runtime: An unsatisfied bound is a compile error that points
+/// at the type. Works both inside of functions and as an item, like a `const _` declaration. Bounds are written
+/// the same way as in a `where` clause, so traits with generics and lifetimes like `Iterator<Item = u8>`,
+/// `?Sized` or `'static` work as well. Unsized types like `str` are allowed.
+/// ```ignore
+/// one_assert::assert_impl!(Config: Clone + Send + Sync + 'static);
+/// one_assert::assert_impl!(Vec<u8>: Extend<u8> + AsRef<[u8]>);
+/// ```
+/// Since there is no value involved, there is no message either. The compiler's error names the missing trait:
+/// ```text
+/// error[E0277]: `Rc<i32>` cannot be sent between threads safely
+///  --> src/lib.rs:3:27
+///   |
+/// 3 | one_assert::assert_impl!(Rc<i32>: Send);
+///   |                          ^^^^^^^ `Rc<i32>` cannot be sent between threads safely
+/// ```
+#[proc_macro]
+pub fn assert_impl(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_impl_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts at compile time that a type has a certain size in bytes.
+///
+/// # Syntax
+/// ```text
+/// assert_size!(Type, size);
+/// ```
+/// Compares [`size_of::<Type>()`](std::mem::size_of) to `size`, which can be any constant expression of type
+/// `usize`. Useful for FFI and other layout-sensitive code, to notice when a change to a type changes its
+/// layout. Works both inside of functions and as an item, but the type can't use generic parameters of the
+/// surrounding code, since the check is a `const` item.
+///
+/// A different size is a compile error with both sizes:
+/// ```text
+/// error[E0308]: mismatched types
+///  --> src/lib.rs:3:26
+///   |
+/// 3 | one_assert::assert_size!(Header, 16);
+///   |                          ^^^^^^ expected an array with a size of 16, found one with a size of 24
+/// ```
+#[proc_macro]
+pub fn assert_size(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_size_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Asserts at compile time that a type has a certain alignment in bytes.
+///
+/// # Syntax
+/// ```text
+/// assert_align!(Type, alignment);
+/// ```
+/// Same as [`assert_size!`], but for [`align_of::<Type>()`](std::mem::align_of).
+#[proc_macro]
+pub fn assert_align(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_align_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Derives `OneAssertDiscriminant` for an enum without fields, for use with the `discriminant` option of [`assert!`].
+///
+/// The discriminant has the type from the `#[repr(...)]` attribute of the enum, or `isize` if there is none.
+/// ```ignore
+/// #[derive(Debug, PartialEq, one_assert::OneAssertDiscriminant)]
+/// #[repr(u8)]
+/// enum Tag { A = 1, B = 2 }
+/// ```
+#[proc_macro_derive(OneAssertDiscriminant)]
+pub fn derive_discriminant(input: TokenStream1) -> TokenStream1 {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match derive::discriminant_internal(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Derives `OneAssertFields` for a struct, for use with [`assert_eq_ignoring!`] and [`assert_fields_eq!`].
+///
+/// All fields have to implement `PartialEq` and `Debug`.
+/// ```ignore
+/// #[derive(Debug, one_assert::OneAssertFields)]
+/// struct Event { name: String, timestamp: u64 }
+/// ```
+#[proc_macro_derive(OneAssertFields)]
+pub fn derive_fields(input: TokenStream1) -> TokenStream1 {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match derive::fields_internal(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Same as [`assert!`], but checks a condition about a value and then evaluates to that value.
+///
+/// # Syntax
+/// ```text
+/// assert_returning!(name = value; condition);
+/// assert_returning!(name = value; condition, options...);
+/// assert_returning!(name = value; condition, options..., "message {}", args...);
+/// ```
+/// `value` is evaluated exactly once and bound to the variable `name`, then the rest of the parameters work
+/// the same way as the ones of [`assert!`], and the condition can use `name` to refer to the value. If the
+/// assertion passes, the macro evaluates to the value:
+/// ```ignore
+/// let count = one_assert::assert_returning!(n = compute(); n > 0);
+/// ```
+/// The value is bound by value, and the condition only borrows it as long as it doesn't move out of it
+/// explicitly. A condition that moves the value is a compile error, since there would be nothing to return.
+#[proc_macro]
+pub fn assert_returning(input: TokenStream1) -> TokenStream1 {
+    match companion::assert_returning_internal(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Same as [`assert!`], but a failed assertion is returned as an error instead of panicking, and errors from
+/// `?` operators in the condition are annotated with the assertion.
+///
+/// # Syntax
+/// Same as [`assert!`].
+///
+/// The macro evaluates to a `Result<(), OneAssertError>`, where the `Display` (and `Debug`) output of the
+/// error is the message that [`assert!`] would panic with. Use `?` to pass it on, which works with every
+/// error type that implements `From<OneAssertError>`, like `Box<dyn Error>`:
+/// ```ignore
+/// #[test]
+/// fn config_is_valid() -> Result<(), one_assert::OneAssertError> {
+///     let config = load_config();
+///     one_assert::try_assert!(config.retries > 0)?;
+///     Ok(())
+/// }
+/// ```
+/// The test harness then prints the full message of the failed assertion, the same as for a panic.
+/// With the `level = "warning"` option, the message is printed and the result is always `Ok`.
+///
+/// Every `expr?` in the condition is turned into
+/// ```ignore
+/// Result::map_err(expr, |error| TryAssertError { assertion: "<condition>", expression: "expr", position, operators, error })?
+/// ```
+/// so that an error that short-circuits the assertion says where it came from, instead of just being
+/// propagated as-is. In a condition with several `?`s like `parse(s)?.validate()?`, `position` says which of
+/// them failed (counting from 1 in the order of the source code), and the message contains it like ``(`?` 1 of 2)``. The surrounding function has to return a `Result` whose error type implements
+/// `From<TryAssertError<E>>`, like `Box<dyn Error>` (for `E: Error`).
+///
+/// `?` operators inside of closures and async blocks in the condition are not modified, since they don't
+/// return from the surrounding function.
+#[proc_macro]
+pub fn try_assert(input: TokenStream1) -> TokenStream1 {
+    let input = syn::parse_macro_input!(input as Args);
+    match companion::try_assert_internal(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into(),
+    }
+}
+
+#[derive(Clone)]
+enum ExprModifier {
+    /// `! expr`
+    Negated(syn::token::Not),
+    /// `( expr )`
+    Parenthesized(syn::token::Paren),
+    /// `{ expr }`
+    Blocked(syn::token::Brace),
+    /// `expr` inside of invisible delimiters, e.g. from a `$cond:expr` in a `macro_rules!` macro
+    Grouped(syn::token::Group),
+}
+
+/// A level of the failure message for a formatter set with `one_assert::set_formatter`: the top level, or a
+/// "caused by" line. Built alongside the format message, but with the strings unescaped
+#[derive(Clone, Default)]
+struct FailureLevel {
+    /// The text of the "caused by" line. Empty for the top level
+    cause: String,
+    /// Pairs of (variable name, printed value), same as in the format message
+    values: Vec<(String, TokenStream)>,
+    /// Expressions that evaluate to notes, same as in the format message
+    notes: Vec<TokenStream>,
+}
+
+struct State {
+    /// Code that sets up the variables for the assertion
+    setup: TokenStream,
+    /// The message that is displayed if the assertion fails. Must contain one `{}` for each dynamic argument
+    format_message: String,
+    /// Arguments that are only evaluated if the assertion fails
+    dynamic_args: Vec<TokenStream>,
+    /// Pairs of (variable name, debug-printed value) that are used in the assertion and should be printed in the error message
+    variables: Vec<(String, TokenStream)>,
+    /// Expressions that evaluate to additional lines (`String`s) that are printed after the variables. Empty strings are skipped
+    notes: Vec<TokenStream>,
+    /// Values that are only formatted when the assertion fails, as (identifier, `format_args!(...)`). Bound once
+    /// by [`State::report`] for both the default message and the formatter
+    deferred_values: Vec<(syn::Ident, TokenStream)>,
+    /// Number of "caused by" lines so far. See [`State::add_cause`] for the indentation scheme
+    depth: usize,
+    /// The expression (or header) of the assertion for the formatter, unescaped
+    header: String,
+    /// The parts of the format message for the formatter. Starts with the top level, then one entry per cause
+    levels: Vec<FailureLevel>,
+    /// The custom message of the assertion, if there is one. Referred to by [`message_ident`] in the format
+    /// message, because it is evaluated once for both the default message and the formatter
+    message_format: Option<TokenStream>,
+    /// The message of the assertion with `message_position = "cause"`. Inserted by [`State::finish`]
+    cause_message: Option<TokenStream>,
+    /// Where `cause_message` is inserted: (position in `format_message`, index in `dynamic_args`).
+    /// Moved to the end of every new "caused by" line
+    message_anchor: (usize, usize),
+    /// Contains `unsafe` if the assertion should be wrapped in an unsafe block
+    possibly_unsafe: TokenStream,
+    /// List of modifiers that need to be applied to the expression
+    modifiers: Vec<(Vec<syn::Attribute>, ExprModifier)>,
+    /// Counter for creating unique identifiers
+    next_ident_id: usize,
+    /// Set once the variables of the `captures` option were added, so that they are only printed once
+    captures_added: bool,
+    /// The options passed to the macro
+    options: Rc<Options>,
+    /// Generated code that replaced parts of the expression, mapped to the original expression.
+    /// Filled by `try_assert!`, which rewrites the `?` operators in the expression
+    display_replacements: Rc<DisplayReplacements>,
+}
+
+impl State {
+    fn new(options: Options, display_replacements: DisplayReplacements) -> Self {
+        Self {
+            setup: TokenStream::new(),
+            format_message: String::new(),
+            dynamic_args: vec![],
+            variables: vec![],
+            notes: vec![],
+            deferred_values: vec![],
+            depth: 0,
+            header: String::new(),
+            levels: vec![FailureLevel::default()],
+            message_format: None,
+            cause_message: None,
+            message_anchor: (0, 0),
+            possibly_unsafe: TokenStream::new(),
+            modifiers: vec![],
+            next_ident_id: 0,
+            captures_added: false,
+            options: Rc::new(options),
+            display_replacements: Rc::new(display_replacements),
+        }
+    }
+
+    /// Create a sub-state that can be used in branches
+    #[rustfmt::skip]
+    fn fork(&self) -> Self {
+        Self {
+            setup: TokenStream::new(),                               // initial setup is shared
+            format_message: self.format_message.clone(),             // format message is printed by fork
+            dynamic_args: self.dynamic_args.clone(),                 // args are tied to the format message
+            variables: self.variables.clone(),                       // keep any non-resolved variables
+            notes: self.notes.clone(),                               // notes belong to the variables
+            deferred_values: self.deferred_values.clone(),           // ^
+            depth: self.depth,                                       // branches continue at the same level
+            header: self.header.clone(),                             // same for the parts for the formatter
+            levels: self.levels.clone(),                             // ^
+            message_format: self.message_format.clone(),             // ^
+            cause_message: self.cause_message.clone(),               // every branch attaches the message to its own cause
+            message_anchor: self.message_anchor,                     // tied to the format message
+            possibly_unsafe: TokenStream::new(),                     // unsafe is only needed on the outermost block
+            modifiers: self.modifiers.clone(),                       // negation has to be applied at the innermost check
+            next_ident_id: self.next_ident_id,                       // identifiers should be unique
+            captures_added: self.captures_added,                     // tied to the variables
+            options: self.options.clone(),                           // options apply to the entire assertion
+            display_replacements: self.display_replacements.clone(), // same for the replacements
+        }
+    }
+
+    /// Ensure that there is no conflict between identifiers in the generated code by adding an incrementing number to each identifier
+    fn create_ident(&mut self, name: &str) -> syn::Ident {
+        // the leading underscore keeps the `unused_variables` lint quiet without needing `#[allow(unused)]`
+        let name = format!("__one_assert_{}_{}", name, self.next_ident_id);
+        self.next_ident_id += 1;
+        syn::Ident::new(&name, Span::call_site())
+    }
+
+    /// Create a variable from an expression and store it in the setup code without printing it
+    fn bind_var(&mut self, expr: syn::Expr, identifier: &str) -> TokenStream {
+        if matches!(expr, syn::Expr::Path(_)) {
+            // could be a variable of a type that doesn't implement Copy, so we can't store it by value.
+            // Instead, we just use the variable directly.
+            expr.to_token_stream()
+        } else if cfg!(feature = "fast") {
+            // no span manipulation, so errors about the variable point at the whole macro call instead
+            let var_ident = self.create_ident(identifier);
+            self.setup.extend(quote! {
+                let #var_ident = #expr;
+            });
+            var_ident.into_token_stream()
+        } else {
+            // The expression is stored as-is, so operands like `unsafe { ... }` blocks keep their `unsafe`.
+            // See note at the end of the file for an explanation on the span manipulation here
+            let expr_span = utils::FullSpan::from_spanned(&expr);
+
+            // The variable is declared with the same span that is used to access it, because the span also
+            // determines name resolution. The expression might come from a different hygiene context than the
+            // macro call (e.g. `$cond:expr` in a `macro_rules!` macro), and the two have to match.
+            let mut var_ident = self.create_ident(identifier);
+            var_ident.set_span(expr_span.start());
+            self.setup.extend(quote! {
+                let #var_ident = __OneAssertWrapper(#expr);
+            });
+
+            expr_span.apply(quote! { #var_ident }, quote! { .0 })
+        }
+    }
+
+    /// "Bind" an expression that can be read again without side effects by using it directly. Used to print
+    /// values that are evaluated somewhere else
+    fn bind_print_only(&mut self, expr: syn::Expr, _identifier: &str) -> TokenStream {
+        expr.into_token_stream()
+    }
+
+    /// Bind an operand of a comparison like `==` or `<`, which only borrows its operands.
+    ///
+    /// A dereferenced operand `*x` is kept as a place expression instead of being moved into a variable, so
+    /// that unsized values like `*boxed_trait_object` or `*boxed_slice` can be compared. The same goes for every
+    /// level of `**nested_box`, since moving out of `*nested_box` is not possible either.
+    fn bind_comparison_operand(&mut self, expr: syn::Expr, identifier: &str) -> TokenStream {
+        match expr {
+            syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Deref(star),
+                expr,
+                attrs,
+            }) if attrs.is_empty() => {
+                let pointer = self.bind_comparison_operand(*expr, identifier);
+                quote! { #star #pointer }
+            }
+            syn::Expr::Index(index) if index.attrs.is_empty() => {
+                // indexing returns a place, which can't be moved out of for types like `String`
+                let star = syn::Token![*](index.bracket_token.span.join());
+                let pointer =
+                    self.bind_var(utils::reference_to(syn::Expr::Index(index)), identifier);
+                quote! { #star #pointer }
+            }
+            expr => self.bind_var(expr, identifier),
+        }
+    }
+
+    /// Create a variable from an expression, store it in the setup code and print it in the error message
+    fn add_var(&mut self, expr: syn::Expr, identifier: &str, display: &str) -> TokenStream {
+        self.add_var_bound_with(expr, identifier, display, Self::bind_var, false)
+    }
+
+    /// Same as [`State::add_var`] for the argument at `position` of a call, which is only printed if the
+    /// `capture` option includes it. Other arguments are still evaluated in order, they are just not printed
+    fn add_arg(&mut self, mut arg: syn::Expr, position: usize, display: &str) -> TokenStream {
+        let identifier = format!("arg{position}");
+        if self.options.captures_arg(position) {
+            return self.add_var(arg, &identifier, display);
+        }
+        utils::strip_value_formats(&mut arg);
+        self.bind_var(arg, &identifier)
+    }
+
+    /// With the `deref` option: an expression for the note with the innermost value of `var_access`, if it is a
+    /// smart pointer like `Box<Box<T>>` or `Rc<T>`, see `DerefChain`
+    fn deref_note(&self, display: &str, var_access: &TokenStream) -> Option<TokenStream> {
+        if !self.options.deref {
+            return None;
+        }
+        Some(quote! {{
+            use ::one_assert::__macro_utilities::{DerefThrice as _, DerefTwice as _, DerefOnce as _, DerefFallback as _};
+            (&&&&::one_assert::__macro_utilities::DerefChain(&#var_access, #display)).__one_assert_deref()
+        }})
+    }
+
+    /// Print the variables of the `captures` option. They are only printed once, by the first call that is
+    /// evaluated, and are read after the condition, so that closures can still borrow them mutably
+    fn add_captures(&mut self) {
+        if std::mem::replace(&mut self.captures_added, true) {
+            return;
+        }
+        for capture in self.options.captures.clone() {
+            let display = self.display_string(&capture);
+            self.add_var_bound_with(capture, "captured", &display, Self::bind_print_only, true);
+        }
+    }
+
+    /// Same as [`State::add_var`] for an operand of a comparison, see [`State::bind_comparison_operand`].
+    ///
+    /// Comparisons only borrow their operands, so the operands are still there if the assertion fails and are
+    /// only printed then, without allocating anything if it passes.
+    fn add_comparison_operand(
+        &mut self,
+        mut expr: syn::Expr,
+        identifier: &str,
+        display: &str,
+    ) -> TokenStream {
+        if self.options.verbose && is_field_chain(&expr) {
+            // the levels are printed before the operand itself, like for a field access as the whole condition
+            expr = syn::Expr::Verbatim(capture_field_chain(expr, self));
+        }
+        self.add_var_bound_with(
+            expr,
+            identifier,
+            display,
+            Self::bind_comparison_operand,
+            true,
+        )
+    }
+
+    /// Implementation of [`State::add_var`] with a custom way to `bind` the expression.
+    ///
+    /// With `print_on_failure`, a value that is printed with `Debug` is formatted directly into the failure
+    /// message instead of being printed in advance. Only possible if the value is not moved by the condition
+    fn add_var_bound_with(
+        &mut self,
+        mut expr: syn::Expr,
+        identifier: &str,
+        display: &str,
+        bind: fn(&mut Self, syn::Expr, &str) -> TokenStream,
+        print_on_failure: bool,
+    ) -> TokenStream {
+        // the attribute has to be removed before the expression ends up in the generated code
+        let format = match utils::take_value_format(&mut expr) {
+            Ok(format) => format.or_else(|| self.options.fmt_with.clone().map(ValueFormat::With)),
+            Err(err) => {
+                self.setup.extend(err.to_compile_error());
+                None
+            }
+        };
+        self.add_lock_checks(&mut expr);
+        self.add_min_max_inputs(&mut expr);
+        self.add_ordering_tiers(&mut expr);
+        let location = self
+            .options
+            .spans
+            .then(|| utils::FullSpan::from_spanned(&expr).source_location())
+            .flatten();
+        let display = match location {
+            Some(location) => format!("{display} ({location})"),
+            None => display.to_owned(),
+        };
+        let var_access = bind(self, expr, identifier);
+
+        let var_debug_str = self.create_ident(&format!("{identifier}_str"));
+        // borrowed, because format arguments have to be sized, which `*boxed_trait_object` is not. The `&` gets
+        // the span of the expression, so that errors like a missing `Debug` impl still point at the expression
+        let start = var_access.clone().into_iter().next().map(|t| t.span());
+        let borrow = syn::Token![&](start.unwrap_or_else(Span::call_site));
+        let debug_str = match (format, self.options.precision) {
+            (Some(ValueFormat::With(formatter)), _) => {
+                // the call gets the span of the formatter, so that a wrong return type points at the formatter
+                let span = formatter
+                    .segments
+                    .last()
+                    .map_or_else(Span::call_site, |s| s.ident.span());
+                quote_spanned! {span=> {
+                    let formatted: ::std::string::String = #formatter(#borrow #var_access);
+                    formatted
+                }}
+            }
+            (Some(ValueFormat::Display), _) => quote! { ::std::format!("{}", #borrow #var_access) },
+            (None, Some(precision)) => {
+                // whether the value is a float is only known at runtime, see `FloatPrecision`
+                quote! {{
+                    use ::one_assert::__macro_utilities::{FloatPrecision as _, FloatPrecisionFallback as _};
+                    (&&::one_assert::__macro_utilities::WithPrecision(&#var_access, #precision)).__one_assert_debug()
+                }}
+            }
+            (None, None) if print_on_failure && !self.options.clean => {
+                if let Some(note) = self.deref_note(&display, &var_access) {
+                    self.notes.push(note); // read at the same time as the value itself
+                }
+                let debug_args = quote! { ::std::format_args!("{:?}", #borrow #var_access) };
+                self.deferred_values
+                    .push((var_debug_str.clone(), debug_args));
+                self.variables
+                    .push((display, var_debug_str.to_token_stream()));
+                return var_access;
+            }
+            (None, None) => quote! { ::std::format!("{:?}", #borrow #var_access) },
+        };
+        self.setup.extend(quote! {
+            let #var_debug_str = #debug_str;
+        });
+        if let Some(note) = self.deref_note(&display, &var_access) {
+            // taken together with the value, since the condition might move it
+            let deref_str = self.create_ident(&format!("{identifier}_deref"));
+            self.setup.extend(quote! { let #deref_str = #note; });
+            self.notes.push(deref_str.to_token_stream());
+        }
+
+        // store variable for now instead of printing it immediately, so that all the variables can be aligned
+        self.variables
+            .push((display, var_debug_str.to_token_stream()));
+
+        var_access
+    }
+
+    /// Bind the result of the condition in the setup code and print it, for the `show_result` option. Returns
+    /// the variable that holds the result
+    fn add_result(&mut self, condition: TokenStream) -> TokenStream {
+        let result = self.create_ident("result");
+        let result_str = self.create_ident("result_str");
+        self.setup.extend(quote! {
+            let #result: ::std::primitive::bool = #condition;
+            let #result_str = ::std::format!("{:?}", #result);
+        });
+        self.variables
+            .push(("result".to_owned(), result_str.to_token_stream()));
+        result.to_token_stream()
+    }
+
+    /// Print the inputs of a `min`, `max` or `clamp` call, since the result alone doesn't show what it was
+    /// picked from. The inputs are replaced with their variables, so that they are still only evaluated once
+    fn add_min_max_inputs(&mut self, expr: &mut syn::Expr) {
+        let is_min_max = |name: &syn::Ident, inputs: usize| {
+            ((name == "min" || name == "max") && inputs == 2) || (name == "clamp" && inputs == 3)
+        };
+        let inputs: Vec<&mut syn::Expr> = match expr {
+            // std::cmp::max(a, b), f32::clamp(x, lo, hi), ...
+            syn::Expr::Call(syn::ExprCall { func, args, .. }) => match &**func {
+                syn::Expr::Path(syn::ExprPath { path, .. })
+                    if path
+                        .segments
+                        .last()
+                        .is_some_and(|name| is_min_max(&name.ident, args.len())) =>
+                {
+                    args.iter_mut().collect()
+                }
+                _ => return,
+            },
+            // a.max(b), x.clamp(lo, hi), ...
+            syn::Expr::MethodCall(syn::ExprMethodCall {
+                receiver,
+                method,
+                args,
+                ..
+            }) if is_min_max(method, args.len() + 1) => {
+                std::iter::once(&mut **receiver).chain(args).collect()
+            }
+            _ => return,
+        };
+
+        for input in inputs {
+            if matches!(input, syn::Expr::Lit(_)) {
+                continue; // the value is already visible in the code
+            }
+            let display = self.display_string(input);
+            let original = std::mem::replace(input, syn::Expr::Verbatim(TokenStream::new()));
+            *input = syn::Expr::Verbatim(self.add_var(original, "input", &display));
+        }
+    }
+
+    /// Print each tier of an `Ordering` chain like `a.cmp(&b).then(c.cmp(&d)).then_with(|| e.cmp(&f))`, since
+    /// the combined result doesn't show which tier decided it. The tiers are replaced with their variables, so
+    /// that they are still only evaluated once. `then_with` tiers are still only evaluated if the previous
+    /// tiers are `Equal`, and print as `<not evaluated>` otherwise
+    fn add_ordering_tiers(&mut self, expr: &mut syn::Expr) {
+        let is_tier = |expr: &syn::Expr| match expr {
+            syn::Expr::MethodCall(syn::ExprMethodCall { method, args, .. }) if args.len() == 1 => {
+                match &args[0] {
+                    // `bool::then` takes a closure, `Ordering::then` doesn't
+                    syn::Expr::Closure(_) => method == "then_with",
+                    _ => method == "then" || method == "then_with",
+                }
+            }
+            _ => false,
+        };
+        if !is_tier(expr) {
+            return;
+        }
+        // the chain from the outermost call inwards, so the tiers are collected in reverse
+        let mut calls = vec![];
+        let mut current = &mut *expr;
+        while is_tier(current) {
+            let syn::Expr::MethodCall(call) = current else {
+                unreachable!()
+            };
+            calls.push((call.method.clone(), &mut call.args[0]));
+            current = &mut call.receiver;
+        }
+        let first = current;
+        let takes_inputs =
+            |arg: &syn::Expr| matches!(arg, syn::Expr::Closure(c) if !c.inputs.is_empty());
+        if calls.iter().any(|(_, arg)| takes_inputs(arg)) {
+            return; // not the closure of `Ordering::then_with`
+        }
+
+        let display = self.display_string(first);
+        let original = std::mem::replace(first, syn::Expr::Verbatim(TokenStream::new()));
+        *first = syn::Expr::Verbatim(self.add_var(original, "tier", &display));
+
+        for (method, arg) in calls.into_iter().rev() {
+            if method == "then" {
+                let display = self.display_string(arg);
+                let original = std::mem::replace(arg, syn::Expr::Verbatim(TokenStream::new()));
+                *arg = syn::Expr::Verbatim(self.add_var(original, "tier", &display));
+                continue;
+            }
+            // the closure is only called if the previous tiers are equal, so its result is remembered on the way
+            let display = match &*arg {
+                syn::Expr::Closure(closure) => self.display_string(&closure.body),
+                arg => format!("{}()", self.display_string(arg)),
+            };
+            let result = self.create_ident("tier_result");
+            self.setup.extend(quote! {
+                let #result = ::std::cell::Cell::new(::std::option::Option::None);
+            });
+            let original = std::mem::replace(arg, syn::Expr::Verbatim(TokenStream::new()));
+            *arg = syn::Expr::Verbatim(quote! {
+                || {
+                    let tier = (#original)();
+                    #result.set(::std::option::Option::Some(tier));
+                    tier
+                }
+            });
+            let value = quote! {
+                match #result.get() {
+                    ::std::option::Option::Some(tier) => ::std::format!("{:?}", tier),
+                    ::std::option::Option::None => ::std::string::String::from("<not evaluated>"),
+                }
+            };
+            self.variables.push((display, value));
+        }
+    }
+
+    /// Check the locks that are acquired with `lock.lock().unwrap()` in `expr` (or with `read()`/`write()` of an
+    /// `RwLock`, or `expect(...)`) before the condition is evaluated. If a lock is poisoned, the assertion fails
+    /// with a note about it, instead of panicking inside of `unwrap` with the opaque `PoisonError`. The lock calls
+    /// are replaced with their variables, so that they are still only called once.
+    ///
+    /// Only the receiver chain of `expr` itself is checked, like `*lock.lock().unwrap()` or
+    /// `lock.read().unwrap().len()`, which is evaluated first anyway. Locks inside of blocks, branches or
+    /// arguments are left alone, since they might not be evaluated at all, or use variables from inside the block
+    fn add_lock_checks(&mut self, expr: &mut syn::Expr) {
+        // the receiver is evaluated first, so any locks in there are checked first
+        match expr {
+            syn::Expr::MethodCall(syn::ExprMethodCall {
+                receiver: inner, ..
+            })
+            | syn::Expr::Field(syn::ExprField { base: inner, .. })
+            | syn::Expr::Index(syn::ExprIndex { expr: inner, .. })
+            | syn::Expr::Unary(syn::ExprUnary { expr: inner, .. })
+            | syn::Expr::Reference(syn::ExprReference { expr: inner, .. })
+            | syn::Expr::Paren(syn::ExprParen { expr: inner, .. })
+            | syn::Expr::Try(syn::ExprTry { expr: inner, .. }) => self.add_lock_checks(inner),
+            _ => return,
+        }
+
+        let syn::Expr::MethodCall(call) = expr else {
+            return;
+        };
+        let unwraps = (call.method == "unwrap" && call.args.is_empty())
+            || (call.method == "expect" && call.args.len() == 1);
+        if !unwraps || call.turbofish.is_some() {
+            return;
+        }
+        let syn::Expr::MethodCall(lock) = &*call.receiver else {
+            return;
+        };
+        let acquires = ["lock", "read", "write"].iter().any(|m| lock.method == m);
+        if !acquires || !lock.args.is_empty() {
+            return;
+        }
+
+        let note = format!(
+            "`{}` is poisoned, so `{}().{}` would panic",
+            self.display_string(&lock.receiver),
+            lock.method,
+            call.method
+        );
+        let result = self.create_ident("lock");
+        let lock = std::mem::replace(&mut *call.receiver, syn::parse_quote! { #result });
+        let mut failure = self.fork();
+        failure
+            .notes
+            .push(quote! { ::std::string::String::from(#note) });
+        let report = failure.finish_failure(); // the call would panic, so this fails no matter the condition
+        self.setup.extend(quote! {
+            let #result = #lock;
+            if {
+                use ::one_assert::__macro_utilities::{LockPoisoned as _, LockPoisonedFallback as _};
+                (&&::one_assert::__macro_utilities::LockCheck(&#result)).__one_assert_poisoned()
+            } {
+                #report
+            }
+        });
+    }
+
+    /// Add a `Name: Value` block for all currently stored variables to the format message
+    fn resolve_variables(&mut self) {
+        let max_name_len = self
+            .variables
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+
+        let indent = " ".repeat(4 + 2 * self.depth);
+        let mut dedup_values = vec![];
+        for (name, var_debug_str) in std::mem::take(&mut self.variables) {
+            let value = self.redact_value(self.clean_value(var_debug_str));
+
+            let unescaped_name = utils::unescape_format(&name);
+            if self.options.dedup {
+                // the values are only known at runtime, so the lines are assembled there
+                dedup_values.push(quote! { (#unescaped_name, ::std::format!("{}", #value)) });
+            } else {
+                self.format_message += &format!("\n{indent}{name:>max_name_len$}: {{}}");
+                self.dynamic_args.push(value.clone());
+            }
+            self.current_level().values.push((unescaped_name, value));
+        }
+        if !dedup_values.is_empty() {
+            self.format_message += "{}";
+            self.dynamic_args.push(quote! {
+                ::one_assert::__macro_utilities::dedup_values(#indent, &[#(#dedup_values),*])
+            });
+        }
+
+        let note_prefix = format!("\n{}", " ".repeat(2 + 2 * self.depth));
+        if self.options.redact.values() {
+            // notes are made from the values, so they would leak what was redacted
+            self.notes.clear();
+        }
+        for note in std::mem::take(&mut self.notes) {
+            self.format_message += "{}";
+            // only borrowed, since the formatter might need the note as well
+            self.dynamic_args.push(quote! {{
+                let note: &::std::primitive::str = &(#note);
+                if note.is_empty() { ::std::string::String::new() } else { ::std::format!("{}{}", #note_prefix, note.replace('\n', #note_prefix)) }
+            }});
+            self.current_level().notes.push(note);
+        }
+    }
+
+    /// The innermost level of the parts for the formatter, which the variables and notes are added to
+    fn current_level(&mut self) -> &mut FailureLevel {
+        self.levels.last_mut().expect("the top level always exists")
+    }
+
+    /// Apply the `clean` option to a debug-printed value. Only evaluated when the assertion fails
+    fn clean_value(&self, debug_str: TokenStream) -> TokenStream {
+        if self.options.clean {
+            quote! { ::one_assert::__macro_utilities::elide_phantom_data(&#debug_str) }
+        } else {
+            debug_str
+        }
+    }
+
+    /// Apply the `redact` option to a printed value: the value is still used, but never printed
+    fn redact_value(&self, value: TokenStream) -> TokenStream {
+        if self.options.redact.values() {
+            let redacted = wording::REDACTED;
+            quote! {{ let _ = &(#value); #redacted }}
+        } else {
+            value
+        }
+    }
+
+    /// Add a `pretty_assertions`-style diff of the pretty-printed `left` and `right` values to the format message
+    fn add_pretty_diff(&mut self, left: &TokenStream, right: &TokenStream) {
+        if self.options.redact.values() {
+            self.resolve_variables();
+            return;
+        }
+        let left_str = self.create_ident("lhs_pretty_str");
+        let right_str = self.create_ident("rhs_pretty_str");
+        self.setup.extend(quote! {
+            let #left_str = ::std::format!("{:#?}", #left);
+            let #right_str = ::std::format!("{:#?}", #right);
+        });
+
+        self.resolve_variables(); // any other variables should be printed before the diff
+        let left_str = self.clean_value(left_str.to_token_stream());
+        let right_str = self.clean_value(right_str.to_token_stream());
+        self.format_message += "\n\n{}";
+        let diff = quote! {
+            ::one_assert::__macro_utilities::pretty_diff(&#left_str, &#right_str)
+        };
+        self.dynamic_args.push(diff.clone());
+        self.current_level().notes.push(diff);
+    }
+
+    /// Add a note about where two `Option`s differ (`None` vs `Some`, or the inner values of `Some`), with the
+    /// `names` of the two sides. Adds nothing if the values are not `Option`s
+    fn add_option_diff(&mut self, left: &TokenStream, right: &TokenStream, names: (&str, &str)) {
+        let (left_name, right_name) = names;
+        let diff = self.create_ident("option_diff");
+        self.setup.extend(quote! {
+            let #diff = {
+                use ::one_assert::__macro_utilities::{OptionDiff as _, OptionDiffFallback as _};
+                (&&::one_assert::__macro_utilities::OptionPair(&#left, &#right))
+                    .__one_assert_option_diff(#left_name, #right_name)
+            };
+        });
+        self.notes.push(diff.to_token_stream());
+    }
+
+    /// Add a note if two `Poll`s differ in their readiness, like `left is Pending, right is Ready(_)`.
+    /// Adds nothing if the values are not `Poll`s
+    fn add_poll_diff(&mut self, left: &TokenStream, right: &TokenStream) {
+        // comparisons only borrow their operands, so the note can wait until the assertion has failed
+        self.notes.push(quote! {{
+            use ::one_assert::__macro_utilities::{PollDiff as _, PollDiffFallback as _};
+            (&&::one_assert::__macro_utilities::PollPair(&#left, &#right)).__one_assert_poll_diff()
+        }});
+    }
+
+    /// Add a note for every index where two collections of floats both contain `NaN`, which is never equal to
+    /// itself, so that the comparison fails even though the values look the same. Adds nothing for other values
+    fn add_nan_diff(&mut self, left: &TokenStream, right: &TokenStream) {
+        // comparisons only borrow their operands, so the note can wait until the assertion has failed
+        self.notes.push(quote! {{
+            use ::one_assert::__macro_utilities::{NanDiff as _, NanDiffFallback as _};
+            (&&::one_assert::__macro_utilities::NanPair(&#left, &#right)).__one_assert_nan_diff()
+        }});
+    }
+
+    /// Add a note with the keys or the length of the JSON value that an operand like `value["key"]` indexes
+    /// into. Adds nothing if the operand is not an index expression, or if the indexed value is not a
+    /// `serde_json::Value` (or the `serde_json` feature is disabled).
+    ///
+    /// The indexed value is evaluated again for the note, so this is skipped if that would call any code.
+    /// With `eager`, the note is created right away, otherwise only if the assertion fails
+    fn add_json_index_note(&mut self, operand: &syn::Expr, eager: bool) {
+        let syn::Expr::Index(syn::ExprIndex { expr: base, .. }) = operand else {
+            return;
+        };
+        if utils::contains_call(base) {
+            return;
+        }
+        let name = utils::unescape_format(&self.display_string(base));
+        let note = quote! {{
+            use ::one_assert::__macro_utilities::{JsonDetailed as _, JsonFallback as _};
+            (&&::one_assert::__macro_utilities::JsonValue(&#base)).__one_assert_json_container(#name)
+        }};
+        if eager {
+            let ident = self.create_ident("json_index");
+            self.setup.extend(quote! { let #ident = #note; });
+            self.notes.push(ident.to_token_stream());
+        } else {
+            self.notes.push(note);
+        }
+    }
+
+    /// Add a note with the keys that are only in one of two maps, and the keys with different values, with the
+    /// `names` of the two sides. Adds nothing if the values are not maps, see `OneAssertMapLike`
+    fn add_map_diff(&mut self, left: &TokenStream, right: &TokenStream, names: (&str, &str)) {
+        let (left_name, right_name) = names;
+        let diff = self.create_ident("map_diff");
+        self.setup.extend(quote! {
+            let #diff = {
+                use ::one_assert::__macro_utilities::{MapDiff as _, MapDiffFallback as _};
+                (&&::one_assert::__macro_utilities::MapPair(&#left, &#right))
+                    .__one_assert_map_diff(#left_name, #right_name)
+            };
+        });
+        self.notes.push(diff.to_token_stream());
+    }
+
+    /// Add a note with a hash of both sides of a comparison with the `fingerprint` option.
+    /// Adds nothing if the values don't implement `Hash`
+    fn add_fingerprints(&mut self, left: &TokenStream, right: &TokenStream) {
+        if !self.options.fingerprint {
+            return;
+        }
+        // comparisons only borrow their operands, so the hashes can wait until the assertion has failed
+        self.notes.push(quote! {{
+            use ::one_assert::__macro_utilities::{Fingerprints as _, FingerprintsFallback as _};
+            (&&::one_assert::__macro_utilities::FingerprintPair(&#left, &#right)).__one_assert_fingerprints()
+        }});
+    }
+
+    /// Add a note with the time between the two sides of a comparison, like `end - start = 1.2s`.
+    /// Adds nothing if the values are not points in time like `Instant` or `SystemTime`, see `TimePoint`
+    fn add_elapsed(
+        &mut self,
+        left: &syn::Expr,
+        right: &syn::Expr,
+        lhs: &TokenStream,
+        rhs: &TokenStream,
+    ) {
+        let operand_code = |operand: &syn::Expr| {
+            let code = utils::unescape_format(&self.display_string(operand));
+            match operand {
+                syn::Expr::Path(_)
+                | syn::Expr::Field(_)
+                | syn::Expr::MethodCall(_)
+                | syn::Expr::Call(_)
+                | syn::Expr::Index(_)
+                | syn::Expr::Paren(_) => code,
+                _ => format!("({code})"),
+            }
+        };
+        let (left, right) = (operand_code(left), operand_code(right));
+        // comparisons only borrow their operands, so the difference can wait until the assertion has failed
+        self.notes.push(quote! {{
+            use ::one_assert::__macro_utilities::{Elapsed as _, ElapsedFallback as _};
+            (&&::one_assert::__macro_utilities::TimePair(&#lhs, &#rhs)).__one_assert_elapsed(#left, #right)
+        }});
+    }
+
+    /// Append the discriminant of the most recently added variable to its value with the `discriminant` option.
+    ///
+    /// Only types that implement `OneAssertDiscriminant` have one, see `DiscriminantSuffix`.
+    fn add_discriminant(&mut self, var_access: &TokenStream) {
+        if !self.options.discriminant {
+            return;
+        }
+        // taken before the condition is evaluated, because the condition might consume the value
+        let suffix = self.create_ident("discriminant");
+        self.setup.extend(quote! {
+            let #suffix = {
+                use ::one_assert::__macro_utilities::{DiscriminantSuffix as _, DiscriminantSuffixFallback as _};
+                (&&::one_assert::__macro_utilities::WithDiscriminant(&#var_access)).__one_assert_discriminant()
+            };
+        });
+        if let Some((_, debug_str)) = self.variables.last_mut() {
+            *debug_str = quote! { ::std::format!("{}{}", #debug_str, #suffix) };
+        }
+    }
+
+    /// Emit a compiler warning with the `warn_duplicates` option if both sides of a comparison are the same code
+    /// that calls something, like `compute() == compute()`. Both sides are evaluated separately, which is correct,
+    /// but might be surprising.
+    ///
+    /// Proc macros can't emit warnings on stable Rust, so this calls a deprecated function instead. The warning
+    /// can be silenced with `#[allow(deprecated)]`.
+    fn warn_duplicate_operands(&mut self, left: &syn::Expr, right: &syn::Expr) {
+        if !self.options.warn_duplicates {
+            return;
+        }
+        let code = self.code_string(right);
+        if self.code_string(left) != code || !utils::contains_call(right) {
+            return;
+        }
+        let note = format!(
+            "`{}` is evaluated on both sides of the comparison. Each side is evaluated separately, so any side \
+             effects happen twice and the two values might differ",
+            utils::unescape_format(&code)
+        );
+        let span = utils::FullSpan::from_spanned(right).start();
+        // one per comparison, so every warning names its own code
+        let mut warning = self.create_ident("duplicate_operand");
+        warning.set_span(span);
+        self.setup.extend(quote_spanned! {span=>
+            {
+                #[deprecated(note = #note)]
+                fn #warning() {}
+                #warning();
+            }
+        });
+    }
+
+    /// Print the type of a method call receiver with the `types` option
+    fn add_receiver_type(&mut self, receiver: &TokenStream) {
+        if !self.options.types {
+            return;
+        }
+        // taken before the call, because the method might consume the receiver
+        let type_name = self.create_ident("self_type");
+        self.setup.extend(quote! {
+            let #type_name = ::one_assert::__macro_utilities::type_name_of(&#receiver);
+        });
+        self.variables
+            .push(("self type".to_owned(), type_name.to_token_stream()));
+    }
+
+    /// Checks if the assertion is currently inside of a `!`
+    fn is_negated(&self) -> bool {
+        let negations = self
+            .modifiers
+            .iter()
+            .filter(|(_, m)| matches!(m, ExprModifier::Negated(_)))
+            .count();
+        negations % 2 == 1
+    }
+
+    /// The attribute that is placed on generated blocks to silence warnings about unused code.
+    ///
+    /// Empty if the `warn_unused` option is set, so that warnings in the user's expression are not masked.
+    fn allow_unused(&self) -> TokenStream {
+        if self.options.warn_unused {
+            TokenStream::new()
+        } else {
+            quote! { #[allow(unused)] }
+        }
+    }
+
+    /// The code of (a part of) the expression for the format message, as the user wrote it.
+    ///
+    /// Everything that ends up in the message goes through here, so `redact` replaces the code with `<redacted>`.
+    /// Use [`Self::code_string`] to look at the code itself.
+    fn display_string(&self, expr: &syn::Expr) -> String {
+        if self.options.redact.expression() {
+            return wording::REDACTED.to_owned();
+        }
+        self.code_string(expr)
+    }
+
+    /// The code of (a part of) the expression as the user wrote it, even with `redact`
+    fn code_string(&self, expr: &syn::Expr) -> String {
+        let mut expr = self.display_replacements.restore(expr);
+        utils::strip_value_formats(&mut expr); // only meant for the macro, not part of the condition
+        if self.options.simplify_negation {
+            simplify_negations(&mut expr);
+        }
+        printable_expr_string(&expr)
+    }
+
+    /// Set up the first line of the message: "assertion \`{header}\` failed: {message}".
+    /// `header` has to be escaped for a format string already (see [`printable_expr_string`])
+    fn start(&mut self, header: &str, format: &TokenStream) {
+        let header = if self.options.redact.expression() {
+            wording::REDACTED
+        } else {
+            header
+        };
+        // A wrapper type to create multi-token variables for span manipulation
+        if !cfg!(feature = "fast") {
+            self.setup = quote! {
+                #[allow(dead_code)] // not constructed if nothing is captured
+                struct __OneAssertWrapper<T>(T);
+            };
+        }
+        self.format_message = format!("assertion `{header}` failed");
+        self.header = utils::unescape_format(header);
+        if let Some(label) = &self.options.label {
+            self.format_message = format!("[{label}] {}", self.format_message);
+        }
+        if let Some(level) = self.options.level {
+            self.format_message = format!("[{}] {}", level.name(), self.format_message);
+        }
+
+        if self.options.module {
+            self.format_message += " [in {}]";
+            self.dynamic_args.push(quote! { ::std::module_path!() });
+        }
+
+        if format.is_empty() {
+            return; // nothing to print
+        }
+        self.message_format = Some(format.clone());
+        let message = message_ident();
+        if self.options.message_position == MessagePosition::Cause {
+            self.cause_message = Some(message.to_token_stream());
+            self.message_anchor = (self.format_message.len(), self.dynamic_args.len());
+        } else {
+            self.format_message += ": {}";
+            self.dynamic_args.push(message.to_token_stream());
+        }
+    }
+
+    /// Complete the format message and generate the code that reports a failure with it: a panic, a
+    /// warning with the `level = "warning"` option, or leaving the `try_assert!` block with the error
+    fn report(&mut self) -> TokenStream {
+        if let Some(message) = self.cause_message.take() {
+            let (position, arg_index) = self.message_anchor;
+            self.format_message.insert_str(position, ": {}");
+            self.dynamic_args.insert(arg_index, message);
+        }
+        self.resolve_variables();
+        if cfg!(feature = "backtrace") {
+            // only captured here, so that passing assertions don't pay for it
+            self.format_message += "{}";
+            self.dynamic_args
+                .push(quote! { ::one_assert::__macro_utilities::backtrace_section() });
+        }
+
+        let format_message = &self.format_message;
+        let dynamic_args = &self.dynamic_args;
+        let header = &self.header;
+        let message = match &self.message_format {
+            Some(_) => {
+                let ident = message_ident();
+                quote! { ::std::option::Option::Some(#ident) }
+            }
+            None => quote! { ::std::option::Option::None },
+        };
+        let levels = self.levels.iter().map(|level| {
+            let cause = &level.cause;
+            let names = level.values.iter().map(|(name, _)| name);
+            let values = level.values.iter().map(|(_, value)| value);
+            let notes = &level.notes;
+            quote! {(
+                #cause,
+                ::std::vec![#((#names, ::std::string::ToString::to_string(&(#values)))),*],
+                ::std::vec![#(::std::string::ToString::to_string(&(#notes))),*],
+            )}
+        });
+        let final_message = quote! {
+            ::one_assert::__macro_utilities::format_failure(
+                ::std::format!(#format_message, #(#dynamic_args),*),
+                #header,
+                #message,
+                || ::std::vec![#(#levels),*],
+            )
+        };
+
+        let action = if self.options.level == Some(Level::Warning) {
+            quote! { ::std::eprintln!("{}", #final_message); }
+        } else if self.options.return_error {
+            let label = result_label();
+            quote! {
+                break #label ::std::result::Result::Err(::one_assert::OneAssertError::new(#final_message))
+            }
+        } else {
+            let panic = panic_with_message("{}", &[final_message]);
+            quote! { #panic; }
+        };
+        let action = if self.deferred_values.is_empty() {
+            action
+        } else {
+            // same as the message below: formatted once, directly into the final message
+            let (idents, args): (Vec<_>, Vec<_>) = self.deferred_values.iter().cloned().unzip();
+            quote! {
+                match (#(#args,)*) {
+                    (#(#idents,)*) => { #action }
+                }
+            }
+        };
+        match &self.message_format {
+            // evaluated once for both the default message and the formatter. A `match` keeps the temporaries
+            // of the arguments alive, which a `let` wouldn't
+            Some(format) => {
+                let ident = message_ident();
+                quote! {
+                    match ::std::format_args!(#format) {
+                        #ident => { #action }
+                    }
+                }
+            }
+            None => action,
+        }
+    }
+
+    /// Generate the code that unconditionally reports a failure, for checks in the setup of the assertion.
+    ///
+    /// The rest of the assertion is never evaluated afterwards, since the check found that it would panic. A
+    /// warning leaves the block from [`Self::finish`] instead of panicking.
+    fn finish_failure(mut self) -> TokenStream {
+        let mut report = self.report();
+        if self.options.level == Some(Level::Warning) {
+            report.extend(self.leave_after_warning());
+        }
+        let allow_unused = self.allow_unused();
+        let State {
+            setup,
+            possibly_unsafe,
+            ..
+        } = self;
+        quote! {
+            #allow_unused
+            #possibly_unsafe {
+                #setup
+                #report
+            }
+        }
+    }
+
+    /// Generate the final assertion code for `assert_condition`, with all the setup, modifiers and the panic
+    fn finish(mut self, mut assert_condition: TokenStream) -> TokenStream {
+        if self.options.show_result {
+            assert_condition = self.add_result(assert_condition);
+        }
+        let report = self.report();
+        let allow_unused = self.allow_unused();
+        let return_error = self.options.return_error;
+        let pass = return_error.then(|| quote! { ::std::result::Result::Ok(()) });
+        let warning = self.options.level == Some(Level::Warning);
+        let after_report = warning.then(|| self.leave_after_warning());
+        let State {
+            setup,
+            possibly_unsafe,
+            modifiers,
+            ..
+        } = self;
+
+        for (i, (attrs, modifier)) in modifiers.into_iter().enumerate().rev() {
+            let inner = std::mem::take(&mut assert_condition);
+            match modifier {
+                // braces directly around the `if` condition are redundant and trigger the `unused_braces` lint
+                ExprModifier::Blocked(_) if i == 0 => assert_condition = inner,
+                ExprModifier::Negated(not_token) => {
+                    assert_condition = quote! { #(#attrs)* #not_token #inner };
+                }
+                ExprModifier::Parenthesized(parentheses) => {
+                    parentheses.surround(&mut assert_condition, |out| inner.to_tokens(out));
+                }
+                ExprModifier::Blocked(braces) => {
+                    braces.surround(&mut assert_condition, |out| inner.to_tokens(out));
+                }
+                ExprModifier::Grouped(group) => {
+                    let mut grouped = TokenStream::new();
+                    group.surround(&mut grouped, |out| inner.to_tokens(out));
+                    assert_condition = quote! { #(#attrs)* #grouped };
+                }
+            }
+        }
+
+        let check = quote! {
+            #possibly_unsafe {
+                #setup
+                if #assert_condition {
+                    // using an empty if instead of `!(#expression)` to avoid messing with the spans in `expression`.
+                    // And to produce a better error: "expected bool, found <type>" instead of
+                    // "no unary operator '!' implemented for <type>"
+                    #pass
+                } else {
+                    #report
+                    #after_report
+                }
+            }
+        };
+        if return_error {
+            let label = result_label();
+            // failures in the setup (like from the `verbose` option) break out of the block as well
+            quote! {{
+                #allow_unused
+                let result: ::std::result::Result<(), ::one_assert::OneAssertError> =
+                    #label: { #check };
+                result
+            }}
+        } else if warning {
+            let label = result_label();
+            quote! {{
+                #allow_unused
+                #label: { #check }
+            }}
+        } else {
+            quote! {
+                #allow_unused
+                #check
+            }
+        }
+    }
+
+    /// Leave the assertion after a warning was printed with the `level = "warning"` option. A warning doesn't
+    /// stop the program, so the assertion still succeeds afterwards
+    fn leave_after_warning(&self) -> TokenStream {
+        let label = result_label();
+        let pass = self
+            .options
+            .return_error
+            .then(|| quote! { ::std::result::Result::Ok(()) });
+        quote! { break #label #pass; }
+    }
+
+    /// Adds a "caused by" message to the format message.
+    ///
+    /// Every cause starts a new level of nesting, which is indented by two more spaces than the previous one:
+    /// The "caused by" line of level `n` is indented by `2 + 2n` spaces, and the variables and notes below it
+    /// by `4 + 2n` and `2 + 2(n + 1)` respectively. The variables of the top level (`n = 0`) are indented by 4.
+    /// ```text
+    /// assertion `...` failed
+    ///     <variables>
+    ///   caused by: ...
+    ///       <variables>
+    ///     caused by: ...
+    ///         <variables>
+    /// ```
+    fn add_cause(&mut self, cause: &str) {
+        let cause = if self.options.redact.expression() {
+            wording::REDACTED
+        } else {
+            cause
+        };
+        let indent = " ".repeat(2 + 2 * self.depth);
+        self.format_message += &format!("\n{indent}{}: {cause}", wording::CAUSED_BY);
+        self.levels.push(FailureLevel {
+            cause: utils::unescape_format(cause),
+            ..FailureLevel::default()
+        });
+        self.depth += 1;
+        self.message_anchor = (self.format_message.len(), self.dynamic_args.len());
+    }
+}
+
+fn assert_internal(input: Args, display_replacements: DisplayReplacements) -> Result<TokenStream> {
+    let Args {
+        expr,
+        options,
+        format,
+    } = input;
+
+    let mut state = State::new(options, display_replacements);
+    let expr_str = state.code_string(&expr);
+    let format = utils::substitute_condition(format, &state.display_string(&expr))?;
+
+    // the shortcuts below never fail (or panic on their own), so `try_assert!` only has to add the `Ok`
+    let ok = state.options.return_error.then(|| {
+        quote! { ::std::result::Result::<(), ::one_assert::OneAssertError>::Ok(()) }
+    });
+
+    if expr_str == "true" {
+        let flavor = assert_true_flavor();
+        return Ok(quote! {{ #flavor #ok }});
+    } else if expr_str == "false" {
+        let msg = "surprisingly, `false` did not evaluate to true";
+        if ok.is_some() {
+            return Ok(quote! {
+                ::std::result::Result::<(), ::one_assert::OneAssertError>::Err(
+                    ::one_assert::OneAssertError::new(::std::string::String::from(#msg))
+                )
+            });
+        }
+        return Ok(panic_with_message(msg, &[]));
+    }
+
+    if let syn::Expr::Binary(binary) = &expr {
+        match fold_literal_comparison(binary) {
+            Some(true) => {
+                // keep the message type-checked, even though it can never be printed
+                let message = (!format.is_empty()).then(|| {
+                    quote! { if false { ::std::panic!("{}", ::std::format_args!(#format)) } }
+                });
+                return Ok(quote! {{
+                    #[allow(unused)]
+                    {
+                        let _: ::std::primitive::bool = #expr;
+                        #message
+                    }
+                    #ok
+                }});
+            }
+            Some(false) => {
+                let msg = format!("this assertion always fails: `{expr_str}` compares two literals and is always false. Use `assert!(false)` if this is intentional");
+                return Error::err_spanned(expr, msg); // checked in tests/fail/const_fold.rs
+            }
+            None => {}
+        }
+    }
+
+    state.start(&expr_str, &format);
+    if state.options.auto_context {
+        for local in utils::context_locals(&expr) {
+            let local: syn::Expr = syn::parse_quote! { #local };
+            let name = state.display_string(&local);
+            state.add_var(local, "context", &name);
+        }
+    }
+
+    // eval_expr(expr, state)
+    let output = eval_expr(expr, state)?;
+    // println!();
+    // println!();
+    // println!("{}", output);
+    // println!();
+    // println!();
+    Ok(output)
+}
+
+fn eval_expr(e: syn::Expr, mut state: State) -> Result<TokenStream> {
+    let mut assert_condition = e.to_token_stream();
+    match e {
+        // [a, b, c, d]
+        syn::Expr::Array(_) => {} // let the compiler generate the error
+
+        // a = b
+        syn::Expr::Assign(syn::ExprAssign { eq_token, .. }) => {
+            let msg = "Expected a boolean expression, found an assignment. Did you intend to compare with `==`?";
+            return Error::err_spanned(eq_token, msg); // checked in tests/fail/expr/assign.rs
+        }
+
+        // async { ... }
+        syn::Expr::Async(_) => {
+            let msg = "Expected a boolean expression, found an async block. Did you intend to await a future?";
+            return Error::err_spanned(e, msg); // checked in tests/fail/expr/async.rs
+        }
+
+        // future.await
+        syn::Expr::Await(_) => {} // might work if the future resolves to a boolean and the assert is in an async context
+
+        // a && b && ... or a || b || ...
+        syn::Expr::Binary(syn::ExprBinary {
+            left,
+            op: op @ (syn::BinOp::And(_) | syn::BinOp::Or(_)),
+            right,
+            attrs,
+        }) => {
+            let mut operands = vec![];
+            let mut ops = vec![];
+            flatten_logical_chain(*left, &op, &mut operands, &mut ops);
+            operands.push(*right);
+            ops.push(op);
+
+            let total = operands.len();
+            let index_len = total.to_string().len();
+            let mut labels = vec![];
+            let mut vars = vec![];
+            for (i, operand) in operands.into_iter().enumerate() {
+                labels.push(state.display_string(&operand));
+                let display = format!("operand {:>index_len$}", i + 1);
+                vars.push(add_logical_operand(
+                    operand,
+                    &format!("operand{i}"),
+                    &display,
+                    &mut state,
+                ));
+            }
+
+            // the note explains why the assertion failed, which is the other way around inside of a `!`
+            if !state.is_negated() {
+                if matches!(ops[0], syn::BinOp::And(_)) {
+                    let arms = labels.iter().enumerate().map(|(i, label)| {
+                        let msg = format!("operand {} of {total} ({label}) was false", i + 1);
+                        quote! { ::std::option::Option::Some(#i) => ::std::format!(#msg), }
+                    });
+                    state.notes.push(quote! {
+                        match [#(#vars),*].iter().position(|operand| !*operand) {
+                            #(#arms)*
+                            _ => ::std::string::String::new(),
+                        }
+                    });
+                } else {
+                    let msg = format!("all {total} operands were false");
+                    state
+                        .notes
+                        .push(quote! { ::std::string::String::from(#msg) });
+                }
+            }
+
+            let first = &vars[0];
+            let rest = ops
+                .iter()
+                .zip(&vars[1..])
+                .map(|(op, var)| quote! { #op #var });
+            assert_condition = quote! { #(#attrs)* #first #(#rest)* };
+        }
+
+        // left <op> right
+        syn::Expr::Binary(syn::ExprBinary {
+            left,
+            op,
+            right,
+            attrs,
+        }) => {
+            if is_comparison(&op) {
+                state.warn_duplicate_operands(&left, &right);
+            }
+            let (lhs, rhs);
+            if matches!(op, syn::BinOp::Eq(_))
+                && state.options.layout == Layout::PrettyAssertions
+                && !state.is_negated()
+            {
+                lhs = state.bind_comparison_operand(*left, "lhs");
+                rhs = state.bind_comparison_operand(*right, "rhs");
+                state.add_pretty_diff(&lhs, &rhs);
+            } else if is_comparison(&op) {
+                let operands = (left.clone(), right.clone());
+                let (left_label, right_label) = match state.options.operand_labels {
+                    Some((left_label, right_label)) => {
+                        (left_label.to_owned(), right_label.to_owned())
+                    }
+                    None => (
+                        operand_label(&left, "left", &state),
+                        operand_label(&right, "right", &state),
+                    ),
+                };
+                state.add_json_index_note(&left, false);
+                state.add_json_index_note(&right, false);
+                lhs = state.add_comparison_operand(*left, "lhs", &left_label);
+                state.add_discriminant(&lhs);
+                rhs = state.add_comparison_operand(*right, "rhs", &right_label);
+                state.add_discriminant(&rhs);
+                if matches!(op, syn::BinOp::Eq(_)) && !state.is_negated() {
+                    let sides = state.options.operand_labels.unwrap_or(("left", "right"));
+                    state.add_option_diff(&lhs, &rhs, sides);
+                    state.add_map_diff(&lhs, &rhs, sides);
+                    state.add_poll_diff(&lhs, &rhs);
+                    state.add_nan_diff(&lhs, &rhs);
+                }
+                if matches!(op, syn::BinOp::Eq(_) | syn::BinOp::Ne(_)) {
+                    state.add_fingerprints(&lhs, &rhs);
+                }
+                state.add_elapsed(&operands.0, &operands.1, &lhs, &rhs);
+            } else {
+                let (left_label, right_label) = (
+                    operand_label(&left, "left", &state),
+                    operand_label(&right, "right", &state),
+                );
+                lhs = state.add_var(*left, "lhs", &left_label);
+                state.add_discriminant(&lhs);
+                rhs = state.add_var(*right, "rhs", &right_label);
+                state.add_discriminant(&rhs);
+            }
+            assert_condition = quote! { #(#attrs)* #lhs #op #rhs };
+        }
+
+        // { ... }
+        syn::Expr::Block(syn::ExprBlock { block, attrs, .. }) => {
+            return eval_block(block, attrs, state)
+        }
+
+        // break
+        syn::Expr::Break(_) => {
+            // we need to generate our own error, because break returns `!` so it compiles, but the assertion makes no sense
+            let msg = "Expected a boolean expression, found a break statement";
+            return Error::err_spanned(e, msg); // checked in tests/fail/expr/break.rs
+        }
+
+        // function(args...)
+        syn::Expr::Call(syn::ExprCall {
+            args,
+            func,
+            paren_token,
+            attrs,
+        }) if !args.is_empty() => {
+            state.add_captures();
+            let index_len = (args.len() - 1).to_string().len();
+            let out_args = args
+                .into_iter()
+                .enumerate()
+                .map(|(i, arg)| state.add_arg(arg, i, &format!("arg {i:>index_len$}")));
+
+            // output: `quote! { #(#attrs)* #func ( #(#out_args),* ) }` except we want to use the original parentheses for span purposes
+            assert_condition = quote! { #(#attrs)* #func };
+            paren_token.surround(&mut assert_condition, |out| {
+                out.extend(quote! { #(#out_args),* })
+            });
+        }
+        // function() // no args
+        syn::Expr::Call(_) => state.add_captures(), // a plain function call, so only the `captures` option adds anything
+
+        // expr as ty
+        syn::Expr::Cast(_) => {} // let the compiler generate the error.
+        // Might work if expr is `true as bool`, which would actually be a workaround for the `assert!(true)` case
+
+        // |args| { ... }
+        syn::Expr::Closure(_) => {} // let the compiler generate the error
+
+        // const { ... }
+        syn::Expr::Const(syn::ExprConst { block, attrs, .. }) => {
+            return eval_block(block, attrs, state);
+        }
+        // the way this is structured means you can technically assert a non-const block while pretending it's a const block,
+        // but then again, why do you have a const block in an assert?
+
+        // continue
+        syn::Expr::Continue(_) => {
+            // we need to generate our own error, because continue returns `!` so it compiles, but the assertion makes no sense
+            let msg = "Expected a boolean expression, found a continue statement";
+            return Error::err_spanned(e, msg); // checked in tests/fail/expr/continue.rs
+        }
+
+        // obj.field
+        syn::Expr::Field(syn::ExprField {
+            attrs,
+            base,
+            dot_token,
+            member,
+        }) if state.options.verbose => {
+            // in long chains like `config.server.tls.enabled`, any of the levels might hold the unexpected value
+            let base = capture_field_level(*base, &mut state);
+            assert_condition = quote! { #(#attrs)* #base #dot_token #member };
+        }
+        syn::Expr::Field(_) => {} // might work if the field is a boolean
+        // Without `verbose`, the object that the field is accessed on is not printed, since the only interesting
+        // part of it is usually the field, and that is already evaluated as the assertion.
+
+        // for pat in { ... }
+        syn::Expr::ForLoop(_) => {
+            // we generate our own error, because the compiler just says "expected bool, found ()"
+            let msg = "Expected a boolean expression, found a for loop";
+            return Error::err_spanned(e, msg); // checked in tests/fail/expr/forloop.rs
+        }
+
+        // group with invisible delimiters, e.g. from passing `$cond:expr` to the macro in a `macro_rules!` macro
+        syn::Expr::Group(syn::ExprGroup {
+            expr,
+            group_token,
+            attrs,
+        }) => {
+            // keep the group and its attributes around the final condition, same as with parentheses
+            state
+                .modifiers
+                .push((attrs, ExprModifier::Grouped(group_token)));
+            return eval_expr(*expr, state);
+        }
+
+        // if cond { ... } else { ... }
+        syn::Expr::If(branch) => {
+            let possibly_unsafe = std::mem::take(&mut state.possibly_unsafe);
+            let allow_unused = state.allow_unused();
+            let output = setup_if(branch, state)?;
+
+            let output = quote! {
+                #allow_unused
+                #possibly_unsafe {
+                    #output
+                }
+            };
+            return Ok(output);
+        }
+
+        // expr[index]
+        syn::Expr::Index(syn::ExprIndex {
+            index,
+            expr,
+            attrs,
+            bracket_token,
+        }) if !matches!(*index, syn::Expr::Lit(_)) => {
+            let index = state.add_var(*index, "index", "index");
+            // output: `quote! { #(#attrs)* #expr [#index] }` except we want to use the original brackets for span purposes
+            assert_condition = quote! { #(#attrs)* #expr };
+            bracket_token.surround(&mut assert_condition, |out| index.to_tokens(out));
+
+            // not printing the indexed object, because the output could be huge.
+            // If we knew the object was a form of array, then we could would slice the range around the index,
+            // but it could also be a HashMap or a custom type, so we can't do that.
+        }
+        // expr[literal]
+        syn::Expr::Index(_) => {} // not printing literals, because their value is already known.
+
+        // _
+        syn::Expr::Infer(_) => {} // let the compiler generate the error
+
+        // let pat = expr
+        syn::Expr::Let(_) => {
+            // we have to generate our own error, because the produced code is `if #expression`, which would become `if let ...` 😂
+            let msg = "Expected a boolean expression, found a let statement";
+            return Error::err_spanned(e, msg); // checked in tests/fail/expr/let.rs
+        }
+
+        // lit
+        syn::Expr::Lit(_) => {} // might work if the literal is a boolean
+        // The base case for `assert!(true)` and `assert!(false)` was already caught in the initial
+        // setup. This is the case where a recursive call contained a plain `true` or `false`, so we
+        // shall accept them without printing weird messages
+
+        // loop { ... }
+        syn::Expr::Loop(_) => {} // might work if the loop breaks with a boolean
+        // If somebody has too much free time on their hands they can go ahead and write some recursive
+        // block parsing code to find all the `break` statements so that the error message can say
+        // which one was triggered. This would be really useful info for the user, but it's a lot of effort
+        // for something that probably nobody will ever see.
+        // Side note: Finding a `break` would actually help with the case where there are no breaks, because
+        // then the loop would just never return (`!`), so the compiler doesn't complain but the assertion
+        // makes no sense.
+
+        // some_macro!(...)
+        syn::Expr::Macro(_) => {} // not touching this
+
+        // match expr { ... }
+        syn::Expr::Match(syn::ExprMatch {
+            arms,
+            expr,
+            attrs,
+            match_token,
+            brace_token,
+        }) => {
+            let expr_str = state.display_string(&expr);
+            let match_expr = state.add_var(*expr, "matched", "matched value");
+
+            state.resolve_variables();
+
+            let mut arms_output = TokenStream::new();
+            for arm in arms {
+                let syn::Arm {
+                    pat,
+                    guard,
+                    body,
+                    attrs,
+                    fat_arrow_token,
+                    ..
+                } = arm;
+
+                let display_guard = guard
+                    .as_ref()
+                    .map(|(if_token, expr)| {
+                        let expr = state.display_replacements.restore(expr);
+                        quote! { #if_token #expr }
+                    })
+                    .unwrap_or_default();
+                let guard_operands = guard
+                    .as_ref()
+                    .map(|(_, expr)| {
+                        utils::outer_guard_operands(expr, &utils::pattern_bindings(&pat))
+                    })
+                    .unwrap_or_default();
+                let guard = guard
+                    .map(|(if_token, expr)| quote! { #if_token #expr })
+                    .unwrap_or_default();
+
+                let arm_kind = if guard.is_empty() && utils::is_catch_all_pattern(&pat) {
+                    wording::CATCH_ALL_ARM
+                } else {
+                    wording::ARM
+                };
+                let pattern = quote! { #pat #guard };
+
+                let mut arm_state = state.fork();
+
+                let cause = wording::match_arm(
+                    &expr_str,
+                    arm_kind,
+                    &printable_expr_string(&quote! { #pat #display_guard }),
+                    &arm_state.display_string(&body),
+                );
+                arm_state.add_cause(&cause);
+
+                // the guard was already evaluated, but variables from outside of the arm can simply be read again
+                for operand in guard_operands {
+                    let display = arm_state.display_string(&operand);
+                    arm_state.add_var_bound_with(
+                        operand,
+                        "guard",
+                        &display,
+                        State::bind_print_only,
+                        false,
+                    );
+                }
+
+                let assert_eval = eval_expr(*body, arm_state)?;
+
+                arms_output.extend(quote! {
+                    #(#attrs)* #pattern #fat_arrow_token {
+                        #assert_eval
+                    }
+                });
+            }
+
+            // output: `quote! { #(#attrs)* #match_token #match_expr { #arms_output } }` except we want to use the original braces for span purposes
+            let mut inner_tokens = quote! { #(#attrs)* #match_token #match_expr };
+            brace_token.surround(&mut inner_tokens, |out| out.extend(arms_output));
+
+            let allow_unused = state.allow_unused();
+            let State {
+                setup,
+                possibly_unsafe,
+                ..
+            } = state;
+
+            let output = quote! {
+                #allow_unused
+                #possibly_unsafe {
+                    #setup
+                    #inner_tokens
+                }
+            };
+            return Ok(output);
+        }
+
+        // iter.all(|x| ...) or iter.any(|x| ...)
+        syn::Expr::MethodCall(syn::ExprMethodCall {
+            receiver,
+            method,
+            turbofish: None,
+            args,
+            attrs,
+            dot_token,
+            paren_token,
+        }) if (method == "all" || method == "any")
+            && args.len() == 1
+            && matches!(&args[0], syn::Expr::Closure(closure) if closure.inputs.len() == 1 && closure.asyncness.is_none()) =>
+        {
+            // A variable is used directly, so that the method call borrows or moves it the same way as without the
+            // macro. Anything else is stored in a mutable variable, since `Iterator::all` takes `&mut self`
+            let obj = if matches!(*receiver, syn::Expr::Path(_)) {
+                state.add_var(*receiver, "object", "self")
+            } else {
+                let var = state.create_ident("object");
+                state.setup.extend(quote! { let mut #var = #receiver; });
+                let var_expr = syn::parse_quote! { #var };
+                state.add_var_bound_with(var_expr, "object", "self", State::bind_print_only, false)
+            };
+            state.add_receiver_type(&obj);
+
+            // The closure is not Debug, so instead of printing it, the predicate is wrapped in a closure that
+            // remembers the element that decided the result. The original method is still called, so that the
+            // predicate is applied once per element, same as without the macro, and any receiver with an `all`
+            // or `any` method works.
+            let Some(syn::Expr::Closure(predicate)) = args.into_iter().next() else {
+                unreachable!("checked in the match guard");
+            };
+            let recorder = state.create_ident("recorder");
+            state.setup.extend(quote! {
+                let #recorder = &::one_assert::__macro_utilities::PredicateRecorder::new();
+            });
+            let wrapped = wrap_predicate(predicate, &recorder, method == "any", &mut state);
+            assert_condition = quote! { #(#attrs)* #obj #dot_token #method };
+            paren_token.surround(&mut assert_condition, |out| wrapped.to_tokens(out));
+
+            let display = if method == "all" {
+                "first failing element"
+            } else {
+                "first matching element"
+            };
+            state
+                .variables
+                .push((display.to_owned(), quote! { #recorder.detail() }));
+        }
+
+        // receiver.method(args...)
+        syn::Expr::MethodCall(syn::ExprMethodCall {
+            receiver,
+            method,
+            turbofish,
+            args,
+            attrs,
+            dot_token,
+            paren_token,
+        }) => {
+            let json_predicate =
+                args.is_empty() && JSON_PREDICATES.iter().any(|name| method == name);
+            let mut receiver = *receiver;
+            if json_predicate {
+                state.add_json_index_note(&receiver, true);
+                if matches!(receiver, syn::Expr::Index(_)) {
+                    // indexing returns a place, which can't be moved out of for types like `Value`
+                    receiver = utils::reference_to(receiver);
+                }
+            }
+            let obj = state.add_var(receiver, "object", "self");
+            state.add_receiver_type(&obj);
+            if json_predicate {
+                // taken before the call, because the method might consume the receiver if it is not a `Value`
+                let negated = state.is_negated();
+                let method_name = method.to_string();
+                let kind = state.create_ident("json_kind");
+                state.setup.extend(quote! {
+                    let #kind = {
+                        use ::one_assert::__macro_utilities::{JsonDetailed as _, JsonFallback as _};
+                        (&&::one_assert::__macro_utilities::JsonValue(&#obj)).__one_assert_json_kind(#method_name, #negated)
+                    };
+                });
+                state.notes.push(kind.to_token_stream());
+            }
+            if method == "is_aligned" && args.is_empty() && turbofish.is_none() {
+                // raw pointers are printed as their address, without being dereferenced
+                state.notes.push(quote! {{
+                    use ::one_assert::__macro_utilities::{PointerAlignmentDetailed as _, PointerAlignmentFallback as _};
+                    (&&::one_assert::__macro_utilities::PointerAlignment(&#obj)).__one_assert_alignment()
+                }});
+            }
+            if (method == "is_ready" || method == "is_pending") && args.is_empty() {
+                // taken before the call, because the method might consume the receiver if it is not a `Poll`
+                let expect_ready = (method == "is_ready") != state.is_negated();
+                let check = state.create_ident("poll_check");
+                state.setup.extend(quote! {
+                    let #check = {
+                        use ::one_assert::__macro_utilities::{PollCheckDetailed as _, PollCheckFallback as _};
+                        (&&::one_assert::__macro_utilities::PollCheck(&#obj, #expect_ready)).__one_assert_poll_check()
+                    };
+                });
+                state.notes.push(check.to_token_stream());
+            }
+            if FLOAT_PREDICATES.iter().any(|name| method == name) && args.is_empty() {
+                // taken before the call, because the method might consume the receiver if it is not a float
+                let class = state.create_ident("float_class");
+                state.setup.extend(quote! {
+                    let #class = {
+                        use ::one_assert::__macro_utilities::{FloatClassDetailed as _, FloatClassFallback as _};
+                        (&&::one_assert::__macro_utilities::FloatClass(&#obj)).__one_assert_float_class()
+                    };
+                });
+                state.notes.push(class.to_token_stream());
+            }
+            let index_len = (args.len().saturating_sub(1)).to_string().len();
+            let out_args = args
+                .into_iter()
+                .enumerate()
+                .map(|(i, arg)| state.add_arg(arg, i, &format!("arg {i:>index_len$}")));
+
+            // output: `quote! { #(attrs)* #obj #dot_token #method #turbofish ( #(#out_args),* ) }` except we want to use the original parentheses for span purposes
+            assert_condition = quote! { #(#attrs)* #obj #dot_token #method #turbofish };
+            paren_token.surround(&mut assert_condition, |out| {
+                out.extend(quote! { #(#out_args),* })
+            });
+        }
+
+        // (expr)
+        syn::Expr::Paren(syn::ExprParen {
+            expr,
+            paren_token,
+            attrs,
+            ..
+        }) => {
+            state
+                .modifiers
+                .push((attrs, ExprModifier::Parenthesized(paren_token)));
+            return eval_expr(*expr, state);
+        }
+
+        // some::path::<of>::stuff
+        syn::Expr::Path(_) => {} // might be a constant of type bool, otherwise let the compiler generate the error
+
+        // a..b
+        syn::Expr::Range(_) => {} // let the compiler generate the error
+
+        // &expr
+        syn::Expr::Reference(_) => {} // let the compiler generate the error
+
+        // [x; n]
+        syn::Expr::Repeat(_) => {
+            // as an operand (`matrix[[0; 2]]`, `[0; 3] == arr`) this is captured by the parent expression.
+            // As the condition itself, it is most likely a typo, and the compiler would just say "expected bool, found array"
+            let msg = "Expected a boolean expression, found an array `[value; length]`. Did you mean to compare it with `==` or check its elements with `.iter().all(...)`?";
+            return Error::err_spanned(e, msg); // checked in tests/fail/expr/repeat.rs
+        }
+
+        // return expr
+        syn::Expr::Return(_) => {
+            // we need to generate our own error, because return returns `!` so it compiles, but the assertion makes no sense
+            let msg = "Expected a boolean expression, found a return statement";
+            return Error::err_spanned(e, msg); // checked in tests/fail/expr/return.rs
+        }
+
+        // MyStruct { field: value }
+        syn::Expr::Struct(_) => {
+            // we generate our own error, because the compiler will suggest adding parentheses around the struct literal
+            let msg = "Expected a boolean expression, found a struct literal";
+            return Error::err_spanned(e, msg);
+        }
+
+        // expr?
+        syn::Expr::Try(_) => {} // might work if expr is a Result<bool> or similar, otherwise let the compiler generate the error
+
+        // (a, b, c)
+        syn::Expr::Tuple(_) => {} // let the compiler generate the error
+
+        // !(a == b) with the `simplify_negation` option
+        syn::Expr::Unary(syn::ExprUnary {
+            ref expr,
+            op: syn::UnOp::Not(_),
+            ..
+        }) if state.options.simplify_negation && inverted_comparison(expr).is_some() => {
+            // the message already shows `a >= b` (see `simplify_negations`). The evaluation stays `!(a < b)`,
+            // which is not the same as `a >= b` for values like NaN
+            let syn::Expr::Unary(syn::ExprUnary {
+                expr,
+                op: syn::UnOp::Not(not_token),
+                attrs,
+            }) = e
+            else {
+                unreachable!()
+            };
+            state
+                .modifiers
+                .push((attrs, ExprModifier::Negated(not_token)));
+            return eval_expr(*expr, state);
+        }
+        // !expr
+        syn::Expr::Unary(syn::ExprUnary {
+            expr,
+            op: syn::UnOp::Not(not_token),
+            attrs,
+        }) => {
+            // praying that people didn't override the `Not` operator for their types
+            state
+                .modifiers
+                .push((attrs, ExprModifier::Negated(not_token)));
+            state.add_var(
+                syn::Expr::Lit(syn::ExprLit {
+                    attrs: vec![],
+                    lit: syn::Lit::Bool(syn::LitBool::new(true, Span::call_site())),
+                }),
+                "negated",
+                "assertion negated",
+            );
+            return eval_expr(*expr, state);
+        }
+        // op expr
+        syn::Expr::Unary(syn::ExprUnary { expr, op, attrs }) => {
+            let original = state.add_var(*expr, "original", "original");
+            assert_condition = quote! { #(#attrs)* #op #original };
+        }
+
+        // unsafe { ... }
+        syn::Expr::Unsafe(syn::ExprUnsafe {
+            block,
+            attrs,
+            unsafe_token,
+        }) => {
+            state.possibly_unsafe = quote! { #(#attrs)* #unsafe_token };
+            return eval_block(block, vec![], state);
+        }
+
+        // something that even syn doesn't know, so we can't do anything with it.
+        // Generated code from try_assert is also stored as Verbatim, but that one doesn't need a note
+        syn::Expr::Verbatim(tokens) if !state.display_replacements.contains(&tokens) => {
+            let note = "note: no values were captured, because this syntax is not supported yet";
+            state
+                .notes
+                .push(quote! { ::std::string::String::from(#note) });
+        }
+
+        // while cond { ... }
+        syn::Expr::While(_) => {
+            // we generate our own error, because the compiler just says "expected bool, found ()"
+            let msg = "Expected a boolean expression, found a while loop";
+            return Error::err_spanned(e, msg);
+        }
+
+        _ => {} // we don't know what this is, so we can't do anything with it
+                // this includes unstable syntax that is already contained in syn, like
+                // syn::Expr::TryBlock
+                // syn::Expr::Yield
+    }
+
+    Ok(state.finish(assert_condition))
+}
+
+/// Capture every level of a chain of field accesses and method calls like `config.server().tls` with the
+/// `verbose` option, and return the code that accesses the value of `expr`.
+///
+/// Each object that a field or method is accessed on is printed, named after its code. The arguments of method
+/// calls are captured as usual, and the results of the calls are stored in variables, so that nothing is
+/// evaluated twice. If the chain calls `unwrap()` or `expect(...)` on a `None` or `Err`, the assertion fails
+/// with a note about it, instead of panicking inside of the call.
+fn capture_field_chain(expr: syn::Expr, state: &mut State) -> TokenStream {
+    match expr {
+        syn::Expr::Field(syn::ExprField {
+            attrs,
+            base,
+            dot_token,
+            member,
+        }) => {
+            let base = capture_field_level(*base, state);
+            quote! { #(#attrs)* #base #dot_token #member }
+        }
+        syn::Expr::MethodCall(syn::ExprMethodCall {
+            attrs,
+            receiver,
+            dot_token,
+            method,
+            turbofish,
+            paren_token,
+            args,
+        }) => {
+            let receiver_str = state.display_string(&receiver);
+            let receiver = capture_field_level(*receiver, state);
+
+            let unwraps =
+                (method == "unwrap" && args.is_empty()) || (method == "expect" && args.len() == 1);
+            if unwraps && turbofish.is_none() {
+                let description = state.create_ident("unwrap_failure");
+                let mut failure = state.fork();
+                let note = format!("`{receiver_str}` is {{}}, so `{method}` would panic");
+                failure
+                    .notes
+                    .push(quote! { ::std::format!(#note, #description) });
+                let report = failure.finish_failure(); // the call would panic, so this fails no matter the condition
+                state.setup.extend(quote! {
+                    if let ::std::option::Option::Some(#description) = {
+                        use ::one_assert::__macro_utilities::{UnwrapFailure as _, UnwrapFailureFallback as _};
+                        (&&::one_assert::__macro_utilities::UnwrapCheck(&#receiver)).__one_assert_unwrap_failure()
+                    } {
+                        #report
+                    }
+                });
+            }
+
+            let out_args = args.into_iter().map(|arg| {
+                if let syn::Expr::Lit(_) = arg {
+                    arg.into_token_stream() // the value is already visible in the code
+                } else {
+                    let display = state.display_string(&arg);
+                    state.add_var(arg, "arg", &display)
+                }
+            });
+            let mut call = quote! { #(#attrs)* #receiver #dot_token #method #turbofish };
+            paren_token.surround(&mut call, |out| out.extend(quote! { #(#out_args),* }));
+            state.bind_var(syn::Expr::Verbatim(call), "level")
+        }
+        syn::Expr::Path(_) => expr.into_token_stream(),
+        expr => state.bind_var(expr, "level"),
+    }
+}
+
+/// Checks if an operand is a chain for [`capture_field_chain`] with the `verbose` option: a field access, optionally
+/// followed by method calls, like `config.port.unwrap()`
+fn is_field_chain(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Field(field) => field.attrs.is_empty(),
+        syn::Expr::MethodCall(call) => call.attrs.is_empty() && is_field_chain(&call.receiver),
+        _ => false,
+    }
+}
+
+/// Capture a level of [`capture_field_chain`] and print its value
+fn capture_field_level(expr: syn::Expr, state: &mut State) -> TokenStream {
+    let display = state.display_string(&expr);
+    let access = capture_field_chain(expr, state);
+    // the level is only borrowed for printing, since the next level might only access a part of it
+    state.add_var_bound_with(
+        syn::Expr::Verbatim(access),
+        "level",
+        &display,
+        State::bind_print_only,
+        false,
+    )
+}
+
+fn eval_block(
+    mut block: syn::Block,
+    attrs: Vec<syn::Attribute>,
+    mut state: State,
+) -> Result<TokenStream> {
+    state.resolve_variables();
+
+    let original_tokens = quote! { #(#attrs)* #block };
+
+    let expr = match block.stmts.pop() {
+        Some(syn::Stmt::Expr(expr, None)) => expr,
+        // without a tail expression, the block would evaluate to `()` and the compiler would only complain
+        // about the type. `{ let x = 1; x == 1; }` is easy to write by accident, so the error names the problem
+        Some(syn::Stmt::Expr(_, Some(semi)))
+        | Some(syn::Stmt::Macro(syn::StmtMacro {
+            semi_token: Some(semi),
+            ..
+        })) => {
+            let msg = "Expected the block to end in a boolean expression, but its last statement ends with a `;`. Remove the `;` to use the expression as the condition";
+            return Error::err_spanned(semi, msg); // checked in tests/fail/expr/block.rs
+        }
+        Some(syn::Stmt::Local(local)) => {
+            let msg = "Expected the block to end in a boolean expression, but it ends with a `let` statement";
+            return Error::err_spanned(local, msg); // checked in tests/fail/expr/block.rs
+        }
+        Some(syn::Stmt::Item(item)) => {
+            let msg = "Expected the block to end in a boolean expression, but it ends with an item";
+            return Error::err_spanned(item, msg); // checked in tests/fail/expr/block.rs
+        }
+        None => {
+            let msg = "Expected the block to end in a boolean expression, but it is empty";
+            return Error::err_spanned(original_tokens, msg); // checked in tests/fail/expr/block.rs
+        }
+        // a macro call like `matches!(x, ...)` at the end of the block, which is parsed as a statement
+        Some(syn::Stmt::Macro(_)) => {
+            let allow_unused = state.allow_unused();
+            let State {
+                setup,
+                possibly_unsafe,
+                ..
+            } = state;
+            return Ok(quote! {
+                #allow_unused
+                #possibly_unsafe {
+                    #setup
+                    if #original_tokens {}
+                }
+            });
+        }
+    };
+
+    let condition_str = state.display_string(&expr);
+    state.add_cause(&wording::block_return(&condition_str));
+
+    state
+        .modifiers
+        .push((attrs, ExprModifier::Blocked(block.brace_token)));
+
+    for stmt in block.stmts {
+        stmt.to_tokens(&mut state.setup);
+    }
+
+    eval_expr(expr, state)
+}
+
+fn setup_if(branch: syn::ExprIf, mut state: State) -> Result<TokenStream> {
+    let syn::ExprIf {
+        cond,
+        then_branch,
+        attrs,
+        if_token,
+        else_branch: Some((else_token, else_branch)),
+    } = branch
+    else {
+        return Ok(branch.to_token_stream()); // if without else: let the compiler generate the error
+    };
+
+    let condition_str = state.display_string(&cond);
+    let condition = state.add_var(*cond, "condition", &format!("condition `{condition_str}`"));
+
+    let then_branch = eval_block(then_branch, vec![], state.fork())?;
+    let else_branches = recurse_else_branches(*else_branch, state.fork())?;
+
+    state.resolve_variables(); // only resolve variables after the recursive calls so that the forks can align the conditions
+
+    let State { setup, .. } = state;
+
+    Ok(quote! {
+        {
+            #setup
+            #(#attrs)* #if_token #condition {
+                #then_branch
+            } #else_token #else_branches
+        }
+    })
+}
+
+fn recurse_else_branches(branch: syn::Expr, state: State) -> Result<TokenStream> {
+    match branch {
+        // else { ... }
+        syn::Expr::Block(syn::ExprBlock { block, attrs, .. }) => {
+            let body = eval_block(block, attrs, state)?;
+            Ok(quote! { { #body } })
+        }
+
+        // else if cond { ... }
+        syn::Expr::If(expr) => setup_if(expr, state),
+
+        _ => {
+            // docs on syn::ExprIf (in 2.0.71): "The `else` branch expression may only be an `If` or `Block` expression."
+            let msg = "parsing error: expected else block or if-else chain";
+            Error::err_spanned(branch, msg) // should not be reachable, thus not checked
+        }
+    }
+}
+
+/// The opposite comparison of a (possibly parenthesized) `a <op> b`, e.g. `a != b` for `a == b`.
+/// Used to display `!(a == b)` with the `simplify_negation` option.
+fn inverted_comparison(expr: &syn::Expr) -> Option<syn::ExprBinary> {
+    let binary = match expr {
+        syn::Expr::Paren(syn::ExprParen { expr, .. })
+        | syn::Expr::Group(syn::ExprGroup { expr, .. }) => return inverted_comparison(expr),
+        syn::Expr::Binary(binary) => binary,
+        _ => return None,
+    };
+    use syn::BinOp::*;
+    let op = match binary.op {
+        Eq(t) => Ne(syn::Token![!=](t.spans[0])),
+        Ne(t) => Eq(syn::Token![==](t.spans[0])),
+        Lt(t) => Ge(syn::Token![>=](t.spans[0])),
+        Le(t) => Gt(syn::Token![>](t.spans[0])),
+        Gt(t) => Le(syn::Token![<=](t.spans[0])),
+        Ge(t) => Lt(syn::Token![<](t.spans[0])),
+        _ => return None,
+    };
+    Some(syn::ExprBinary {
+        op,
+        ..binary.clone()
+    })
+}
+
+/// Replace every negated comparison like `!(a == b)` in an expression with its opposite (`a != b`), for the
+/// displayed code with the `simplify_negation` option.
+///
+/// The replacement is put in parentheses, unless it is on its own, like the whole condition, an operand of
+/// `&&` or `||` or the condition of an `if`, since it binds less tightly than the `!` it replaces.
+fn simplify_negations(expr: &mut syn::Expr) {
+    struct Simplifier {
+        /// whether the next expression can be a comparison without parentheses
+        standalone: bool,
+    }
+    impl syn::visit_mut::VisitMut for Simplifier {
+        fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+            let standalone = std::mem::take(&mut self.standalone);
+            match expr {
+                syn::Expr::Binary(syn::ExprBinary {
+                    left,
+                    op: syn::BinOp::And(_) | syn::BinOp::Or(_),
+                    right,
+                    ..
+                }) => {
+                    for operand in [left, right] {
+                        self.standalone = true;
+                        self.visit_expr_mut(operand);
+                    }
+                }
+                syn::Expr::If(syn::ExprIf {
+                    cond,
+                    then_branch,
+                    else_branch,
+                    ..
+                }) => {
+                    self.standalone = true;
+                    self.visit_expr_mut(cond);
+                    self.visit_block_mut(then_branch);
+                    if let Some((_, else_branch)) = else_branch {
+                        self.visit_expr_mut(else_branch);
+                    }
+                }
+                syn::Expr::Paren(syn::ExprParen { expr: inner, .. }) => {
+                    self.standalone = true;
+                    self.visit_expr_mut(inner);
+                }
+                _ => syn::visit_mut::visit_expr_mut(self, expr),
+            }
+
+            let syn::Expr::Unary(syn::ExprUnary {
+                expr: inner,
+                op: syn::UnOp::Not(_),
+                ..
+            }) = expr
+            else {
+                return;
+            };
+            if let Some(inverted) = inverted_comparison(inner) {
+                *expr = if standalone {
+                    inverted.into()
+                } else {
+                    syn::parse_quote! { (#inverted) }
+                };
+            }
+        }
+
+        fn visit_stmt_mut(&mut self, stmt: &mut syn::Stmt) {
+            self.standalone = true;
+            syn::visit_mut::visit_stmt_mut(self, stmt);
+        }
+    }
+    syn::visit_mut::VisitMut::visit_expr_mut(&mut Simplifier { standalone: true }, expr);
+}
+
+fn printable_expr_string(expr: &impl ToTokens) -> String {
+    utils::tidy_path_spacing(&expr.to_token_stream().to_string())
+        .replace('{', "{{")
+        .replace('}', "}}")
+}
+
+/// The methods of `f32` and `f64` that check the category of the value, which get a note with the category
+const FLOAT_PREDICATES: &[&str] = &["is_finite", "is_normal", "is_nan", "is_infinite"];
+
+/// The type predicates of `serde_json::Value`, which get a note about the actual type of the value
+const JSON_PREDICATES: &[&str] = &[
+    "is_null",
+    "is_boolean",
+    "is_number",
+    "is_i64",
+    "is_u64",
+    "is_f64",
+    "is_string",
+    "is_array",
+    "is_object",
+];
+
+/// The name of an operand of a binary operator: `default` (`left` or `right`), or the code of a tuple field
+/// access like `pair.0`. Sides that only differ by their index are easier to tell apart by that index.
+/// With `redact`, the code is hidden, so the label is always `default`.
+///
+/// A leading `&` or `&mut` is not part of the label, since it prints the same as the value behind it. The
+/// comparison itself still uses the reference
+fn operand_label(operand: &syn::Expr, default: &str, state: &State) -> String {
+    match operand {
+        syn::Expr::Reference(syn::ExprReference { expr, .. }) => {
+            operand_label(expr, default, state)
+        }
+        syn::Expr::Field(syn::ExprField {
+            base,
+            member: syn::Member::Unnamed(index),
+            ..
+        }) if !state.options.redact.expression() => {
+            let field = utils::FieldIdent::from_index(index.index as usize, index.span);
+            format!("{}.{field}", state.display_string(base))
+        }
+        _ => default.to_owned(),
+    }
+}
+
+/// Split the left side of a chain of the same logical operator like `a && b && c` into its operands and
+/// operators, in source order.
+///
+/// Only the left side is followed, since the operators are left-associative: A chain on the right side
+/// has to be in parentheses, and a different operator (like `a || b && c`) binds more or less tightly and
+/// forms a single operand.
+fn flatten_logical_chain(
+    expr: syn::Expr,
+    chain_op: &syn::BinOp,
+    operands: &mut Vec<syn::Expr>,
+    ops: &mut Vec<syn::BinOp>,
+) {
+    match expr {
+        syn::Expr::Binary(syn::ExprBinary {
+            left,
+            op,
+            right,
+            attrs,
+        }) if attrs.is_empty()
+            && std::mem::discriminant(&op) == std::mem::discriminant(chain_op) =>
+        {
+            flatten_logical_chain(*left, chain_op, operands, ops);
+            operands.push(*right);
+            ops.push(op);
+        }
+        expr => operands.push(expr),
+    }
+}
+
+/// Wrap the closure of an `all` or `any` call in a closure that passes every result of the original one to the
+/// `recorder`, together with a copy of the element. The element is bound to the original parameter pattern in
+/// an inner closure, so that a `return` in the body still returns from the predicate
+fn wrap_predicate(
+    predicate: syn::ExprClosure,
+    recorder: &syn::Ident,
+    stop_at: bool,
+    state: &mut State,
+) -> TokenStream {
+    let syn::ExprClosure {
+        attrs,
+        lifetimes,
+        constness,
+        movability,
+        capture,
+        inputs,
+        output,
+        body,
+        ..
+    } = predicate;
+    let element = state.create_ident("element");
+    let kept = state.create_ident("kept");
+    let (pattern, param) = match inputs.into_iter().next() {
+        Some(syn::Pat::Type(syn::PatType { pat, ty, .. })) => (*pat, quote! { #element: #ty }),
+        Some(pattern) => (pattern, element.to_token_stream()),
+        None => unreachable!("checked in the match guard"),
+    };
+    let inner_body = match output {
+        syn::ReturnType::Default => quote! { { let #pattern = #element; #body } },
+        output => quote! { #output { let #pattern = #element; #body } },
+    };
+    quote! {
+        #(#attrs)* #lifetimes #constness #movability #capture |#param| {
+            let #kept = {
+                use ::one_assert::__macro_utilities::{KeepElementDetailed as _, KeepElementFallback as _};
+                (&&::one_assert::__macro_utilities::KeepElement(&#element)).__one_assert_keep()
+            };
+            #recorder.check((|| #inner_body)(), #stop_at, || {
+                use ::one_assert::__macro_utilities::{DescribeKeptDetailed as _, DescribeKeptFallback as _};
+                (&&::one_assert::__macro_utilities::DescribeKept(&#kept)).__one_assert_describe()
+            })
+        }
+    }
+}
+
+/// Capture an operand of a `&&` or `||` chain and return a `bool` expression for it.
+///
+/// Comparisons print both of their sides instead of the resulting `bool`, like `operand 1 left` and
+/// `operand 1 right`, and nested chains in parentheses or with the other operator are split further into
+/// `operand 1.1`, `operand 1.2` and so on. Everything else is printed as a single `bool`
+fn add_logical_operand(
+    operand: syn::Expr,
+    identifier: &str,
+    display: &str,
+    state: &mut State,
+) -> TokenStream {
+    let inner = match operand {
+        syn::Expr::Paren(syn::ExprParen { attrs, expr, .. }) if attrs.is_empty() => *expr,
+        operand => operand,
+    };
+    match inner {
+        syn::Expr::Binary(syn::ExprBinary {
+            left,
+            op,
+            right,
+            attrs,
+        }) if attrs.is_empty() && is_comparison(&op) => {
+            state.warn_duplicate_operands(&left, &right);
+            let left_label = operand_label(&left, &format!("{display} left"), state);
+            let right_label = operand_label(&right, &format!("{display} right"), state);
+            let lhs =
+                state.add_comparison_operand(*left, &format!("{identifier}_lhs"), &left_label);
+            let rhs =
+                state.add_comparison_operand(*right, &format!("{identifier}_rhs"), &right_label);
+            // the comparison is only evaluated once, the result is used for the note and the condition
+            let result = state.create_ident(identifier);
+            state.setup.extend(quote! { let #result = #lhs #op #rhs; });
+            result.into_token_stream()
+        }
+        syn::Expr::Binary(syn::ExprBinary {
+            left,
+            op: op @ (syn::BinOp::And(_) | syn::BinOp::Or(_)),
+            right,
+            attrs,
+        }) if attrs.is_empty() => {
+            let mut operands = vec![];
+            let mut ops = vec![];
+            flatten_logical_chain(*left, &op, &mut operands, &mut ops);
+            operands.push(*right);
+            ops.push(op);
+            let vars: Vec<_> = operands
+                .into_iter()
+                .enumerate()
+                .map(|(i, operand)| {
+                    let identifier = format!("{identifier}_{i}");
+                    let display = format!("{display}.{}", i + 1);
+                    add_logical_operand(operand, &identifier, &display, state)
+                })
+                .collect();
+            let first = &vars[0];
+            let rest = ops
+                .iter()
+                .zip(&vars[1..])
+                .map(|(op, var)| quote! { #op #var });
+            quote! { (#first #(#rest)*) }
+        }
+        inner => state.add_var(inner, identifier, display),
+    }
+}
+
+/// Checks if `op` is one of the comparison operators, which only borrow their operands
+fn is_comparison(op: &syn::BinOp) -> bool {
+    use syn::BinOp::*;
+    matches!(op, Eq(_) | Ne(_) | Lt(_) | Le(_) | Gt(_) | Ge(_))
+}
+
+/// Evaluates a comparison between two integer or bool literals like `1 > 2` at compile time.
+///
+/// Returns `None` if the comparison is anything else or can't be evaluated (e.g. mismatched suffixes).
+fn fold_literal_comparison(expr: &syn::ExprBinary) -> Option<bool> {
+    use std::cmp::Ordering;
+
+    let literal = |e: &syn::Expr| match e {
+        syn::Expr::Lit(syn::ExprLit { lit, attrs }) if attrs.is_empty() => Some(lit.clone()),
+        _ => None,
+    };
+    let ordering = match (literal(&expr.left)?, literal(&expr.right)?) {
+        (syn::Lit::Bool(left), syn::Lit::Bool(right)) => left.value.cmp(&right.value),
+        (syn::Lit::Int(left), syn::Lit::Int(right)) => {
+            let (left_suffix, right_suffix) = (left.suffix(), right.suffix());
+            if !left_suffix.is_empty() && !right_suffix.is_empty() && left_suffix != right_suffix {
+                return None; // let the compiler complain about the types
+            }
+            let left = left.base10_parse::<u128>().ok()?;
+            let right = right.base10_parse::<u128>().ok()?;
+            left.cmp(&right)
+        }
+        _ => return None,
+    };
+    Some(match expr.op {
+        syn::BinOp::Eq(_) => ordering == Ordering::Equal,
+        syn::BinOp::Ne(_) => ordering != Ordering::Equal,
+        syn::BinOp::Lt(_) => ordering == Ordering::Less,
+        syn::BinOp::Le(_) => ordering != Ordering::Greater,
+        syn::BinOp::Gt(_) => ordering == Ordering::Greater,
+        syn::BinOp::Ge(_) => ordering != Ordering::Less,
+        _ => return None,
+    })
+}
+
+/// Generate the `panic!` for a failed assertion. With the `eager_print` feature, the message is also printed
+/// to stderr before panicking, in case the panic hook is replaced or the panic is caught and its payload lost
+fn panic_with_message(format_message: &str, args: &[TokenStream]) -> TokenStream {
+    if cfg!(feature = "eager_print") {
+        quote! {{
+            let message = ::std::format!(#format_message, #(#args),*);
+            ::std::eprintln!("{}", message);
+            ::std::panic!("{}", message)
+        }}
+    } else {
+        quote! { ::std::panic!(#format_message, #(#args),*) }
+    }
+}
+
+/// The variable with the custom message of an assertion, see [`State::message_format`]
+fn message_ident() -> syn::Ident {
+    syn::Ident::new("__one_assert_message", Span::mixed_site())
+}
+
+/// The label of the block that `try_assert!` breaks out of with the error, and that a warning from
+/// `level = "warning"` leaves. Hygienic, so that it doesn't clash with labels in the condition
+fn result_label() -> syn::Lifetime {
+    syn::Lifetime::new("'__one_assert_result", Span::mixed_site())
+}
+
+fn assert_true_flavor() -> TokenStream {
+    quote! {
+        let line = ::std::line!();
+        if line % 100 == 69 {
+            ::std::panic!("You actually used `assert!(true)`? Nice.");
+        } else if line % 100 == 0 {
+            ::std::panic!("Congratulations! You are the {}th person to use `assert!(true)`! You win a free panic!", line);
+        } else if line % 10 == 0 {
+            // Have the assertion randomly pass
+        } else {
+            const MESSAGES: &[&'static ::std::primitive::str] = &[
+                "Ha! Did you think `assert!(true)` would do nothing? Fool!",
+                "assertion `true` failed:\n  left: tr\n right: ue",
+                "assertion `true` failed: `true` did not evaluate to true",
+                "assertion `true` failed: `true` did not evaluate to true...? Huh? What? 🤔",
+                "Undefined reference to `true`. Did you mean `false`?",
+                "assertion `true` failed: `true` did not evaluate to true. What a surprise!",
+            ];
+            let msg = MESSAGES[line as usize % MESSAGES.len()];
+            ::std::panic!("{}", msg);
+        }
+    }
+}
+
+// # Span manipulation workaround:
+// Spans cannot be manipulated on stable rust right now (see <https://github.com/rust-lang/rust/issues/54725>).
+// This also applies to getting the full span of an expression, which requires joining the spans of the individual
+// tokens. On stable, .span() will just return the first token, meaning that if you have an expression like
+// `1 + 2` and a compiler error should be printed on the entire expression, it will instead only underline
+// the first token, the `1` in this case.
+// To work around this, the common approach (see syn::Error::new_spanned) is to bind the first and last token
+// of your code to the first and last individual span of the input, so that when the rust compiler wants to
+// underline the "entire" span, it will join the spans for us and underline the entire expression.
+// This requires that the code that should be underlined has more than one token, so that more than one span
+// can be bound to it. This function should create variable names, which are only one token long, so we need
+// to artificially create a multi-token variable. This is the point of the __OneAssertWrapper struct. It simply
+// contains the value of the variable, and any access will be written as `var.0` instead of `var`, giving us
+// the multi-token variable we need.
+//
+// ## Simplified but full example
+//
+// ### Without the span manipulation
+// Input: `assert!(1 + 2);`
+//
+// Output:
+// ```
+// let var = 1 + 2;
+// if var {} else { panic!("assertion failed"); }
+// ```
+//
+// This code would produce a compiler error like this:
+// ```
+// error: mismatched types
+//  1 | assert!(1 + 2);
+//              ^ expected bool, found {integer}
+// ```
+// which is not very helpful, because the error message only points at the first token of the expression.
+//
+// ### With the span manipulation
+// Input: `assert!(1 + 2);`
+//
+// Output:
+// ```
+// let var = __OneAssertWrapper(1 + 2);
+// if var.0 {} else { panic!("assertion failed"); }
+// ```
+// Note that the token-span assignment of the usage of `var.0` is as follows:
+// - `var` is assigned the span of the `1` from the input
+// - `.0` is assigned the span of the `2` from the input
+//
+// Produced error:
+// ```
+// error: mismatched types
+//  1 | assert!(1 + 2);
+//              ^^^^^ expected bool, found {integer}
+// ```
+// As you can see, the compiler wants to underline the full `var.0`, meaning it will end up underlining
+// everything between the original `1` and `2` tokens, which is exactly what we want.
@@ -0,0 +1,426 @@
+use crate::*;
+
+/// The overall layout of the failure message
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Layout {
+    /// `name: value` lines for every captured value
+    #[default]
+    Default,
+    /// A line-by-line diff of the two sides of an `==` comparison, in the style of `pretty_assertions`
+    PrettyAssertions,
+}
+
+/// Where the message of the assertion is printed, from the `message_position = "..."` option
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum MessagePosition {
+    /// After the first line: ``assertion `...` failed: message``
+    #[default]
+    Header,
+    /// After the innermost "caused by" line, which is the check that actually failed
+    Cause,
+}
+
+/// What is hidden from the failure message, from the `redact` and `redact = "..."` options
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Redact {
+    /// Print everything
+    #[default]
+    None,
+    /// Replace the code anywhere in the message with `<redacted>`
+    Expression,
+    /// Replace the printed values with `<redacted>`, but keep the code
+    Values,
+    /// Both of the above
+    All,
+}
+
+impl Redact {
+    /// Checks if the code of the assertion should be hidden
+    pub fn expression(self) -> bool {
+        matches!(self, Redact::Expression | Redact::All)
+    }
+    /// Checks if the captured values should be hidden
+    pub fn values(self) -> bool {
+        matches!(self, Redact::Values | Redact::All)
+    }
+}
+
+/// The severity of an assertion, from the `level = "..."` option
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Level {
+    /// Panic on failure, same as without a level
+    Critical,
+    /// Print the failure message to stderr instead of panicking
+    Warning,
+}
+
+impl Level {
+    /// The name of the level, as it is written in the option and printed in the header
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Critical => "critical",
+            Level::Warning => "warning",
+        }
+    }
+}
+
+/// Optional keywords that can be passed to the `assert` macro between the condition and the message
+#[derive(Default)]
+pub(crate) struct Options {
+    /// `layout = "..."`
+    pub layout: Layout,
+    /// `clean`: remove noise like `PhantomData<...>` from the printed values
+    pub clean: bool,
+    /// `warn_unused`: don't put `#[allow(unused)]` on the generated code
+    pub warn_unused: bool,
+    /// `warn_duplicates`: warn about comparisons that evaluate the same call on both sides
+    pub warn_duplicates: bool,
+    /// `precision = N`: print float values with `{:.N?}`
+    pub precision: Option<usize>,
+    /// `simplify_negation`: display `!(a == b)` as `a != b` etc. instead of printing "assertion negated"
+    pub simplify_negation: bool,
+    /// `module`: add the `module_path!()` of the assertion to the header
+    pub module: bool,
+    /// `types`: print the types of method call receivers
+    pub types: bool,
+    /// `level = "..."`: tag the assertion with a severity, which also decides if it panics
+    pub level: Option<Level>,
+    /// `discriminant`: print the discriminant of enums in comparisons
+    pub discriminant: bool,
+    /// `message_position = "..."`
+    pub message_position: MessagePosition,
+    /// `spans`: print the source location of every captured value
+    pub spans: bool,
+    /// `fmt_with = path`: print captured values with `path(&value)` instead of `Debug`
+    pub fmt_with: Option<syn::Path>,
+    /// `verbose`: print the intermediate objects of field access chains
+    pub verbose: bool,
+    /// `fingerprint`: print a hash of both sides of `==` and `!=` comparisons
+    pub fingerprint: bool,
+    /// `dedup`: collapse runs of numbered values with the same value into a single line
+    pub dedup: bool,
+    /// `capture = [0, 2]`: only print the arguments of calls at these positions, with the span of each position
+    pub capture: Option<Vec<(usize, Span)>>,
+    /// `captures = [x, y]`: the variables that the called closure captures, printed by the call
+    pub captures: Vec<syn::Expr>,
+    /// `deref`: print the innermost value of smart pointers next to the pointers
+    pub deref: bool,
+    /// `show_result`: print the value that the condition evaluated to
+    pub show_result: bool,
+    /// `auto_context`: print every local variable that the condition reads
+    pub auto_context: bool,
+    /// `redact` or `redact = "..."`: hide the code or the values of the assertion from the message
+    pub redact: Redact,
+    /// Set by `try_assert!` instead of being written by the user: evaluate to a `Result` instead of panicking
+    pub return_error: bool,
+    /// Set by a `label:` in front of the condition instead of being written as an option: added to the header
+    pub label: Option<syn::Ident>,
+    /// Set by the `actual = ..., expected = ...` form instead of being written as an option: the names of the
+    /// two sides of the comparison
+    pub operand_labels: Option<(&'static str, &'static str)>,
+}
+
+impl Options {
+    /// Checks if the argument at `position` of a call should be printed, see the `capture` option
+    pub fn captures_arg(&self, position: usize) -> bool {
+        self.capture
+            .as_ref()
+            .map_or(true, |capture| capture.iter().any(|(i, _)| *i == position))
+    }
+
+    /// Names of all the available options. Used for error messages
+    const NAMES: &'static [&'static str] = &[
+        "layout",
+        "clean",
+        "warn_unused",
+        "warn_duplicates",
+        "precision",
+        "simplify_negation",
+        "module",
+        "types",
+        "level",
+        "discriminant",
+        "message_position",
+        "spans",
+        "fmt_with",
+        "verbose",
+        "fingerprint",
+        "dedup",
+        "capture",
+        "captures",
+        "deref",
+        "show_result",
+        "auto_context",
+        "redact",
+    ];
+
+    /// Checks if the next tokens look like an option (`name`, `name = value`) rather than the message
+    pub fn peek(input: syn::parse::ParseStream) -> bool {
+        let fork = input.fork();
+        if fork.parse::<syn::Ident>().is_err() {
+            return false;
+        }
+        // a message has to start with a string literal or a macro like `concat!`, so an identifier
+        // that is followed by anything else can't be part of the message
+        fork.is_empty()
+            || fork.peek(syn::Token![,])
+            || (fork.peek(syn::Token![=]) && !fork.peek(syn::Token![==]))
+    }
+
+    /// Parse all options up to the message (or the end of the input), including the trailing comma
+    pub fn parse_all(&mut self, input: syn::parse::ParseStream) -> syn::Result<()> {
+        let mut seen = vec![];
+        while Self::peek(input) {
+            let name = input.fork().parse::<syn::Ident>()?;
+            if seen.contains(&name) {
+                let msg = format!("option `{name}` was specified more than once");
+                return Err(syn::Error::new_spanned(name, msg)); // checked in tests/fail/options.rs
+            }
+            self.parse_option(input)?;
+            seen.push(name);
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+        Ok(())
+    }
+
+    /// Parse a single `name` or `name = value` option
+    fn parse_option(&mut self, input: syn::parse::ParseStream) -> syn::Result<()> {
+        let name = input.parse::<syn::Ident>()?;
+        let value = if input.peek(syn::Token![=]) {
+            input.parse::<syn::Token![=]>()?;
+            Some(input.parse::<syn::Expr>()?)
+        } else {
+            None
+        };
+
+        match name.to_string().as_str() {
+            "layout" => {
+                let value = expect_str(&name, value)?;
+                self.layout = match value.value().as_str() {
+                    "default" => Layout::Default,
+                    "pretty_assertions" => Layout::PrettyAssertions,
+                    _ => {
+                        let msg = r#"unknown layout. Expected "default" or "pretty_assertions""#;
+                        return Err(syn::Error::new_spanned(value, msg)); // checked in tests/fail/options.rs
+                    }
+                };
+            }
+            "clean" => {
+                expect_flag(&name, value)?;
+                self.clean = true;
+            }
+            "warn_unused" => {
+                expect_flag(&name, value)?;
+                self.warn_unused = true;
+            }
+            "warn_duplicates" => {
+                expect_flag(&name, value)?;
+                self.warn_duplicates = true;
+            }
+            "precision" => {
+                let value = expect_int(&name, value)?;
+                self.precision = Some(value.base10_parse()?);
+            }
+            "simplify_negation" => {
+                expect_flag(&name, value)?;
+                self.simplify_negation = true;
+            }
+            "module" => {
+                expect_flag(&name, value)?;
+                self.module = true;
+            }
+            "types" => {
+                expect_flag(&name, value)?;
+                self.types = true;
+            }
+            "message_position" => {
+                let value = expect_str(&name, value)?;
+                self.message_position = match value.value().as_str() {
+                    "header" => MessagePosition::Header,
+                    "cause" => MessagePosition::Cause,
+                    _ => {
+                        let msg = r#"unknown message position. Expected "header" or "cause""#;
+                        return Err(syn::Error::new_spanned(value, msg)); // checked in tests/fail/options.rs
+                    }
+                };
+            }
+            "spans" => {
+                expect_flag(&name, value)?;
+                self.spans = true;
+            }
+            "discriminant" => {
+                expect_flag(&name, value)?;
+                self.discriminant = true;
+            }
+            "verbose" => {
+                expect_flag(&name, value)?;
+                self.verbose = true;
+            }
+            "fingerprint" => {
+                expect_flag(&name, value)?;
+                self.fingerprint = true;
+            }
+            "deref" => {
+                expect_flag(&name, value)?;
+                self.deref = true;
+            }
+            "dedup" => {
+                expect_flag(&name, value)?;
+                self.dedup = true;
+            }
+            "auto_context" => {
+                expect_flag(&name, value)?;
+                self.auto_context = true;
+            }
+            "redact" => {
+                self.redact = match value {
+                    None => Redact::Expression,
+                    value => {
+                        let value = expect_str(&name, value)?;
+                        match value.value().as_str() {
+                            "expression" => Redact::Expression,
+                            "values" => Redact::Values,
+                            "all" => Redact::All,
+                            _ => {
+                                let msg = r#"unknown redact mode. Expected "expression", "values" or "all""#;
+                                return Err(syn::Error::new_spanned(value, msg));
+                                // checked in tests/fail/options.rs
+                            }
+                        }
+                    }
+                };
+            }
+            "show_result" => {
+                expect_flag(&name, value)?;
+                self.show_result = true;
+            }
+            "fmt_with" => {
+                self.fmt_with = Some(expect_path(&name, value)?);
+            }
+            "capture" => {
+                let positions = expect_int_list(&name, value)?;
+                let positions = positions.iter().map(|i| Ok((i.base10_parse()?, i.span())));
+                self.capture = Some(positions.collect::<syn::Result<_>>()?);
+            }
+            "captures" => {
+                self.captures = expect_place_list(&name, value)?;
+            }
+            "level" => {
+                let value = expect_str(&name, value)?;
+                self.level = Some(match value.value().as_str() {
+                    "critical" => Level::Critical,
+                    "warning" => Level::Warning,
+                    _ => {
+                        let msg = r#"unknown level. Expected "critical" or "warning""#;
+                        return Err(syn::Error::new_spanned(value, msg)); // checked in tests/fail/options.rs
+                    }
+                });
+            }
+            _ => {
+                let names = utils::list_items(Self::NAMES, |n| format!("`{n}`"));
+                let msg = format!("unknown option `{name}`. Expected one of {names}");
+                return Err(syn::Error::new_spanned(name, msg)); // checked in tests/fail/options.rs
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get the value of a `name = "string"` option
+fn expect_str(name: &syn::Ident, value: Option<syn::Expr>) -> syn::Result<syn::LitStr> {
+    match value {
+        Some(syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(s),
+            ..
+        })) => Ok(s),
+        Some(value) => {
+            let msg = format!("option `{name}` expects a string literal");
+            Err(syn::Error::new_spanned(value, msg))
+        }
+        None => {
+            let msg = format!("option `{name}` expects a value: `{name} = \"...\"`");
+            Err(syn::Error::new_spanned(name, msg))
+        }
+    }
+}
+
+/// Get the value of a `name = 123` option
+fn expect_int(name: &syn::Ident, value: Option<syn::Expr>) -> syn::Result<syn::LitInt> {
+    match value {
+        Some(syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(i),
+            ..
+        })) => Ok(i),
+        Some(value) => {
+            let msg = format!("option `{name}` expects an integer literal");
+            Err(syn::Error::new_spanned(value, msg)) // checked in tests/fail/options.rs
+        }
+        None => {
+            let msg = format!("option `{name}` expects a value: `{name} = 123`");
+            Err(syn::Error::new_spanned(name, msg))
+        }
+    }
+}
+
+/// Get the value of a `name = [1, 2, 3]` option
+fn expect_int_list(name: &syn::Ident, value: Option<syn::Expr>) -> syn::Result<Vec<syn::LitInt>> {
+    let msg = format!("option `{name}` expects a list of integer literals: `{name} = [0, 2]`");
+    let array = match value {
+        Some(syn::Expr::Array(array)) => array,
+        Some(value) => return Err(syn::Error::new_spanned(value, msg)), // checked in tests/fail/options.rs
+        None => return Err(syn::Error::new_spanned(name, msg)),
+    };
+    let ints = array.elems.into_iter().map(|elem| match elem {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(i),
+            ..
+        }) => Ok(i),
+        elem => Err(syn::Error::new_spanned(elem, &msg)),
+    });
+    ints.collect()
+}
+
+/// Get the value of a `name = [x, y.field]` option
+fn expect_place_list(name: &syn::Ident, value: Option<syn::Expr>) -> syn::Result<Vec<syn::Expr>> {
+    let msg = format!("option `{name}` expects a list of variables: `{name} = [x, y]`");
+    let array = match value {
+        Some(syn::Expr::Array(array)) => array,
+        Some(value) => return Err(syn::Error::new_spanned(value, msg)), // checked in tests/fail/options.rs
+        None => return Err(syn::Error::new_spanned(name, msg)),
+    };
+    let places = array.elems.into_iter().map(|elem| match elem {
+        syn::Expr::Path(_) | syn::Expr::Field(_) => Ok(elem),
+        elem => Err(syn::Error::new_spanned(elem, &msg)), // checked in tests/fail/options.rs
+    });
+    places.collect()
+}
+
+/// Get the value of a `name = path::to::item` option
+fn expect_path(name: &syn::Ident, value: Option<syn::Expr>) -> syn::Result<syn::Path> {
+    match value {
+        Some(syn::Expr::Path(syn::ExprPath {
+            path, qself: None, ..
+        })) => Ok(path),
+        Some(value) => {
+            let msg = format!("option `{name}` expects a path to a function");
+            Err(syn::Error::new_spanned(value, msg)) // checked in tests/fail/options.rs
+        }
+        None => {
+            let msg = format!("option `{name}` expects a value: `{name} = path::to::function`");
+            Err(syn::Error::new_spanned(name, msg))
+        }
+    }
+}
+
+/// Make sure that a flag option like `name` has no value
+fn expect_flag(name: &syn::Ident, value: Option<syn::Expr>) -> syn::Result<()> {
+    if let Some(value) = value {
+        let msg = format!("option `{name}` does not take a value");
+        return Err(syn::Error::new_spanned(value, msg)); // checked in tests/fail/options.rs
+    }
+    Ok(())
+}
@@ -0,0 +1,867 @@
+#![allow(dead_code)]
+
+use crate::*;
+
+/// Undo the escaping of `{` and `}` for format strings, see [`printable_expr_string`]
+pub(crate) fn unescape_format(s: &str) -> String {
+    s.replace("{{", "{").replace("}}", "}")
+}
+
+/// Keywords that can be followed by a path like `::std::f64::MAX`, so the space between them has to stay
+const KEYWORDS_BEFORE_PATHS: &[&str] = &[
+    "as", "box", "break", "dyn", "else", "for", "if", "impl", "in", "let", "match", "move", "mut",
+    "return", "while", "yield",
+];
+
+/// Remove the spaces that printing a token stream puts around `::` and into the generic arguments of a
+/// turbofish, so that `foo :: Generic :: < 3 > :: VALUE` becomes `foo::Generic::<3>::VALUE`. The same goes for
+/// the `?` operator, so `a.parse() ? .len()` becomes `a.parse()?.len()`.
+///
+/// Literals are copied unchanged, and everything else (like `vec! [1, 2]`) keeps its spacing.
+pub(crate) fn tidy_path_spacing(code: &str) -> String {
+    let chars: Vec<char> = code.chars().collect();
+    let mut out = String::with_capacity(code.len());
+    // the number of open generic `<`, for the top level and every `{ ... }` inside of generics
+    let mut depths = vec![0usize];
+    let mut after_generics = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let at_token_start = i == 0 || !is_ident_char(chars[i - 1]);
+        if let Some(len) = literal_len(&chars[i..]).filter(|_| at_token_start) {
+            out.extend(&chars[i..i + len]);
+            i += len;
+            after_generics = false;
+            continue;
+        }
+        let depth = depths.last_mut().unwrap();
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == ':' && next == Some(':') {
+            let trimmed = out.trim_end();
+            let word_start = trimmed
+                .rfind(|c: char| !is_ident_char(c))
+                .map_or(0, |i| i + 1);
+            let word = &trimmed[word_start..];
+            if after_generics || (!word.is_empty() && !KEYWORDS_BEFORE_PATHS.contains(&word)) {
+                out.truncate(trimmed.len());
+            }
+            out.push_str("::");
+            i = skip_spaces(&chars, i + 2);
+            if chars.get(i) == Some(&'<') {
+                out.push('<');
+                *depth += 1;
+                i = skip_spaces(&chars, i + 1);
+            }
+            after_generics = false;
+            continue;
+        }
+        if *depth == 0 {
+            if c == '{' {
+                depths.push(0);
+            } else if c == '}' && depths.len() > 1 {
+                depths.pop();
+            }
+            if after_generics && c == ' ' && next == Some('(') {
+                i += 1; // `parse::<u8> ()` => `parse::<u8>()`
+                continue;
+            }
+            if c == ' '
+                && next == Some('?')
+                && out.ends_with(|c| is_ident_char(c) || ")]}?\"'".contains(c))
+            {
+                i += 1; // `x ?` => `x?`, but not `T: ?Sized`
+                continue;
+            }
+            if c == ' ' && out.ends_with('?') && next.is_some_and(|c| ".;?,)]".contains(c)) {
+                i += 1; // `x? .len()` => `x?.len()`
+                continue;
+            }
+            after_generics &= c == ' ';
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        match c {
+            '{' => depths.push(0),
+            ' ' if next == Some('>') => {
+                i += 1;
+                continue;
+            }
+            ' ' if next == Some('<') && out.ends_with(is_ident_char) => {
+                // generic arguments of a type like `Vec<u8>`
+                out.push('<');
+                *depth += 1;
+                i = skip_spaces(&chars, i + 2);
+                continue;
+            }
+            '>' if !out.ends_with('-') => {
+                out.push('>');
+                *depth -= 1;
+                after_generics = *depth == 0;
+                i += 1;
+                continue;
+            }
+            '-' | '&' if next == Some(' ') && (out.ends_with('<') || out.ends_with(", ")) => {
+                // prefixes like `-1` or `&str`
+                out.push(c);
+                i += 2;
+                continue;
+            }
+            _ => {}
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn skip_spaces(chars: &[char], mut i: usize) -> usize {
+    while chars.get(i) == Some(&' ') {
+        i += 1;
+    }
+    i
+}
+
+/// The length of the string, byte string, raw string or char literal at the start of `chars`, if there is one.
+/// Lifetimes like `'a` are not literals
+fn literal_len(chars: &[char]) -> Option<usize> {
+    let prefix = chars
+        .iter()
+        .take_while(|c| matches!(c, 'b' | 'c' | 'r'))
+        .count();
+    let hashes = chars[prefix..].iter().take_while(|c| **c == '#').count();
+    let raw = prefix > 0 && chars[prefix - 1] == 'r';
+    if prefix > 2 || (hashes > 0 && !raw) {
+        return None;
+    }
+    let start = prefix + hashes;
+    match chars.get(start)? {
+        '"' if raw => {
+            let end = (start + 1..chars.len()).find(|&i| {
+                chars[i] == '"'
+                    && chars[i + 1..].iter().take_while(|c| **c == '#').count() >= hashes
+            })?;
+            Some(end + 1 + hashes)
+        }
+        '"' => {
+            let mut i = start + 1;
+            while *chars.get(i)? != '"' {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            Some(i + 1)
+        }
+        '\'' if !raw && hashes == 0 => {
+            if chars.get(start + 1) == Some(&'\\') {
+                let end = (start + 3..chars.len()).find(|&i| chars[i] == '\'')?;
+                Some(end + 1)
+            } else if chars.get(start + 2) == Some(&'\'') {
+                Some(start + 3)
+            } else {
+                None // a lifetime
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A workaround for Spans on stable Rust.
+///
+/// Span manipulation doesn't work on stable Rust, which also means that spans cannot be joined
+/// together. This means that any compiler errors that occur would only point at the first token
+/// of the spanned expression, which is not very helpful.
+///
+/// The workaround, as demonstrated by `syn::Error::new_spanned`, is to have the first part of the
+/// spanned expression be spanned with the first part of the source span, and the second part of the
+/// spanned expression be spanned with the second part of the source span. The compiler only looks
+/// at the start and end of the span and underlines everything in between, so this works.
+#[derive(Copy, Clone)]
+pub(crate) struct FullSpan(Span, Span);
+
+impl FullSpan {
+    pub fn from_span(span: Span) -> Self {
+        Self(span, span)
+    }
+    pub fn from_spanned<T: ToTokens + syn::spanned::Spanned>(span: &T) -> Self {
+        let start = span.span();
+        let end = span
+            .to_token_stream()
+            .into_iter()
+            .last()
+            .map(|t| t.span())
+            .unwrap_or(start);
+        Self(start, end)
+    }
+    pub fn start(self) -> Span {
+        self.0
+    }
+    /// Describe where the span is in the source code, like `col 5-12` or `line 3 col 5 - line 4 col 2`.
+    ///
+    /// Columns are 1-based and inclusive, same as in compiler messages. Returns `None` if the compiler
+    /// doesn't provide locations, which is the case before Rust 1.88.
+    pub fn source_location(self) -> Option<String> {
+        let (start, end) = (self.0.start(), self.1.end());
+        if start.line == 0 {
+            return None;
+        }
+        let (first, last) = (start.column + 1, end.column.max(start.column + 1));
+        Some(if start.line != end.line {
+            format!(
+                "line {} col {first} - line {} col {last}",
+                start.line, end.line
+            )
+        } else if first == last {
+            format!("col {first}")
+        } else {
+            format!("col {first}-{last}")
+        })
+    }
+    pub fn apply(self, a: TokenStream, b: TokenStream) -> TokenStream {
+        let mut ret = a.with_span(self.0);
+        ret.extend(b.with_span(self.1));
+        ret
+    }
+}
+
+pub(crate) enum FieldIdent {
+    Named(syn::Ident),
+    Index(proc_macro2::Literal),
+}
+impl FieldIdent {
+    pub fn from_index(i: usize, span: Span) -> FieldIdent {
+        let mut literal = proc_macro2::Literal::usize_unsuffixed(i);
+        literal.set_span(span);
+        FieldIdent::Index(literal)
+    }
+}
+impl ToTokens for FieldIdent {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            FieldIdent::Named(ident) => tokens.extend(quote! { #ident }),
+            FieldIdent::Index(index) => tokens.extend(quote! { #index }),
+        }
+    }
+}
+impl std::fmt::Display for FieldIdent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldIdent::Named(ident) => write!(f, "{}", ident),
+            FieldIdent::Index(index) => write!(f, "{}", index),
+        }
+    }
+}
+
+/// Format a list of items as a comma-separated list, with "or" before the last item.
+pub(crate) fn list_items<T>(items: &[T], mut display: impl FnMut(&T) -> String) -> String {
+    match items {
+        [] => String::new(),
+        [x] => display(x),
+        [a, b] => format!("{} or {}", display(a), display(b)),
+        [start @ .., last] => {
+            let mut s = String::new();
+            for item in start {
+                s += &display(item);
+                s += ", ";
+            }
+            s += "or ";
+            s += &display(last);
+            s
+        }
+    }
+}
+
+/// Extension trait for [`TokenStream`] that allows setting the span of all tokens in the stream.
+pub(crate) trait TokenStreamExt {
+    fn set_span(&mut self, span: Span);
+    fn with_span(self, span: Span) -> Self;
+}
+impl TokenStreamExt for TokenStream {
+    fn set_span(&mut self, span: Span) {
+        let old = std::mem::replace(self, TokenStream::new());
+        *self = old.with_span(span);
+    }
+    fn with_span(self, span: Span) -> Self {
+        self.into_iter()
+            .map(|mut t| {
+                if let proc_macro2::TokenTree::Group(ref mut g) = t {
+                    *g = proc_macro2::Group::new(g.delimiter(), g.stream().with_span(span));
+                }
+                t.set_span(span);
+                t
+            })
+            .collect()
+    }
+}
+
+/// The attributes of an expression, if it can have any
+pub(crate) fn expr_attrs_mut(expr: &mut syn::Expr) -> Option<&mut Vec<syn::Attribute>> {
+    use syn::Expr::*;
+    let attrs = match expr {
+        Array(e) => &mut e.attrs,
+        Assign(e) => &mut e.attrs,
+        Async(e) => &mut e.attrs,
+        Await(e) => &mut e.attrs,
+        Binary(e) => &mut e.attrs,
+        Block(e) => &mut e.attrs,
+        Break(e) => &mut e.attrs,
+        Call(e) => &mut e.attrs,
+        Cast(e) => &mut e.attrs,
+        Closure(e) => &mut e.attrs,
+        Const(e) => &mut e.attrs,
+        Continue(e) => &mut e.attrs,
+        Field(e) => &mut e.attrs,
+        ForLoop(e) => &mut e.attrs,
+        Group(e) => &mut e.attrs,
+        If(e) => &mut e.attrs,
+        Index(e) => &mut e.attrs,
+        Infer(e) => &mut e.attrs,
+        Let(e) => &mut e.attrs,
+        Lit(e) => &mut e.attrs,
+        Loop(e) => &mut e.attrs,
+        Macro(e) => &mut e.attrs,
+        Match(e) => &mut e.attrs,
+        MethodCall(e) => &mut e.attrs,
+        Paren(e) => &mut e.attrs,
+        Path(e) => &mut e.attrs,
+        Range(e) => &mut e.attrs,
+        Reference(e) => &mut e.attrs,
+        Repeat(e) => &mut e.attrs,
+        Return(e) => &mut e.attrs,
+        Struct(e) => &mut e.attrs,
+        Try(e) => &mut e.attrs,
+        TryBlock(e) => &mut e.attrs,
+        Tuple(e) => &mut e.attrs,
+        Unary(e) => &mut e.attrs,
+        Unsafe(e) => &mut e.attrs,
+        While(e) => &mut e.attrs,
+        Yield(e) => &mut e.attrs,
+        _ => return None, // Verbatim and anything that syn adds in the future
+    };
+    Some(attrs)
+}
+
+/// How a captured value is printed instead of `Debug`, from an attribute on the operand or the `fmt_with` option
+pub(crate) enum ValueFormat {
+    /// `#[fmt_with(path)]`: `path(&value)`
+    With(syn::Path),
+    /// `#[display]`: `{}` instead of `{:?}`
+    Display,
+}
+
+/// The names of the attributes that choose a [`ValueFormat`]
+const VALUE_FORMAT_ATTRS: [&str; 2] = ["fmt_with", "display"];
+
+/// Remove a `#[fmt_with(path)]` or `#[display]` attribute from an expression and return the format
+pub(crate) fn take_value_format(expr: &mut syn::Expr) -> syn::Result<Option<ValueFormat>> {
+    let Some(attrs) = expr_attrs_mut(expr) else {
+        return Ok(None);
+    };
+    let mut format = None;
+    let mut error = None;
+    attrs.retain(|attr| {
+        let Some(name) = VALUE_FORMAT_ATTRS
+            .into_iter()
+            .find(|name| attr.path().is_ident(name))
+        else {
+            return true;
+        };
+        let result = match &format {
+            Some(ValueFormat::With(_)) if name == "fmt_with" => {
+                let msg = "`fmt_with` was specified more than once for this value";
+                Err(syn::Error::new_spanned(attr, msg)) // checked in tests/fail/fmt_with.rs
+            }
+            Some(ValueFormat::Display) if name == "display" => {
+                let msg = "`display` was specified more than once for this value";
+                Err(syn::Error::new_spanned(attr, msg))
+            }
+            Some(_) => {
+                let msg = "`fmt_with` and `display` can't be combined for the same value";
+                Err(syn::Error::new_spanned(attr, msg)) // checked in tests/fail/fmt_with.rs
+            }
+            None if name == "fmt_with" => attr
+                .parse_args::<syn::Path>()
+                .map(|path| format = Some(ValueFormat::With(path))),
+            None => match &attr.meta {
+                syn::Meta::Path(_) => {
+                    format = Some(ValueFormat::Display);
+                    Ok(())
+                }
+                meta => {
+                    let msg = "`display` does not take any arguments: #[display]";
+                    Err(syn::Error::new_spanned(meta, msg)) // checked in tests/fail/fmt_with.rs
+                }
+            },
+        };
+        if let Err(e) = result {
+            error.get_or_insert(e);
+        }
+        false
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(format),
+    }
+}
+
+/// Remove all `#[fmt_with(...)]` and `#[display]` attributes from an expression and its sub-expressions
+pub(crate) fn strip_value_formats(expr: &mut syn::Expr) {
+    struct Stripper;
+    impl syn::visit_mut::VisitMut for Stripper {
+        fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+            if let Some(attrs) = expr_attrs_mut(expr) {
+                attrs.retain(|attr| {
+                    !VALUE_FORMAT_ATTRS
+                        .into_iter()
+                        .any(|name| attr.path().is_ident(name))
+                });
+            }
+            syn::visit_mut::visit_expr_mut(self, expr);
+        }
+    }
+    syn::visit_mut::VisitMut::visit_expr_mut(&mut Stripper, expr);
+}
+
+/// Check if evaluating an expression calls any code, like a function, a method or a macro. Closures are not
+/// called just by being created, so their bodies are skipped. Calls of paths that start with an uppercase
+/// letter like `Some(x)` are assumed to create a tuple struct or variant, which doesn't run any code
+pub(crate) fn contains_call(expr: &syn::Expr) -> bool {
+    struct Finder(bool);
+    impl syn::visit_mut::VisitMut for Finder {
+        fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+            match expr {
+                syn::Expr::Call(syn::ExprCall { func, .. }) if !is_constructor(func) => {
+                    self.0 = true
+                }
+                syn::Expr::MethodCall(_) | syn::Expr::Macro(_) => self.0 = true,
+                syn::Expr::Closure(_) => {}
+                _ => syn::visit_mut::visit_expr_mut(self, expr),
+            }
+        }
+    }
+    fn is_constructor(func: &syn::Expr) -> bool {
+        let syn::Expr::Path(syn::ExprPath { path, .. }) = func else {
+            return false;
+        };
+        path.segments
+            .last()
+            .is_some_and(|name| name.ident.to_string().starts_with(char::is_uppercase))
+    }
+    let mut finder = Finder(false);
+    syn::visit_mut::VisitMut::visit_expr_mut(&mut finder, &mut expr.clone());
+    finder.0
+}
+
+/// The largest number of arguments of any function or method call in an expression, to check the positions
+/// of the `capture` option. Calls inside of closures are skipped, since their arguments are never captured
+pub(crate) fn max_call_args(expr: &syn::Expr) -> usize {
+    struct Counter(usize);
+    impl syn::visit_mut::VisitMut for Counter {
+        fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+            match expr {
+                syn::Expr::Call(syn::ExprCall { args, .. })
+                | syn::Expr::MethodCall(syn::ExprMethodCall { args, .. }) => {
+                    self.0 = self.0.max(args.len())
+                }
+                syn::Expr::Closure(_) => return,
+                _ => {}
+            }
+            syn::visit_mut::visit_expr_mut(self, expr);
+        }
+    }
+    let mut counter = Counter(0);
+    syn::visit_mut::VisitMut::visit_expr_mut(&mut counter, &mut expr.clone());
+    counter.0
+}
+
+/// Borrow an expression: `expr` becomes `&expr`, with the `&` at the start of the expression
+pub(crate) fn reference_to(expr: syn::Expr) -> syn::Expr {
+    let start = FullSpan::from_spanned(&expr).start();
+    syn::Expr::Reference(syn::ExprReference {
+        attrs: vec![],
+        and_token: syn::Token![&](start),
+        mutability: None,
+        expr: Box::new(expr),
+    })
+}
+
+/// The names of all the variables that a pattern binds
+pub(crate) fn pattern_bindings(pat: &syn::Pat) -> Vec<syn::Ident> {
+    struct Collector(Vec<syn::Ident>);
+    impl syn::visit_mut::VisitMut for Collector {
+        fn visit_pat_ident_mut(&mut self, pat: &mut syn::PatIdent) {
+            self.0.push(pat.ident.clone());
+            syn::visit_mut::visit_pat_ident_mut(self, pat);
+        }
+        fn visit_expr_mut(&mut self, _: &mut syn::Expr) {
+            // constants in range patterns etc. don't bind anything
+        }
+    }
+    let mut collector = Collector(vec![]);
+    syn::visit_mut::VisitMut::visit_pat_mut(&mut collector, &mut pat.clone());
+    collector.0
+}
+
+/// The local variables that a condition reads, for the `auto_context` option, in order of their first use.
+///
+/// Only single-segment identifiers that start with a lowercase letter or `_` are returned, since paths like
+/// `module::item` and names like `MAX` or `None` are not local variables. Closures, blocks and `match` arms are
+/// skipped, because they can declare their own variables that shadow the outer ones. Called functions are left
+/// out as well, and so are variables that are bound by a `let` in the condition.
+pub(crate) fn context_locals(expr: &syn::Expr) -> Vec<syn::Ident> {
+    #[derive(Default)]
+    struct Collector {
+        used: Vec<syn::Ident>,
+        bound: Vec<syn::Ident>,
+    }
+    impl syn::visit_mut::VisitMut for Collector {
+        fn visit_expr_closure_mut(&mut self, _: &mut syn::ExprClosure) {}
+        fn visit_block_mut(&mut self, _: &mut syn::Block) {}
+        fn visit_arm_mut(&mut self, _: &mut syn::Arm) {}
+        fn visit_expr_call_mut(&mut self, call: &mut syn::ExprCall) {
+            for arg in &mut call.args {
+                self.visit_expr_mut(arg);
+            }
+        }
+        fn visit_expr_let_mut(&mut self, expr: &mut syn::ExprLet) {
+            self.visit_expr_mut(&mut expr.expr);
+            self.bound.extend(pattern_bindings(&expr.pat));
+        }
+        fn visit_expr_path_mut(&mut self, expr: &mut syn::ExprPath) {
+            let Some(ident) = expr.path.get_ident() else {
+                return;
+            };
+            let local = ident
+                .to_string()
+                .starts_with(|c: char| c == '_' || c.is_lowercase());
+            if local && expr.qself.is_none() && !self.used.contains(ident) {
+                self.used.push(ident.clone());
+            }
+        }
+    }
+    let mut collector = Collector::default();
+    syn::visit_mut::VisitMut::visit_expr_mut(&mut collector, &mut expr.clone());
+    let Collector { mut used, bound } = collector;
+    used.retain(|ident| !bound.contains(ident));
+    used
+}
+
+/// Check if a pattern matches every value, like `_` or a binding like `other`. Bindings that start with an
+/// uppercase letter are assumed to be unit structs, variants or constants like `None`
+pub(crate) fn is_catch_all_pattern(pat: &syn::Pat) -> bool {
+    match pat {
+        syn::Pat::Wild(_) => true,
+        syn::Pat::Ident(syn::PatIdent {
+            ident,
+            subpat: None,
+            ..
+        }) => !ident.to_string().starts_with(char::is_uppercase),
+        syn::Pat::Ident(syn::PatIdent {
+            subpat: Some((_, subpat)),
+            ..
+        }) => is_catch_all_pattern(subpat),
+        syn::Pat::Or(syn::PatOr { cases, .. }) => cases.iter().any(is_catch_all_pattern),
+        syn::Pat::Paren(syn::PatParen { pat, .. }) => is_catch_all_pattern(pat),
+        _ => false,
+    }
+}
+
+/// The operands of the comparisons in a match guard that only read variables from outside of the arm,
+/// like `y` in `_ if y < 5`.
+///
+/// Only variables and their fields are returned, since reading those again in the arm has no side effects.
+/// Operands that use one of the `bindings` of the pattern are left out.
+pub(crate) fn outer_guard_operands(guard: &syn::Expr, bindings: &[syn::Ident]) -> Vec<syn::Expr> {
+    fn is_outer_place(expr: &syn::Expr, bindings: &[syn::Ident]) -> bool {
+        match expr {
+            syn::Expr::Path(syn::ExprPath {
+                path, qself: None, ..
+            }) => path
+                .get_ident()
+                .is_some_and(|ident| !bindings.contains(ident)),
+            syn::Expr::Field(syn::ExprField { base, .. }) => is_outer_place(base, bindings),
+            syn::Expr::Paren(syn::ExprParen { expr, .. }) => is_outer_place(expr, bindings),
+            _ => false,
+        }
+    }
+    fn collect(expr: &syn::Expr, bindings: &[syn::Ident], out: &mut Vec<syn::Expr>) {
+        match expr {
+            syn::Expr::Binary(syn::ExprBinary {
+                left, op, right, ..
+            }) => match op {
+                syn::BinOp::And(_) | syn::BinOp::Or(_) => {
+                    collect(left, bindings, out);
+                    collect(right, bindings, out);
+                }
+                syn::BinOp::Eq(_)
+                | syn::BinOp::Ne(_)
+                | syn::BinOp::Lt(_)
+                | syn::BinOp::Le(_)
+                | syn::BinOp::Gt(_)
+                | syn::BinOp::Ge(_) => {
+                    for operand in [left, right] {
+                        let tokens = operand.to_token_stream().to_string();
+                        let seen = out
+                            .iter()
+                            .any(|e| e.to_token_stream().to_string() == tokens);
+                        if is_outer_place(operand, bindings) && !seen {
+                            out.push((**operand).clone());
+                        }
+                    }
+                }
+                _ => {}
+            },
+            syn::Expr::Paren(syn::ExprParen { expr, .. })
+            | syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Not(_),
+                expr,
+                ..
+            }) => collect(expr, bindings, out),
+            _ => {}
+        }
+    }
+    let mut operands = vec![];
+    collect(guard, bindings, &mut operands);
+    operands
+}
+
+/// Operators that take a left and a right side, and can't be used as a prefix
+const BINARY_ONLY_OPERATORS: &[&str] = &[
+    "==", "!=", "<", "<=", ">", ">=", "+", "/", "%", "&&", "||", "|", "^", "<<", ">>", "=", "+=",
+    "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<=", ">>=",
+];
+/// Operators that are either binary or a prefix, like `-x` or `*x`
+const BINARY_OR_PREFIX_OPERATORS: &[&str] = &["-", "*", "&"];
+
+/// A hint for a common mistake in an expression that failed to parse, based on its first and last tokens
+pub(crate) fn malformed_expr_hint(tokens: &TokenStream) -> Option<String> {
+    let tokens: Vec<proc_macro2::TokenTree> = tokens.clone().into_iter().collect();
+    let is_binary =
+        |op: &str| BINARY_ONLY_OPERATORS.contains(&op) || BINARY_OR_PREFIX_OPERATORS.contains(&op);
+
+    let last = punct_at_end(&tokens);
+    if is_binary(&last) {
+        return Some(format!(
+            "the expression ends with the binary operator `{last}`, so a right-hand side is required"
+        ));
+    } else if last == "!" {
+        return Some("the expression ends with `!`, so an operand is required".to_owned());
+    } else if last == "." {
+        return Some(
+            "the expression ends with `.`, so a field or method name is required".to_owned(),
+        );
+    } else if let Some(proc_macro2::TokenTree::Ident(ident)) = tokens.last() {
+        if ident == "as" {
+            return Some("the expression ends with `as`, so a type is required".to_owned());
+        }
+    }
+
+    let first = punct_at_start(&tokens);
+    if BINARY_ONLY_OPERATORS.contains(&first.as_str()) {
+        return Some(format!(
+            "the expression starts with the binary operator `{first}`, so a left-hand side is required"
+        ));
+    }
+    None
+}
+
+/// The characters of the (possibly multi-character) punctuation at the end of `tokens`, like `==`
+fn punct_at_end(tokens: &[proc_macro2::TokenTree]) -> String {
+    let mut chars = vec![];
+    for (i, token) in tokens.iter().enumerate().rev() {
+        let proc_macro2::TokenTree::Punct(punct) = token else {
+            break;
+        };
+        // only the last character of an operator is `Alone`
+        if i + 1 < tokens.len() && punct.spacing() == proc_macro2::Spacing::Alone {
+            break;
+        }
+        chars.push(punct.as_char());
+    }
+    chars.iter().rev().collect()
+}
+
+/// The characters of the (possibly multi-character) punctuation at the start of `tokens`, like `==`
+fn punct_at_start(tokens: &[proc_macro2::TokenTree]) -> String {
+    let mut op = String::new();
+    for token in tokens {
+        let proc_macro2::TokenTree::Punct(punct) = token else {
+            break;
+        };
+        op.push(punct.as_char());
+        if punct.spacing() == proc_macro2::Spacing::Alone {
+            break;
+        }
+    }
+    op
+}
+
+/// Check that the number of positional placeholders in the message matches the number of arguments.
+///
+/// `format_args!` would check this as well, but its errors point at the generated code. Only messages that
+/// start with a string literal are checked, anything else (like `concat!(...)`) is left to the compiler.
+pub(crate) fn check_format_args(format: &TokenStream) -> syn::Result<()> {
+    let parser = |input: syn::parse::ParseStream| {
+        let literal = input.parse::<syn::LitStr>()?;
+        if !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+        }
+        let args =
+            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated(input)?;
+        Ok((literal, args))
+    };
+    let Ok((literal, args)) = syn::parse::Parser::parse2(parser, format.clone()) else {
+        return Ok(()); // not a plain string literal with arguments
+    };
+    let Some(expected) = count_positional_placeholders(&literal.value()) else {
+        return Ok(()); // malformed format string. The compiler has a better error for that
+    };
+    // named arguments (`name = value`) are only used by named placeholders
+    let given = args
+        .iter()
+        .filter(|arg| !matches!(arg, syn::Expr::Assign(_)))
+        .count();
+    if expected == given {
+        return Ok(());
+    }
+
+    let msg = format!(
+        "the message expects {expected} positional argument{}, but {}",
+        if expected == 1 { "" } else { "s" },
+        match given {
+            0 => "none were given".to_owned(),
+            1 => "1 was given".to_owned(),
+            n => format!("{n} were given"),
+        }
+    );
+    Err(syn::Error::new_spanned(literal, msg)) // checked in tests/fail/format_args.rs
+}
+
+/// Replace the `{condition}` placeholders in the message with the code of the condition. `condition` has to be
+/// escaped for a format string already (see [`printable_expr_string`]).
+///
+/// Only a plain `{condition}` is replaced, an escaped `{{condition}}` stays as it is. A message that uses the
+/// placeholder can't also have a `condition = ...` argument, since it would be unclear which one is meant.
+pub(crate) fn substitute_condition(
+    format: TokenStream,
+    condition: &str,
+) -> syn::Result<TokenStream> {
+    let parser = |input: syn::parse::ParseStream| {
+        let literal = input.parse::<syn::LitStr>()?;
+        let rest = input.parse::<TokenStream>()?;
+        Ok((literal, rest))
+    };
+    let Ok((literal, rest)) = syn::parse::Parser::parse2(parser, format.clone()) else {
+        return Ok(format); // not a plain string literal
+    };
+    let value = literal.value();
+    let mut replaced = String::with_capacity(value.len());
+    let mut found = false;
+    let mut remaining = value.as_str();
+    while let Some(pos) = remaining.find(['{', '}']) {
+        replaced.push_str(&remaining[..pos]);
+        remaining = &remaining[pos..];
+        let skip = if remaining.starts_with("{{") || remaining.starts_with("}}") {
+            2
+        } else if remaining.starts_with("{condition}") {
+            found = true;
+            replaced.push_str(condition);
+            remaining = &remaining["{condition}".len()..];
+            continue;
+        } else {
+            1
+        };
+        replaced.push_str(&remaining[..skip]);
+        remaining = &remaining[skip..];
+    }
+    replaced.push_str(remaining);
+    if !found {
+        return Ok(format);
+    }
+
+    let args_parser = |input: syn::parse::ParseStream| {
+        if !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+        }
+        syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated(input)
+    };
+    let args = syn::parse::Parser::parse2(args_parser, rest.clone())?;
+    for arg in &args {
+        if let syn::Expr::Assign(assign) = arg {
+            if matches!(&*assign.left, syn::Expr::Path(p) if p.path.is_ident("condition")) {
+                let msg = "`{condition}` in the message is replaced with the condition of the assertion, so it can't be combined with a `condition = ...` argument";
+                return Err(syn::Error::new_spanned(assign, msg)); // checked in tests/fail/format_args.rs
+            }
+        }
+    }
+
+    let literal = syn::LitStr::new(&replaced, literal.span());
+    Ok(quote! { #literal #rest })
+}
+
+/// The number of positional arguments that a format string uses, or `None` if it is malformed.
+///
+/// `{}` and `{:?}` take the next argument, `{2}` and `{:1$}` refer to an argument by index, and `{:.*}` takes
+/// the next argument as the precision. Named placeholders like `{name}` don't use positional arguments.
+fn count_positional_placeholders(format: &str) -> Option<usize> {
+    let mut next_implicit = 0;
+    let mut required = 0;
+    let mut use_index = |index: usize| required = required.max(index + 1);
+
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+            }
+            '}' => return None,
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next()? {
+                        '}' => break,
+                        c => placeholder.push(c),
+                    }
+                }
+                let (argument, spec) = placeholder
+                    .split_once(':')
+                    .unwrap_or((placeholder.as_str(), ""));
+
+                // `$` references in the spec, like the `1` in `{:1$}`
+                for reference in spec.split('$').rev().skip(1) {
+                    let digits = reference
+                        .chars()
+                        .rev()
+                        .take_while(char::is_ascii_digit)
+                        .count();
+                    let start = reference.len() - digits;
+                    // `{:x$}` refers to a named argument, which is fine
+                    if digits > 0
+                        && !reference[..start].ends_with(|c: char| c == '_' || c.is_alphabetic())
+                    {
+                        use_index(reference[start..].parse().ok()?);
+                    }
+                }
+                if spec.contains(".*") {
+                    use_index(next_implicit);
+                    next_implicit += 1;
+                }
+
+                let argument = argument.trim();
+                if argument.is_empty() {
+                    use_index(next_implicit);
+                    next_implicit += 1;
+                } else if let Ok(index) = argument.parse::<usize>() {
+                    use_index(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(required)
+}
@@ -0,0 +1,29 @@
+//! The wording of the "caused by" lines that explain which part of a nested condition failed. Kept in one place,
+//! so that the phrasing stays consistent across the different kinds of conditions.
+//!
+//! All the `&str` parameters are parts of the format message and have to be escaped for a format string
+//! already (see [`printable_expr_string`](crate::printable_expr_string)).
+
+/// Start of every cause line, in front of the cause itself: `caused by: <cause>`
+pub(crate) const CAUSED_BY: &str = "caused by";
+
+/// Replaces the code in the header and the cause lines, and the printed values, with the `redact` option
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// Name of a `match` arm in [`match_arm`]
+pub(crate) const ARM: &str = "arm";
+
+/// Name of a `match` arm that matches anything in [`match_arm`], since such an arm is easy to miss when looking
+/// for the one that was entered
+pub(crate) const CATCH_ALL_ARM: &str = "catch-all arm";
+
+/// The cause for a block `{ ...; condition }` whose final expression was false
+pub(crate) fn block_return(condition: &str) -> String {
+    format!("block return assertion `{condition}` failed")
+}
+
+/// The cause for a `match expr { pattern => condition }` where the condition of the entered arm was false.
+/// `arm_kind` is either [`ARM`] or [`CATCH_ALL_ARM`]
+pub(crate) fn match_arm(expr: &str, arm_kind: &str, pattern: &str, condition: &str) -> String {
+    format!("match {expr} entered {arm_kind} `{pattern}` where assertion `{condition}` failed")
+}
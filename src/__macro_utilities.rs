@@ -0,0 +1,2087 @@
+//! Runtime helpers that are used by the code generated by the macros.
+//!
+//! Nothing in here is part of the public API and may change at any time.
+
+const RED: &str = "\u{1b}[31m";
+const GREEN: &str = "\u{1b}[32m";
+const BOLD: &str = "\u{1b}[1m";
+const RESET: &str = "\u{1b}[0m";
+
+/// Create a line-by-line diff of two (pretty-printed) values in the style of the `pretty_assertions` crate.
+///
+/// Lines that only exist in `left` are prefixed with a red `<`, lines that only exist in `right` with a
+/// green `>`, and lines that are in both with a space.
+pub fn pretty_diff(left: &str, right: &str) -> String {
+    let left = left.lines().collect::<Vec<_>>();
+    let right = right.lines().collect::<Vec<_>>();
+
+    // lcs[i][j] = length of the longest common subsequence of left[i..] and right[j..]
+    let mut lcs = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lcs[i][j] = if left[i] == right[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = format!(
+        "{BOLD}Diff{RESET} {RED}<{RESET} {RED}left{RESET} / {GREEN}right{RESET} {GREEN}>{RESET} :"
+    );
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        out.push('\n');
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            out += &format!(" {}", left[i]);
+            i += 1;
+            j += 1;
+        } else if i < left.len() && (j == right.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // removals are printed before insertions, same as pretty_assertions does
+            out += &format!("{RED}<{}{RESET}", left[i]);
+            i += 1;
+        } else {
+            out += &format!("{GREEN}>{}{RESET}", right[j]);
+            j += 1;
+        }
+    }
+    out
+}
+
+/// Replace every `PhantomData<...>` in a debug-printed value with just `PhantomData`.
+///
+/// String and char literals in the value are skipped, so that actual data that happens to
+/// contain the text is not modified.
+pub fn elide_phantom_data(debug_str: &str) -> String {
+    const NEEDLE: &str = "PhantomData<";
+
+    let mut out = String::with_capacity(debug_str.len());
+    let mut rest = debug_str;
+    while let Some(c) = rest.chars().next() {
+        if c == '"' || c == '\'' {
+            let len = quoted_len(rest, c);
+            out += &rest[..len];
+            rest = &rest[len..];
+        } else if rest.starts_with(NEEDLE)
+            && !out.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+        {
+            out += "PhantomData";
+            rest = &rest[NEEDLE.len()..];
+            let mut depth = 1;
+            let mut prev = '<';
+            while depth > 0 {
+                let Some(c) = rest.chars().next() else { break };
+                match c {
+                    '<' => depth += 1,
+                    '>' if prev != '-' => depth -= 1, // `->` in fn types is not a closing bracket
+                    _ => {}
+                }
+                prev = c;
+                rest = &rest[c.len_utf8()..];
+            }
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out
+}
+
+/// Length of the string or char literal at the start of `s` (including the quotes), respecting escapes
+fn quoted_len(s: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return i + c.len_utf8();
+        }
+    }
+    s.len()
+}
+
+/// Values longer than this (in characters) are cut off by [`truncate`]
+const MAX_VALUE_LEN: usize = 256;
+
+/// Cut off `value` after [`MAX_VALUE_LEN`] characters
+fn truncate(value: String) -> String {
+    match value.char_indices().nth(MAX_VALUE_LEN) {
+        Some((end, _)) => {
+            let rest = value[end..].chars().count();
+            format!("{}... ({rest} more characters)", &value[..end])
+        }
+        None => value,
+    }
+}
+
+/// Format `name: value` lines with the names right-aligned, the same way `assert!` prints its variables
+pub fn format_values(values: &[(&str, String)]) -> String {
+    let width = values.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    values
+        .iter()
+        .map(|(name, value)| format!("\n    {name:>width$}: {value}"))
+        .collect()
+}
+
+/// Format `name: value` lines like [`format_values`], but with runs of the same value under consecutively
+/// numbered names (`arg 2`, `arg 3`, ...) collapsed into a single line: `arg 2..9: 0 (×8)`. Used for the
+/// `dedup` option
+pub fn dedup_values(indent: &str, values: &[(&str, String)]) -> String {
+    let mut lines = vec![];
+    let mut start = 0;
+    while start < values.len() {
+        let (name, value) = &values[start];
+        let mut end = start + 1;
+        while end < values.len()
+            && values[end].1 == *value
+            && is_next_number(values[end - 1].0, values[end].0)
+        {
+            end += 1;
+        }
+        if end - start > 1 {
+            let last = split_number(values[end - 1].0).map_or(0, |(_, n)| n);
+            lines.push((
+                format!("{name}..{last}"),
+                format!("{value} (×{})", end - start),
+            ));
+        } else {
+            lines.push((name.to_string(), value.clone()));
+        }
+        start = end;
+    }
+    let width = lines.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    lines
+        .iter()
+        .map(|(name, value)| format!("\n{indent}{name:>width$}: {value}"))
+        .collect()
+}
+
+/// Split a name like `arg 2` into `("arg ", 2)`
+fn split_number(name: &str) -> Option<(&str, usize)> {
+    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    Some((prefix, name[prefix.len()..].parse().ok()?))
+}
+
+/// Check if `next` is `previous` with the number at the end increased by one, like `arg 2` and `arg 3`
+fn is_next_number(previous: &str, next: &str) -> bool {
+    match (split_number(previous), split_number(next)) {
+        (Some((a, n)), Some((b, m))) => a == b && n + 1 == m,
+        _ => false,
+    }
+}
+
+/// The values and notes of one level of a failure message: `(cause, [(name, value)], notes)`. The top level has
+/// an empty cause
+pub type FailureLevel<'a> = (&'a str, Vec<(&'a str, String)>, Vec<String>);
+
+/// The final message of a failed assertion: `default_message`, or the result of the formatter from
+/// [`set_formatter`](crate::set_formatter). The parts for the formatter are only collected if there is one
+pub fn format_failure(
+    default_message: String,
+    expression: &str,
+    message: Option<std::fmt::Arguments>,
+    levels: impl FnOnce() -> Vec<FailureLevel<'static>>,
+) -> String {
+    let Some(formatter) = crate::formatter::formatter() else {
+        return default_message;
+    };
+    let into_parts = |(cause, values, notes): FailureLevel| {
+        let values = values
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect();
+        let notes = notes.into_iter().filter(|note| !note.is_empty()).collect();
+        (cause.to_owned(), values, notes)
+    };
+    let mut levels = levels().into_iter().map(into_parts);
+    let (_, values, notes) = levels.next().unwrap_or_default();
+    let causes = levels
+        .map(|(description, values, notes)| crate::FailureCause {
+            description,
+            values,
+            notes,
+        })
+        .collect();
+    formatter(&crate::AssertFailure {
+        expression: expression.to_owned(),
+        message: message.map(|message| message.to_string()),
+        values,
+        notes,
+        causes,
+        default_message,
+    })
+}
+
+/// Details for a failed `assert_contains!`
+pub fn contains_failure(
+    haystack: &dyn std::fmt::Debug,
+    needle: &dyn std::fmt::Debug,
+    near_match: Option<String>,
+) -> String {
+    let mut values = vec![
+        ("haystack", truncate(format!("{haystack:?}"))),
+        ("needle", format!("{needle:?}")),
+    ];
+    if let Some(near_match) = near_match {
+        values.push(("closest match", near_match));
+    }
+    format_values(&values)
+}
+
+/// Details for a failed `assert_one_of!`
+pub fn one_of_failure(value: &dyn std::fmt::Debug, allowed: &[&dyn std::fmt::Debug]) -> String {
+    format_values(&[
+        ("value", truncate(format!("{value:?}"))),
+        ("allowed", truncate(format!("{allowed:?}"))),
+    ])
+}
+
+/// Run the steps of `assert_converges!`: Apply `step` to the state until `done` returns `true`, calling
+/// `step` at most `max_steps` times. Returns the number of steps, plus the last state if `done` was never `true`
+pub fn converge<S>(
+    initial: S,
+    max_steps: usize,
+    mut step: impl FnMut(S) -> S,
+    mut done: impl FnMut(&S) -> bool,
+) -> Result<usize, (usize, S)> {
+    let mut state = initial;
+    let mut steps = 0;
+    while !done(&state) {
+        if steps == max_steps {
+            return Err((steps, state));
+        }
+        state = step(state);
+        steps += 1;
+    }
+    Ok(steps)
+}
+
+/// Details for a failed `assert_converges!`
+pub fn converge_failure(steps: usize, state: &dyn std::fmt::Debug) -> String {
+    format_values(&[
+        ("steps", steps.to_string()),
+        ("last state", truncate(format!("{state:?}"))),
+    ])
+}
+
+/// Compare the steps that were counted by the block of `assert_within_steps!` to the budget. Returns the details
+/// for the failure message if there were too many
+pub fn steps_failure(steps: usize, max: usize) -> Option<String> {
+    if steps <= max {
+        return None;
+    }
+    Some(format_values(&[
+        ("steps", steps.to_string()),
+        ("max", max.to_string()),
+    ]))
+}
+
+/// A waker that does nothing when it is woken, since [`poll_once`] never polls a second time
+struct NoopWaker;
+
+impl std::task::Wake for NoopWaker {
+    fn wake(self: std::sync::Arc<Self>) {}
+}
+
+/// Poll `future` exactly once for `assert_ready!`
+pub fn poll_once<F: std::future::Future>(future: F) -> std::task::Poll<F::Output> {
+    let waker = std::task::Waker::from(std::sync::Arc::new(NoopWaker));
+    let mut context = std::task::Context::from_waker(&waker);
+    let future = std::pin::pin!(future);
+    std::future::Future::poll(future, &mut context)
+}
+
+/// The details for the failure message of `assert_ready!` if the future returned `Pending`
+pub fn pending_failure() -> String {
+    let poll = format_values(&[("poll", "Pending".to_owned())]);
+    format!("{poll}\n  the future did not complete synchronously")
+}
+
+/// Compare two values after passing each of them through `normalize` once, for `assert_eq_by!`. Returns the
+/// details for the failure message if the normalized values differ
+pub fn eq_by_failure<T, N>(left: T, right: T, mut normalize: impl FnMut(T) -> N) -> Option<String>
+where
+    T: std::fmt::Debug,
+    N: PartialEq + std::fmt::Debug,
+{
+    // printed before the values are handed to `normalize`, which might consume them
+    let (left_str, right_str) = (format!("{left:?}"), format!("{right:?}"));
+    let (normalized_left, normalized_right) = (normalize(left), normalize(right));
+    if normalized_left == normalized_right {
+        return None;
+    }
+    Some(format_values(&[
+        ("left", truncate(left_str)),
+        ("right", truncate(right_str)),
+        ("normalized left", truncate(format!("{normalized_left:?}"))),
+        (
+            "normalized right",
+            truncate(format!("{normalized_right:?}")),
+        ),
+    ]))
+}
+
+/// Call `f` and `g` with each input for `assert_same_output!`, until their outputs differ.
+/// Returns the details for the failure message if they do
+pub fn first_divergence<
+    I: std::fmt::Debug,
+    L: PartialEq<R> + std::fmt::Debug,
+    R: std::fmt::Debug,
+>(
+    inputs: impl IntoIterator<Item = I>,
+    mut f: impl FnMut(&I) -> L,
+    mut g: impl FnMut(&I) -> R,
+) -> Option<String> {
+    for (index, input) in inputs.into_iter().enumerate() {
+        let (left, right) = (f(&input), g(&input));
+        if left != right {
+            return Some(format_values(&[
+                ("index", index.to_string()),
+                ("input", truncate(format!("{input:?}"))),
+                ("left", truncate(format!("{left:?}"))),
+                ("right", truncate(format!("{right:?}"))),
+            ]));
+        }
+    }
+    None
+}
+
+/// Check that each element of `sequence` relates to the previous one by `<`/`<=` (or `>`/`>=` if `decreasing`)
+/// for `assert_monotonic!`. Returns the details for the failure message for the first pair that doesn't
+pub fn monotonic_violation<T: PartialOrd + std::fmt::Debug>(
+    sequence: impl IntoIterator<Item = T>,
+    decreasing: bool,
+    strict: bool,
+) -> Option<String> {
+    let operator = match (decreasing, strict) {
+        (false, true) => "<",
+        (false, false) => "<=",
+        (true, true) => ">",
+        (true, false) => ">=",
+    };
+    let mut iter = sequence.into_iter().enumerate();
+    let (_, mut previous) = iter.next()?;
+    for (index, element) in iter {
+        let holds = match operator {
+            "<" => previous < element,
+            "<=" => previous <= element,
+            ">" => previous > element,
+            _ => previous >= element,
+        };
+        if !holds {
+            let previous_name = format!("element {}", index - 1);
+            let element_name = format!("element {index}");
+            let values = format_values(&[
+                (&previous_name, truncate(format!("{previous:?}"))),
+                (&element_name, truncate(format!("{element:?}"))),
+            ]);
+            // indented below the "caused by" line, same as in `assert!`
+            let values = values.replace('\n', "\n  ");
+            return Some(format!(
+                "\n  caused by: {previous_name} {operator} {element_name} is false{values}"
+            ));
+        }
+        previous = element;
+    }
+    None
+}
+
+/// The number of elements on each side of an index that are printed by the slice assertions
+const SLICE_WINDOW: usize = 3;
+
+/// Print the elements `start..end` of `slice` (as far as they exist) as a `name[start..end]` line, so that long
+/// slices don't flood the message
+fn slice_window<T: std::fmt::Debug>(
+    name: &str,
+    slice: &[T],
+    start: usize,
+    end: usize,
+) -> (String, String) {
+    let end = end.min(slice.len());
+    let start = start.min(end);
+    let value = truncate(format!("{:?}", &slice[start..end]));
+    (format!("{name}[{start}..{end}]"), value)
+}
+
+/// Compare two slices for `assert_starts_with!`. Returns the details for the failure message if `haystack`
+/// doesn't start with `prefix`
+pub fn starts_with_failure<H, P, T, U>(haystack: &H, prefix: &P) -> Option<String>
+where
+    H: AsRef<[T]> + ?Sized,
+    P: AsRef<[U]> + ?Sized,
+    T: PartialEq<U> + std::fmt::Debug,
+    U: std::fmt::Debug,
+{
+    let (haystack, prefix) = (haystack.as_ref(), prefix.as_ref());
+    let index = match haystack.iter().zip(prefix).position(|(h, p)| h != p) {
+        Some(index) => index,
+        None if haystack.len() >= prefix.len() => return None,
+        None => haystack.len(),
+    };
+    let position = if index == haystack.len() {
+        format!("index {index} (end of haystack)")
+    } else {
+        format!("index {index}")
+    };
+    let (start, end) = (index.saturating_sub(SLICE_WINDOW), index + SLICE_WINDOW + 1);
+    let (haystack_name, haystack_window) = slice_window("haystack", haystack, start, end);
+    let (prefix_name, prefix_window) = slice_window("prefix", prefix, start, end);
+    Some(format_values(&[
+        ("first difference", position),
+        (&haystack_name, haystack_window),
+        (&prefix_name, prefix_window),
+    ]))
+}
+
+/// Search the elements of `needle` in order in `haystack` for `assert_subsequence!`, skipping any elements in
+/// between. Returns the details for the failure message if an element of `needle` can't be found
+pub fn subsequence_failure<H, N, T, U>(haystack: &H, needle: &N) -> Option<String>
+where
+    H: AsRef<[T]> + ?Sized,
+    N: AsRef<[U]> + ?Sized,
+    T: PartialEq<U> + std::fmt::Debug,
+    U: std::fmt::Debug,
+{
+    let (haystack, needle) = (haystack.as_ref(), needle.as_ref());
+    // matching each element as early as possible leaves the most room for the remaining ones
+    let mut indices = vec![];
+    let mut start = 0;
+    for element in needle {
+        match haystack[start..].iter().position(|h| h == element) {
+            Some(offset) => {
+                indices.push(start + offset);
+                start += offset + 1;
+            }
+            None => break,
+        }
+    }
+    let matched = indices.len();
+    if matched == needle.len() {
+        return None;
+    }
+    let end = start + 2 * SLICE_WINDOW + 1;
+    let (remaining_name, remaining) = slice_window("haystack", haystack, start, end);
+    Some(format_values(&[
+        (
+            "matched",
+            format!(
+                "{matched} of {} elements at indices {indices:?}",
+                needle.len()
+            ),
+        ),
+        (
+            "missing element",
+            format!(
+                "needle[{matched}] = {}",
+                truncate(format!("{:?}", needle[matched]))
+            ),
+        ),
+        ("searched from", format!("index {start}")),
+        (&remaining_name, remaining),
+    ]))
+}
+
+/// Find the first byte where `left` and `right` differ, and describe it with the sizes of both sides and the bytes
+/// around it. Returns `None` if they are equal
+#[cfg(feature = "fs")]
+fn byte_difference(left: &[u8], right: &[u8]) -> Option<Vec<(String, String)>> {
+    let index = match left.iter().zip(right).position(|(l, r)| l != r) {
+        Some(index) => index,
+        None if left.len() == right.len() => return None,
+        None => left.len().min(right.len()),
+    };
+    let position = if index == left.len() {
+        format!("byte {index} (end of left)")
+    } else if index == right.len() {
+        format!("byte {index} (end of right)")
+    } else {
+        format!("byte {index}")
+    };
+    let (start, end) = (index.saturating_sub(SLICE_WINDOW), index + SLICE_WINDOW + 1);
+    let window = |name: &str, bytes: &[u8]| {
+        let end = end.min(bytes.len());
+        let start = start.min(end);
+        let value = format!("b\"{}\"", bytes[start..end].escape_ascii());
+        (format!("{name}[{start}..{end}]"), value)
+    };
+    Some(vec![
+        ("left size".to_owned(), format!("{} bytes", left.len())),
+        ("right size".to_owned(), format!("{} bytes", right.len())),
+        ("first difference".to_owned(), position),
+        window("left", left),
+        window("right", right),
+    ])
+}
+
+/// Read and compare two files for `assert_files_eq!`. Returns the details for the failure message if one of them
+/// can't be read, or if their contents differ
+#[cfg(feature = "fs")]
+pub fn files_eq_failure<L, R>(left: &L, right: &R) -> Option<String>
+where
+    L: AsRef<std::path::Path> + ?Sized,
+    R: AsRef<std::path::Path> + ?Sized,
+{
+    let (left, right) = (left.as_ref(), right.as_ref());
+    let mut errors = vec![];
+    let mut read = |name: &'static str, path: &std::path::Path| match std::fs::read(path) {
+        Ok(contents) => Some(contents),
+        Err(error) => {
+            errors.push((name, format!("couldn't read {path:?}: {error}")));
+            None
+        }
+    };
+    let (left_contents, right_contents) = (read("left", left), read("right", right));
+    let (left_contents, right_contents) = match (left_contents, right_contents) {
+        (Some(left), Some(right)) => (left, right),
+        _ => return Some(format_values(&errors)),
+    };
+    let difference = byte_difference(&left_contents, &right_contents)?;
+    let values: Vec<_> = difference
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+    Some(format_values(&values))
+}
+
+/// Run the code of `assert_output_contains!` with a buffer to write its output into. Returns the result of the
+/// code and everything that it wrote
+#[cfg(feature = "output")]
+pub fn capture_output<T>(code: impl FnOnce(&mut Vec<u8>) -> T) -> (T, Vec<u8>) {
+    let mut output = Vec::new();
+    let result = code(&mut output);
+    (result, output)
+}
+
+/// Search the captured output for `assert_output_contains!`. Returns the details for the failure message with the
+/// entire output if it doesn't contain `needle`
+#[cfg(feature = "output")]
+pub fn output_contains_failure<N: crate::OneAssertStrLike + ?Sized>(
+    output: &[u8],
+    needle: &N,
+) -> Option<String> {
+    let (output, needle) = (String::from_utf8_lossy(output), needle.one_assert_str());
+    if output.contains(needle) {
+        return None;
+    }
+    // not truncated, since the point of the assertion is to see what was written instead
+    Some(format_values(&[
+        ("output", format!("{output:?}")),
+        ("needle", format!("{needle:?}")),
+    ]))
+}
+
+/// Run the command of `assert_success!` once and wait for it to finish. Returns its output if it exited with
+/// status zero, or the details for the failure message with the exit code and everything it wrote otherwise
+#[cfg(feature = "process")]
+pub fn command_success<C: std::borrow::BorrowMut<std::process::Command>>(
+    mut command: C,
+) -> Result<std::process::Output, String> {
+    let command = command.borrow_mut();
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(error) => {
+            return Err(format_values(&[
+                ("command", format!("{command:?}")),
+                ("error", format!("couldn't run the command: {error}")),
+            ]))
+        }
+    };
+    if output.status.success() {
+        return Ok(output);
+    }
+    let code = match output.status.code() {
+        Some(code) => code.to_string(),
+        None => "none (terminated by a signal)".to_owned(),
+    };
+    // not truncated, since the output usually says why the command failed
+    Err(format_values(&[
+        ("command", format!("{command:?}")),
+        ("exit code", code),
+        (
+            "stdout",
+            format!("{:?}", String::from_utf8_lossy(&output.stdout)),
+        ),
+        (
+            "stderr",
+            format!("{:?}", String::from_utf8_lossy(&output.stderr)),
+        ),
+    ]))
+}
+
+/// Run the code of `assert_order!` with a function that records the steps it reaches. Returns the result of the
+/// code and the recorded steps in the order they were reached
+pub fn record_order<T, R>(code: impl FnOnce(&dyn Fn(T)) -> R) -> (R, Vec<T>) {
+    let steps = std::cell::RefCell::new(Vec::new());
+    let result = code(&|step| steps.borrow_mut().push(step));
+    (result, steps.into_inner())
+}
+
+/// Compare the recorded steps of `assert_order!` to the expected ones. Returns the details for the failure
+/// message if they differ, with the first position where they do
+pub fn order_failure<T: PartialEq + std::fmt::Debug>(
+    actual: &[T],
+    expected: &[T],
+) -> Option<String> {
+    if actual == expected {
+        return None;
+    }
+    let index = actual
+        .iter()
+        .zip(expected)
+        .position(|(a, e)| a != e)
+        .unwrap_or_else(|| actual.len().min(expected.len()));
+    let difference = match (actual.get(index), expected.get(index)) {
+        (Some(a), Some(e)) => format!("step {index}: expected {e:?}, found {a:?}"),
+        (None, Some(e)) => format!("step {index}: expected {e:?}, found nothing"),
+        (Some(a), None) => format!("step {index}: expected nothing, found {a:?}"),
+        (None, None) => unreachable!("the steps are equal up to the end of both"),
+    };
+    Some(format_values(&[
+        ("expected", truncate(format!("{expected:?}"))),
+        ("actual", truncate(format!("{actual:?}"))),
+        ("first difference", difference),
+    ]))
+}
+
+/// Count the elements of `left` and `right` for `assert_same_elements!`. Returns the details for the failure
+/// message if any element occurs a different number of times, with one line per such element in the order of
+/// their first occurrence
+pub fn same_elements_failure<L, R, T>(left: L, right: R) -> Option<String>
+where
+    L: IntoIterator<Item = T>,
+    R: IntoIterator<Item = T>,
+    T: Eq + std::hash::Hash + std::fmt::Debug,
+{
+    // element => (first occurrence, count in left, count in right)
+    let mut counts = std::collections::HashMap::<T, (usize, usize, usize)>::new();
+    let mut next = 0;
+    let mut count = |element: T, is_left: bool| {
+        let entry = counts.entry(element).or_insert_with(|| {
+            next += 1;
+            (next, 0, 0)
+        });
+        if is_left {
+            entry.1 += 1;
+        } else {
+            entry.2 += 1;
+        }
+    };
+    left.into_iter().for_each(|element| count(element, true));
+    right.into_iter().for_each(|element| count(element, false));
+
+    let mut differences: Vec<_> = counts
+        .into_iter()
+        .filter(|(_, (_, left, right))| left != right)
+        .collect();
+    if differences.is_empty() {
+        return None;
+    }
+    differences.sort_by_key(|(_, (first, _, _))| *first);
+    let lines: Vec<(String, String)> = differences
+        .into_iter()
+        .map(|(element, (_, left, right))| {
+            let name = truncate(format!("{element:?}"));
+            (name, format!("expected {right}, found {left}"))
+        })
+        .collect();
+    let values: Vec<_> = lines
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+    Some(format_values(&values))
+}
+
+/// Compare two slices of numbers element by element for `assert_all_close!`. Returns the details for the failure
+/// message if their lengths differ, or if any pair of elements is further apart than `tolerance`
+pub fn all_close_failure<L, R, T>(left: &L, right: &R, tolerance: T) -> Option<String>
+where
+    L: AsRef<[T]> + ?Sized,
+    R: AsRef<[T]> + ?Sized,
+    T: Copy + PartialOrd + std::ops::Sub<Output = T> + std::fmt::Debug,
+{
+    let (left, right) = (left.as_ref(), right.as_ref());
+    let mut values = vec![];
+    if left.len() != right.len() {
+        values.push(("left length".to_owned(), left.len().to_string()));
+        values.push(("right length".to_owned(), right.len().to_string()));
+    }
+    for (index, (&l, &r)) in left.iter().zip(right).enumerate() {
+        if let Some(value) = out_of_tolerance(l, r, tolerance) {
+            values.push((format!("[{index}]"), value));
+        }
+    }
+    tolerance_failure(values, tolerance)
+}
+
+/// Compare two matrices of numbers element by element for `assert_matrix_close!`. Returns the details for the
+/// failure message if their dimensions differ, or if any pair of elements is further apart than `tolerance`
+pub fn matrix_close_failure<L, R, LRow, RRow, T>(
+    left: &L,
+    right: &R,
+    tolerance: T,
+) -> Option<String>
+where
+    L: AsRef<[LRow]> + ?Sized,
+    R: AsRef<[RRow]> + ?Sized,
+    LRow: AsRef<[T]>,
+    RRow: AsRef<[T]>,
+    T: Copy + PartialOrd + std::ops::Sub<Output = T> + std::fmt::Debug,
+{
+    let (left, right) = (left.as_ref(), right.as_ref());
+    let mut values = vec![];
+    // all the differing dimensions come first, since they are usually the reason for the other differences
+    if left.len() != right.len() {
+        values.push(("left rows".to_owned(), left.len().to_string()));
+        values.push(("right rows".to_owned(), right.len().to_string()));
+    }
+    for (row, (l, r)) in left.iter().zip(right).enumerate() {
+        let (l, r) = (l.as_ref(), r.as_ref());
+        if l.len() != r.len() {
+            let value = format!("left={} right={}", l.len(), r.len());
+            values.push((format!("row {row} length"), value));
+        }
+    }
+    for (row, (l, r)) in left.iter().zip(right).enumerate() {
+        for (col, (&l, &r)) in l.as_ref().iter().zip(r.as_ref()).enumerate() {
+            if let Some(value) = out_of_tolerance(l, r, tolerance) {
+                values.push((format!("[{row}, {col}]"), value));
+            }
+        }
+    }
+    tolerance_failure(values, tolerance)
+}
+
+/// Describe a pair of elements for `assert_all_close!` and `assert_matrix_close!`, if they are further apart
+/// than `tolerance`
+fn out_of_tolerance<T>(left: T, right: T, tolerance: T) -> Option<String>
+where
+    T: Copy + PartialOrd + std::ops::Sub<Output = T> + std::fmt::Debug,
+{
+    // subtracting the smaller one also works for unsigned integers
+    let diff = if left > right {
+        left - right
+    } else {
+        right - left
+    };
+    // `NaN` is neither smaller nor equal
+    match diff.partial_cmp(&tolerance) {
+        Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal) => None,
+        _ => Some(format!("left={left:?} right={right:?} diff={diff:?}")),
+    }
+}
+
+/// The details for the failure message of `assert_all_close!` and `assert_matrix_close!`, if there are any
+/// differences
+fn tolerance_failure<T: std::fmt::Debug>(
+    mut values: Vec<(String, String)>,
+    tolerance: T,
+) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+    values.insert(0, ("tolerance".to_owned(), format!("{tolerance:?}")));
+    let values: Vec<_> = values
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+    Some(format_values(&values))
+}
+
+/// Search the `Display` output of an error and its sources for `assert_err_contains!`. Returns the details for the
+/// failure message if `result` is `Ok` or none of the errors contain `needle`
+pub fn err_contains_failure<T, E, N>(result: &Result<T, E>, needle: &N) -> Option<String>
+where
+    E: std::error::Error,
+    N: crate::OneAssertStrLike + ?Sized,
+{
+    let needle = needle.one_assert_str();
+    let error = match result {
+        Ok(_) => return Some(format_values(&[("result", "Ok(_)".to_owned())])),
+        Err(error) => error,
+    };
+    let mut chain = vec![];
+    let mut current: Option<&dyn std::error::Error> = Some(error);
+    while let Some(error) = current {
+        let message = error.to_string();
+        if message.contains(needle) {
+            return None;
+        }
+        chain.push(message);
+        current = error.source();
+    }
+    let names: Vec<String> = (0..chain.len())
+        .map(|i| match i {
+            0 => "error".to_owned(),
+            i => format!("source {i}"),
+        })
+        .collect();
+    let values: Vec<_> = names
+        .iter()
+        .zip(chain)
+        .map(|(name, message)| (name.as_str(), truncate(message)))
+        .collect();
+    Some(format_values(&values))
+}
+
+/// The backtrace of a failed assertion with the `backtrace` feature, to be appended to the message. Empty unless
+/// `RUST_LIB_BACKTRACE` is set to something other than `0`.
+///
+/// Unlike [`Backtrace::capture`](std::backtrace::Backtrace::capture), `RUST_BACKTRACE` is not used as a
+/// fallback: it already makes the panic hook print a backtrace, and the message should stay the same with it
+pub fn backtrace_section() -> String {
+    match std::env::var_os("RUST_LIB_BACKTRACE") {
+        Some(value) if value != "0" => {
+            format!(
+                "\n\nbacktrace:\n{}",
+                std::backtrace::Backtrace::force_capture()
+            )
+        }
+        _ => String::new(),
+    }
+}
+
+/// Hash a value with [`DefaultHasher::new`](std::collections::hash_map::DefaultHasher::new), which always uses the
+/// same keys, so that equal values get the same hash across calls
+fn hash_of<T: std::hash::Hash + ?Sized>(value: &T) -> u64 {
+    use std::hash::Hasher;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Compare the hashes of two values for `assert_hash_eq!` (`equal`) and `assert_hash_ne!`. Returns the details
+/// for the failure message if the hashes are not equal or not different, respectively
+pub fn hash_failure<L, R>(left: &L, right: &R, equal: bool) -> Option<String>
+where
+    L: std::hash::Hash + std::fmt::Debug + ?Sized,
+    R: std::hash::Hash + std::fmt::Debug + ?Sized,
+{
+    let (left_hash, right_hash) = (hash_of(left), hash_of(right));
+    if (left_hash == right_hash) == equal {
+        return None;
+    }
+    Some(format_values(&[
+        ("left", truncate(format!("{left:?}"))),
+        ("right", truncate(format!("{right:?}"))),
+        ("left hash", format!("{left_hash:#018x}")),
+        ("right hash", format!("{right_hash:#018x}")),
+    ]))
+}
+
+/// Compare the number of drops that were counted by `counter` for `assert_drop_count!`. Returns the details for the
+/// failure message if the count is different from `expected`
+pub fn drop_count_failure(
+    counter: &std::sync::atomic::AtomicUsize,
+    expected: usize,
+) -> Option<String> {
+    let dropped = counter.load(std::sync::atomic::Ordering::SeqCst);
+    count_failure("dropped", dropped, expected)
+}
+
+/// Consume `iter` and compare the number of items it yielded for `assert_count!`. Returns the details for the
+/// failure message if the count is different from `expected`
+pub fn iter_count_failure<I: IntoIterator>(iter: I, expected: usize) -> Option<String> {
+    count_failure("counted", iter.into_iter().count(), expected)
+}
+
+/// Compare a `count` with the `expected` one, printing how many are missing or too many if they differ
+fn count_failure(name: &str, count: usize, expected: usize) -> Option<String> {
+    let difference = match count.cmp(&expected) {
+        std::cmp::Ordering::Equal => return None,
+        std::cmp::Ordering::Less => format!("{} missing", expected - count),
+        std::cmp::Ordering::Greater => format!("{} too many", count - expected),
+    };
+    Some(format_values(&[
+        (name, count.to_string()),
+        ("expected", expected.to_string()),
+        ("difference", difference),
+    ]))
+}
+
+/// Match a string against a glob pattern with `*` and `?` for `assert_matches_glob!`. Returns the details for
+/// the failure message if it doesn't match
+pub fn glob_failure<S: crate::OneAssertStrLike + ?Sized, P: crate::OneAssertStrLike + ?Sized>(
+    string: &S,
+    pattern: &P,
+) -> Option<String> {
+    let (string, pattern) = (string.one_assert_str(), pattern.one_assert_str());
+    let (text, glob): (Vec<char>, Vec<char>) =
+        (string.chars().collect(), pattern.chars().collect());
+
+    // the usual greedy matching, where a failed match after a `*` lets that `*` take one more character.
+    // `furthest` is the furthest (pattern, text) position that was reached, to show where the matching stopped
+    let (mut t, mut p) = (0, 0);
+    let mut last_star = None;
+    let mut furthest = (0, 0);
+    loop {
+        if p < glob.len() && glob[p] == '*' {
+            last_star = Some((p, t));
+            p += 1;
+        } else if t < text.len() && p < glob.len() && (glob[p] == '?' || glob[p] == text[t]) {
+            t += 1;
+            p += 1;
+        } else if t == text.len() && p == glob.len() {
+            return None;
+        } else if let Some((star, start)) = last_star.filter(|(_, start)| *start < text.len()) {
+            p = star + 1;
+            t = start + 1;
+            last_star = Some((star, t));
+        } else {
+            break;
+        }
+        furthest = furthest.max((p, t));
+    }
+
+    let (p, t) = furthest;
+    let part = |chars: &[char]| format!("{:?}", chars.iter().collect::<String>());
+    Some(format_values(&[
+        ("string", truncate(format!("{string:?}"))),
+        ("pattern", truncate(format!("{pattern:?}"))),
+        (
+            "matched",
+            truncate(format!("{} with {}", part(&text[..t]), part(&glob[..p]))),
+        ),
+        (
+            "mismatch",
+            truncate(format!("{} against {}", part(&text[t..]), part(&glob[p..]))),
+        ),
+    ]))
+}
+
+/// Compare two strings for `assert_similar!`. Returns the details for the failure message if their
+/// edit distance is larger than `max_distance`
+pub fn similar_failure<L: crate::OneAssertStrLike + ?Sized, R: crate::OneAssertStrLike + ?Sized>(
+    left: &L,
+    right: &R,
+    max_distance: usize,
+) -> Option<String> {
+    let (left, right) = (left.one_assert_str(), right.one_assert_str());
+    let distance = edit_distance(left, right);
+    if distance <= max_distance {
+        return None;
+    }
+    Some(format_values(&[
+        ("left", truncate(format!("{left:?}"))),
+        ("right", truncate(format!("{right:?}"))),
+        ("distance", distance.to_string()),
+        ("max distance", max_distance.to_string()),
+    ]))
+}
+
+/// Compare two structs field by field for `assert_eq_ignoring!` and `assert_fields_eq!`. Returns the details for the failure message
+/// if any of the fields that are not `ignored` differ, or if `ignored` names a field that doesn't exist
+pub fn fields_failure<T: crate::OneAssertFields>(
+    left: &T,
+    right: &T,
+    ignored: &[&str],
+) -> Option<String> {
+    if let Some(unknown) = ignored.iter().find(|name| !T::FIELD_NAMES.contains(name)) {
+        return Some(format!(
+            "\n  caused by: `{unknown}` is not one of the fields {:?}",
+            T::FIELD_NAMES
+        ));
+    }
+    let differences = left.differing_fields(right, ignored);
+    if differences.is_empty() {
+        return None;
+    }
+    let names = differences
+        .iter()
+        .map(|(field, _, _)| (format!("left.{field}"), format!("right.{field}")))
+        .collect::<Vec<_>>();
+    let mut values = vec![];
+    for ((left_name, right_name), (_, left, right)) in names.iter().zip(differences) {
+        values.push((left_name.as_str(), truncate(left)));
+        values.push((right_name.as_str(), truncate(right)));
+    }
+    Some(format_values(&values))
+}
+
+/// The Levenshtein distance between `a` and `b`: The number of single-character insertions, deletions and
+/// substitutions that turn one into the other.
+///
+/// Uses the usual dynamic programming approach with a single row, so it takes `O(n * m)` time and `O(m)`
+/// memory for strings with `n` and `m` characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    // row[j] is the distance between the processed prefix of `a` and the first `j` characters of `b`
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Same as `std::any::type_name_of_val`, which is not available in the minimum supported Rust version
+pub fn type_name_of<T: ?Sized>(_: &T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
+/// Call the predicate of `assert_that!` with the value
+pub fn apply_predicate<T: ?Sized>(value: &T, predicate: impl FnOnce(&T) -> bool) -> bool {
+    predicate(value)
+}
+
+/// Compare the panic message from `catch_unwind` with `expected` for `should_panic_with!`.
+/// Returns the details for the failure message if they don't match.
+pub fn check_panic_message(result: std::thread::Result<()>, expected: &str) -> Option<String> {
+    let actual = match result {
+        Ok(()) => String::from("<no panic>"),
+        Err(payload) => {
+            if let Some(s) = payload.downcast_ref::<&'static str>() {
+                if *s == expected {
+                    return None;
+                }
+                format!("{s:?}")
+            } else if let Some(s) = payload.downcast_ref::<String>() {
+                if s == expected {
+                    return None;
+                }
+                format!("{s:?}")
+            } else {
+                String::from("<panic payload is not a string>")
+            }
+        }
+    };
+    Some(format_values(&[
+        ("expected", format!("{expected:?}")),
+        ("actual", actual),
+    ]))
+}
+
+/// The two sides of an `==` comparison, for the note about differing `Option`s.
+///
+/// Printed as `(&&OptionPair(&left, &right)).__one_assert_option_diff("left", "right")`, which uses
+/// autoref-specialization
+/// to pick [`OptionDiff`] if both sides are `Option`s, and [`OptionDiffFallback`] otherwise.
+#[derive(Debug)]
+pub struct OptionPair<'a, L: ?Sized, R: ?Sized>(pub &'a L, pub &'a R);
+
+mod sealed {
+    pub trait Sealed {}
+    impl<T> Sealed for Option<T> {}
+    impl<T> Sealed for std::task::Poll<T> {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// Implemented for `Option<T: Debug>` only
+pub trait OptionShape: sealed::Sealed {
+    /// The debug-printed inner value, if any
+    fn debug_inner(&self) -> Option<String>;
+}
+
+impl<T: std::fmt::Debug> OptionShape for Option<T> {
+    fn debug_inner(&self) -> Option<String> {
+        self.as_ref().map(|value| format!("{value:?}"))
+    }
+}
+
+pub trait OptionDiff {
+    fn __one_assert_option_diff(&self, left_name: &str, right_name: &str) -> String;
+}
+
+impl<L: OptionShape, R: OptionShape> OptionDiff for &OptionPair<'_, L, R> {
+    fn __one_assert_option_diff(&self, left_name: &str, right_name: &str) -> String {
+        option_diff(
+            self.0.debug_inner(),
+            self.1.debug_inner(),
+            [left_name, right_name],
+        )
+    }
+}
+
+pub trait OptionDiffFallback {
+    fn __one_assert_option_diff(&self, left_name: &str, right_name: &str) -> String;
+}
+
+impl<L: ?Sized, R: ?Sized> OptionDiffFallback for OptionPair<'_, L, R> {
+    fn __one_assert_option_diff(&self, _: &str, _: &str) -> String {
+        String::new()
+    }
+}
+
+/// Unwrap two (debug-printed) `Option`s as long as both are `Some`, and describe where they differ, with the
+/// `names` of the two sides. Returns an empty string if there is nothing to say
+fn option_diff(mut left: Option<String>, mut right: Option<String>, names: [&str; 2]) -> String {
+    // wraps `inner` in as many `Some(...)` as have been unwrapped so far
+    let shape = |depth: usize, inner: &str| {
+        format!("{}{inner}{}", "Some(".repeat(depth), ")".repeat(depth))
+    };
+    let mut depth = 0;
+    loop {
+        match (left, right) {
+            (Some(l), Some(r)) => {
+                depth += 1;
+                if l == r {
+                    return String::new(); // e.g. `Some(NaN)`, which prints the same but is not equal
+                }
+                // the inner values might be `Option`s again. Only their debug output is known at this point
+                let unwrap = |s: &str| {
+                    if s == "None" {
+                        Ok(None)
+                    } else if let Some(inner) =
+                        s.strip_prefix("Some(").and_then(|s| s.strip_suffix(')'))
+                    {
+                        Ok(Some(inner.to_owned()))
+                    } else {
+                        Err(())
+                    }
+                };
+                match (unwrap(&l), unwrap(&r)) {
+                    (Ok(inner_l), Ok(inner_r)) => {
+                        left = inner_l;
+                        right = inner_r;
+                    }
+                    _ => {
+                        return format!("inner values of {} differ: {l} != {r}", shape(depth, "_"))
+                    }
+                }
+            }
+            (None, None) => return String::new(),
+            (l, r) => {
+                let describe =
+                    |v: Option<String>| shape(depth, if v.is_some() { "Some(_)" } else { "None" });
+                let [left_name, right_name] = names;
+                return format!(
+                    "{left_name} is {}, {right_name} is {}",
+                    describe(l),
+                    describe(r)
+                );
+            }
+        }
+    }
+}
+
+/// The two sides of an `==` comparison, for the note about `Poll`s that differ in their readiness.
+///
+/// Printed as `(&&PollPair(&left, &right)).__one_assert_poll_diff()`, which uses autoref-specialization
+/// to pick [`PollDiff`] if both sides are `Poll`s, and [`PollDiffFallback`] otherwise.
+#[derive(Debug)]
+pub struct PollPair<'a, L: ?Sized, R: ?Sized>(pub &'a L, pub &'a R);
+
+/// Implemented for `Poll<T: Debug>` only
+pub trait PollShape: sealed::Sealed {
+    /// The debug-printed value if the `Poll` is ready, or `None` if it is pending
+    fn debug_ready(&self) -> Option<String>;
+}
+
+impl<T: std::fmt::Debug> PollShape for std::task::Poll<T> {
+    fn debug_ready(&self) -> Option<String> {
+        match self {
+            std::task::Poll::Ready(value) => Some(format!("{value:?}")),
+            std::task::Poll::Pending => None,
+        }
+    }
+}
+
+/// `Ready(_)` or `Pending`, without the value
+fn readiness(ready: &Option<String>) -> &'static str {
+    if ready.is_some() {
+        "Ready(_)"
+    } else {
+        "Pending"
+    }
+}
+
+pub trait PollDiff {
+    fn __one_assert_poll_diff(&self) -> String;
+}
+
+impl<L: PollShape, R: PollShape> PollDiff for &PollPair<'_, L, R> {
+    fn __one_assert_poll_diff(&self) -> String {
+        let (left, right) = (self.0.debug_ready(), self.1.debug_ready());
+        if left.is_some() == right.is_some() {
+            return String::new(); // the values are printed already
+        }
+        format!(
+            "left is {}, right is {}",
+            readiness(&left),
+            readiness(&right)
+        )
+    }
+}
+
+pub trait PollDiffFallback {
+    fn __one_assert_poll_diff(&self) -> String;
+}
+
+impl<L: ?Sized, R: ?Sized> PollDiffFallback for PollPair<'_, L, R> {
+    fn __one_assert_poll_diff(&self) -> String {
+        String::new()
+    }
+}
+
+/// The receiver of a `poll.is_ready()` or `poll.is_pending()` call in an assertion, along with whether the
+/// assertion expects it to be ready.
+///
+/// Printed as `(&&PollCheck(&poll, expect_ready)).__one_assert_poll_check()`, which uses autoref-specialization
+/// to pick [`PollCheckDetailed`] for `Poll`s, and [`PollCheckFallback`] for anything else that happens to have
+/// such a method.
+#[derive(Debug)]
+pub struct PollCheck<'a, P: ?Sized>(pub &'a P, pub bool);
+
+pub trait PollCheckDetailed {
+    fn __one_assert_poll_check(&self) -> String;
+}
+
+impl<P: PollShape> PollCheckDetailed for &PollCheck<'_, P> {
+    fn __one_assert_poll_check(&self) -> String {
+        let ready = self.0.debug_ready();
+        if ready.is_some() == self.1 {
+            return String::new();
+        }
+        let expected = if self.1 { "Ready(_)" } else { "Pending" };
+        format!("self is {}, expected {expected}", readiness(&ready))
+    }
+}
+
+pub trait PollCheckFallback {
+    fn __one_assert_poll_check(&self) -> String;
+}
+
+impl<P: ?Sized> PollCheckFallback for PollCheck<'_, P> {
+    fn __one_assert_poll_check(&self) -> String {
+        String::new()
+    }
+}
+
+/// The receiver of `is_finite`, `is_normal`, `is_nan` or `is_infinite`, for the note about its category.
+///
+/// Printed as `(&&FloatClass(&value)).__one_assert_float_class()`, which uses autoref-specialization to pick
+/// [`FloatClassDetailed`] for `f32` and `f64`, and [`FloatClassFallback`] for anything else that happens to have
+/// such a method.
+#[derive(Debug)]
+pub struct FloatClass<'a, T: ?Sized>(pub &'a T);
+
+pub trait FloatClassDetailed {
+    fn __one_assert_float_class(&self) -> String;
+}
+
+impl<T: FloatBits> FloatClassDetailed for &FloatClass<'_, T> {
+    fn __one_assert_float_class(&self) -> String {
+        let class = match self.0.classify() {
+            std::num::FpCategory::Nan => "not a number",
+            std::num::FpCategory::Infinite => "infinite",
+            std::num::FpCategory::Zero => "zero",
+            std::num::FpCategory::Subnormal => "subnormal",
+            std::num::FpCategory::Normal => "normal",
+        };
+        format!("self is {:?} ({class})", self.0)
+    }
+}
+
+pub trait FloatClassFallback {
+    fn __one_assert_float_class(&self) -> String;
+}
+
+impl<T: ?Sized> FloatClassFallback for FloatClass<'_, T> {
+    fn __one_assert_float_class(&self) -> String {
+        String::new()
+    }
+}
+
+/// A captured value that might be a smart pointer, for the note about its innermost value with the `deref`
+/// option. The `&'static str` is the name of the value.
+///
+/// Printed as `(&&&&DerefChain(&value, name)).__one_assert_deref()`, which uses autoref-specialization to pick
+/// the deepest of [`DerefThrice`], [`DerefTwice`] and [`DerefOnce`] whose target implements `Debug`, and
+/// [`DerefFallback`] for values that can't be dereferenced. The value is only ever borrowed, so nothing is moved
+/// out of the pointers.
+#[derive(Debug)]
+pub struct DerefChain<'a, T: ?Sized>(pub &'a T, pub &'static str);
+
+pub trait DerefThrice {
+    fn __one_assert_deref(&self) -> String;
+}
+
+impl<T: ?Sized + std::ops::Deref> DerefThrice for &&&DerefChain<'_, T>
+where
+    T::Target: std::ops::Deref,
+    <T::Target as std::ops::Deref>::Target: std::ops::Deref,
+    <<T::Target as std::ops::Deref>::Target as std::ops::Deref>::Target: std::fmt::Debug,
+{
+    fn __one_assert_deref(&self) -> String {
+        format!("***{} is {:?}", self.1, &****self.0)
+    }
+}
+
+pub trait DerefTwice {
+    fn __one_assert_deref(&self) -> String;
+}
+
+impl<T: ?Sized + std::ops::Deref> DerefTwice for &&DerefChain<'_, T>
+where
+    T::Target: std::ops::Deref,
+    <T::Target as std::ops::Deref>::Target: std::fmt::Debug,
+{
+    fn __one_assert_deref(&self) -> String {
+        format!("**{} is {:?}", self.1, &***self.0)
+    }
+}
+
+pub trait DerefOnce {
+    fn __one_assert_deref(&self) -> String;
+}
+
+impl<T: ?Sized + std::ops::Deref> DerefOnce for &DerefChain<'_, T>
+where
+    T::Target: std::fmt::Debug,
+{
+    fn __one_assert_deref(&self) -> String {
+        format!("*{} is {:?}", self.1, &**self.0)
+    }
+}
+
+pub trait DerefFallback {
+    fn __one_assert_deref(&self) -> String;
+}
+
+impl<T: ?Sized> DerefFallback for DerefChain<'_, T> {
+    fn __one_assert_deref(&self) -> String {
+        String::new()
+    }
+}
+
+/// A value in an assertion that might be a `serde_json::Value`, for the notes about JSON values.
+///
+/// Printed as `(&&JsonValue(&value)).__one_assert_json_container(name)` (or `__one_assert_json_kind`), which uses
+/// autoref-specialization to pick [`JsonDetailed`] for `serde_json::Value`s (and references to them), and
+/// [`JsonFallback`] for anything else. [`JsonDetailed`] is only implemented with the `serde_json` feature.
+#[derive(Debug)]
+pub struct JsonValue<'a, V: ?Sized>(pub &'a V);
+
+pub trait JsonDetailed {
+    fn __one_assert_json_container(&self, name: &str) -> String;
+    fn __one_assert_json_kind(&self, method: &str, negated: bool) -> String;
+}
+
+#[cfg(feature = "serde_json")]
+impl JsonDetailed for &JsonValue<'_, serde_json::Value> {
+    fn __one_assert_json_container(&self, name: &str) -> String {
+        json_container_note(self.0, name)
+    }
+    fn __one_assert_json_kind(&self, method: &str, negated: bool) -> String {
+        json_kind_note(self.0, method, negated)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl JsonDetailed for &JsonValue<'_, &serde_json::Value> {
+    fn __one_assert_json_container(&self, name: &str) -> String {
+        json_container_note(self.0, name)
+    }
+    fn __one_assert_json_kind(&self, method: &str, negated: bool) -> String {
+        json_kind_note(self.0, method, negated)
+    }
+}
+
+pub trait JsonFallback {
+    fn __one_assert_json_container(&self, name: &str) -> String;
+    fn __one_assert_json_kind(&self, method: &str, negated: bool) -> String;
+}
+
+impl<V: ?Sized> JsonFallback for JsonValue<'_, V> {
+    fn __one_assert_json_container(&self, _name: &str) -> String {
+        String::new()
+    }
+    fn __one_assert_json_kind(&self, _method: &str, _negated: bool) -> String {
+        String::new()
+    }
+}
+
+/// The type of a JSON value, like `a string` or `null`
+#[cfg(feature = "serde_json")]
+fn json_kind(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "a boolean",
+        serde_json::Value::Number(_) => "a number",
+        serde_json::Value::String(_) => "a string",
+        serde_json::Value::Array(_) => "an array",
+        serde_json::Value::Object(_) => "an object",
+    }
+}
+
+/// What an indexed JSON value contains: the keys of an object, the length of an array, or the type of anything
+/// else, which can't be indexed
+#[cfg(feature = "serde_json")]
+fn json_container_note(value: &serde_json::Value, name: &str) -> String {
+    match value {
+        serde_json::Value::Object(map) => {
+            let keys: Vec<&String> = map.keys().collect();
+            truncate(format!("keys of {name}: {keys:?}"))
+        }
+        serde_json::Value::Array(array) => format!("length of {name}: {}", array.len()),
+        value => format!("{name} is {}", json_kind(value)),
+    }
+}
+
+/// The actual type of a JSON value, compared to the type that a predicate like `is_string` checks for
+#[cfg(feature = "serde_json")]
+fn json_kind_note(value: &serde_json::Value, method: &str, negated: bool) -> String {
+    let expected = match method {
+        "is_null" => "null",
+        "is_boolean" => "a boolean",
+        "is_number" => "a number",
+        "is_i64" => "a number that fits in an i64",
+        "is_u64" => "a number that fits in a u64",
+        "is_f64" => "a floating point number",
+        "is_string" => "a string",
+        "is_array" => "an array",
+        "is_object" => "an object",
+        _ => return String::new(),
+    };
+    let actual = match value {
+        serde_json::Value::Number(number) => format!("the number {number}"),
+        value => json_kind(value).to_owned(),
+    };
+    if negated {
+        format!("self is {actual}, expected anything but {expected}")
+    } else {
+        format!("self is {actual}, expected {expected}")
+    }
+}
+
+/// The two sides of an `==` comparison, for the key-level diff of maps.
+///
+/// Printed as `(&&MapPair(&left, &right)).__one_assert_map_diff("left", "right")`, which uses
+/// autoref-specialization
+/// to pick [`MapDiff`] if both sides implement [`OneAssertMapLike`](crate::OneAssertMapLike), and
+/// [`MapDiffFallback`] otherwise.
+#[derive(Debug)]
+pub struct MapPair<'a, L: ?Sized, R: ?Sized>(pub &'a L, pub &'a R);
+
+pub trait MapDiff {
+    fn __one_assert_map_diff(&self, left_name: &str, right_name: &str) -> String;
+}
+
+impl<M: crate::OneAssertMapLike + ?Sized> MapDiff for &MapPair<'_, M, M> {
+    fn __one_assert_map_diff(&self, left_name: &str, right_name: &str) -> String {
+        map_diff(self.0, self.1, [left_name, right_name])
+    }
+}
+
+pub trait MapDiffFallback {
+    fn __one_assert_map_diff(&self, left_name: &str, right_name: &str) -> String;
+}
+
+impl<L: ?Sized, R: ?Sized> MapDiffFallback for MapPair<'_, L, R> {
+    fn __one_assert_map_diff(&self, _: &str, _: &str) -> String {
+        String::new()
+    }
+}
+
+/// The two sides of an `==` comparison, for the note about `NaN` elements in collections of floats.
+///
+/// Printed as `(&&NanPair(&left, &right)).__one_assert_nan_diff()`, which uses autoref-specialization
+/// to pick [`NanDiff`] if both sides are collections of the same float type (see [`FloatElements`]), and
+/// [`NanDiffFallback`] otherwise.
+#[derive(Debug)]
+pub struct NanPair<'a, L: ?Sized, R: ?Sized>(pub &'a L, pub &'a R);
+
+pub trait NanDiff {
+    fn __one_assert_nan_diff(&self) -> String;
+}
+
+impl<L, R> NanDiff for &NanPair<'_, L, R>
+where
+    L: FloatElements + ?Sized,
+    R: FloatElements<Element = L::Element> + ?Sized,
+{
+    fn __one_assert_nan_diff(&self) -> String {
+        let (left, right) = (self.0.float_elements(), self.1.float_elements());
+        let lines: Vec<String> = left
+            .iter()
+            .zip(right)
+            .enumerate()
+            .filter(|(_, (l, r))| l.is_nan() && r.is_nan())
+            .map(|(index, (l, r))| {
+                format!("index {index}: left={l:?} right={r:?}, NaN never equals itself")
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+pub trait NanDiffFallback {
+    fn __one_assert_nan_diff(&self) -> String;
+}
+
+impl<L: ?Sized, R: ?Sized> NanDiffFallback for NanPair<'_, L, R> {
+    fn __one_assert_nan_diff(&self) -> String {
+        String::new()
+    }
+}
+
+/// Collections of `f32` or `f64`, for [`NanDiff`]. A trait with an associated type instead of `AsRef<[T]>`,
+/// because a type can be `AsRef` of several slices
+pub trait FloatElements {
+    type Element: FloatBits;
+    fn float_elements(&self) -> &[Self::Element];
+}
+
+impl<T: FloatBits> FloatElements for [T] {
+    type Element = T;
+    fn float_elements(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: FloatBits, const N: usize> FloatElements for [T; N] {
+    type Element = T;
+    fn float_elements(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: FloatBits> FloatElements for Vec<T> {
+    type Element = T;
+    fn float_elements(&self) -> &[T] {
+        self
+    }
+}
+
+impl<S: FloatElements + ?Sized> FloatElements for &S {
+    type Element = S::Element;
+    fn float_elements(&self) -> &[S::Element] {
+        (**self).float_elements()
+    }
+}
+
+/// The two sides of an `==` or `!=` comparison with the `fingerprint` option.
+///
+/// Printed as `(&&FingerprintPair(&left, &right)).__one_assert_fingerprints()`, which uses autoref-specialization
+/// to pick [`Fingerprints`] if both sides implement [`Hash`](std::hash::Hash), and [`FingerprintsFallback`]
+/// otherwise.
+#[derive(Debug)]
+pub struct FingerprintPair<'a, L: ?Sized, R: ?Sized>(pub &'a L, pub &'a R);
+
+pub trait Fingerprints {
+    fn __one_assert_fingerprints(&self) -> String;
+}
+
+impl<L: std::hash::Hash + ?Sized, R: std::hash::Hash + ?Sized> Fingerprints
+    for &FingerprintPair<'_, L, R>
+{
+    fn __one_assert_fingerprints(&self) -> String {
+        let (left, right) = (hash_of(self.0), hash_of(self.1));
+        format!(" left fingerprint: {left:#018x}\nright fingerprint: {right:#018x}")
+    }
+}
+
+pub trait FingerprintsFallback {
+    fn __one_assert_fingerprints(&self) -> String;
+}
+
+impl<L: ?Sized, R: ?Sized> FingerprintsFallback for FingerprintPair<'_, L, R> {
+    fn __one_assert_fingerprints(&self) -> String {
+        String::new()
+    }
+}
+
+/// The two sides of a comparison, for the time between two points in time.
+///
+/// Printed as `(&&TimePair(&left, &right)).__one_assert_elapsed("end", "start")` with the code of the operands,
+/// which uses autoref-specialization to pick [`Elapsed`] if both sides are a [`TimePoint`], and [`ElapsedFallback`]
+/// otherwise.
+#[derive(Debug)]
+pub struct TimePair<'a, L: ?Sized, R: ?Sized>(pub &'a L, pub &'a R);
+
+/// A point in time like [`Instant`](std::time::Instant) or [`SystemTime`](std::time::SystemTime), whose `Debug`
+/// output says little on its own
+pub trait TimePoint {
+    /// The time from `earlier` to `self`, or the time from `self` to `earlier` as `Err` if `earlier` is later
+    fn one_assert_since(&self, earlier: &Self) -> Result<std::time::Duration, std::time::Duration>;
+}
+
+impl TimePoint for std::time::Instant {
+    fn one_assert_since(&self, earlier: &Self) -> Result<std::time::Duration, std::time::Duration> {
+        self.checked_duration_since(*earlier)
+            .ok_or_else(|| earlier.duration_since(*self))
+    }
+}
+
+impl TimePoint for std::time::SystemTime {
+    fn one_assert_since(&self, earlier: &Self) -> Result<std::time::Duration, std::time::Duration> {
+        self.duration_since(*earlier).map_err(|err| err.duration())
+    }
+}
+
+pub trait Elapsed {
+    fn __one_assert_elapsed(&self, left: &str, right: &str) -> String;
+}
+
+impl<T: TimePoint> Elapsed for &TimePair<'_, T, T> {
+    fn __one_assert_elapsed(&self, left: &str, right: &str) -> String {
+        match self.0.one_assert_since(self.1) {
+            Ok(elapsed) => format!("{left} - {right} = {elapsed:?}"),
+            Err(elapsed) => format!("{left} - {right} = -{elapsed:?}"),
+        }
+    }
+}
+
+pub trait ElapsedFallback {
+    fn __one_assert_elapsed(&self, left: &str, right: &str) -> String;
+}
+
+impl<L: ?Sized, R: ?Sized> ElapsedFallback for TimePair<'_, L, R> {
+    fn __one_assert_elapsed(&self, _left: &str, _right: &str) -> String {
+        String::new()
+    }
+}
+
+/// The receiver of a `ptr.is_aligned()` call in an assertion.
+///
+/// Printed as `(&&PointerAlignment(&ptr)).__one_assert_alignment()`, which uses autoref-specialization to pick
+/// [`PointerAlignmentDetailed`] for raw pointers to sized types, and [`PointerAlignmentFallback`] for anything
+/// else that happens to have an `is_aligned` method.
+#[derive(Debug)]
+pub struct PointerAlignment<'a, P: ?Sized>(pub &'a P);
+
+pub trait PointerAlignmentDetailed {
+    fn __one_assert_alignment(&self) -> String;
+}
+
+/// The required alignment of `T` and the actual alignment of `address`, which is the largest power of two
+/// that divides it. Only the address is used, the pointer is never dereferenced
+fn alignment_note<T>(address: usize) -> String {
+    #[allow(unused_qualifications)] // `align_of` is only in the prelude since Rust 1.80
+    let required = std::mem::align_of::<T>();
+    let actual = 1usize.checked_shl(address.trailing_zeros()).unwrap_or(0);
+    format!("required alignment: {required}\n  actual alignment: {actual}")
+}
+
+impl<T> PointerAlignmentDetailed for &PointerAlignment<'_, *const T> {
+    fn __one_assert_alignment(&self) -> String {
+        alignment_note::<T>(self.0.cast::<u8>() as usize)
+    }
+}
+
+impl<T> PointerAlignmentDetailed for &PointerAlignment<'_, *mut T> {
+    fn __one_assert_alignment(&self) -> String {
+        alignment_note::<T>(self.0.cast::<u8>() as usize)
+    }
+}
+
+pub trait PointerAlignmentFallback {
+    fn __one_assert_alignment(&self) -> String;
+}
+
+impl<P: ?Sized> PointerAlignmentFallback for PointerAlignment<'_, P> {
+    fn __one_assert_alignment(&self) -> String {
+        String::new()
+    }
+}
+
+/// List the keys that are only in one of the maps, and the keys with different values, one per line, with the
+/// `names` of the two maps. Returns an empty string if the maps are equal
+fn map_diff<M: crate::OneAssertMapLike + ?Sized>(left: &M, right: &M, names: [&str; 2]) -> String {
+    let [left_name, right_name] = names;
+    let only_in = |map: &M, other: &M| {
+        let keys = map.one_assert_keys().into_iter();
+        let missing: Vec<String> = keys
+            .filter(|key| other.one_assert_get(key).is_none())
+            .map(|key| format!("{key:?}"))
+            .collect();
+        missing.join(", ")
+    };
+
+    let mut lines = vec![];
+    let only_left = only_in(left, right);
+    if !only_left.is_empty() {
+        lines.push(format!("only in {left_name}: [{only_left}]"));
+    }
+    let only_right = only_in(right, left);
+    if !only_right.is_empty() {
+        lines.push(format!("only in {right_name}: [{only_right}]"));
+    }
+    for key in left.one_assert_keys() {
+        let (Some(l), Some(r)) = (left.one_assert_get(key), right.one_assert_get(key)) else {
+            continue;
+        };
+        if l != r {
+            let (l, r) = (truncate(format!("{l:?}")), truncate(format!("{r:?}")));
+            lines.push(format!("key {key:?}: {left_name}={l} {right_name}={r}"));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Records the element that decided the result of an `iter.all(|x| ...)` or `iter.any(|x| ...)` call in an
+/// assertion.
+///
+/// The original method of the receiver is still called, but with a predicate that wraps the original one and
+/// passes each of its results to [`PredicateRecorder::check`]. That way, any receiver with an `all` or `any`
+/// method works, not only an [`Iterator`]. The recorder is `Sync`, so that parallel iterators can use it too.
+#[derive(Debug, Default)]
+pub struct PredicateRecorder {
+    checked: std::sync::atomic::AtomicUsize,
+    decided: std::sync::Mutex<Option<String>>,
+}
+
+impl PredicateRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count an element that the predicate returned `result` for, and remember it with `describe` if it decided
+    /// the result, which is the first one where `result == stop_at` (`false` for `all`, `true` for `any`).
+    /// Returns `result`
+    pub fn check(&self, result: bool, stop_at: bool, describe: impl FnOnce() -> String) -> bool {
+        let index = self
+            .checked
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        if result == stop_at {
+            let mut decided = self
+                .decided
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if decided.is_none() {
+                *decided = Some(format!("{} (at index {index})", describe()));
+            }
+        }
+        result
+    }
+
+    /// The element that decided the result, or how many elements were checked if none did
+    pub fn detail(&self) -> String {
+        let decided = self
+            .decided
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match &*decided {
+            Some(detail) => detail.clone(),
+            None => format!(
+                "none (checked {} elements)",
+                self.checked.load(std::sync::atomic::Ordering::SeqCst)
+            ),
+        }
+    }
+}
+
+/// An element of an `all` or `any` call, which is copied before it is passed to the predicate, so that it can
+/// still be printed if it decides the result.
+///
+/// Copied as `(&&KeepElement(&element)).__one_assert_keep()`, which uses autoref-specialization to pick
+/// [`KeepElementDetailed`] if the element is `Clone + Debug`, and [`KeepElementFallback`] otherwise. The copy
+/// is printed with [`DescribeKept`]
+#[derive(Debug)]
+pub struct KeepElement<'a, T>(pub &'a T);
+
+/// A copy of an element that can be printed
+pub trait KeepElementDetailed {
+    type Kept;
+    fn __one_assert_keep(&self) -> Self::Kept;
+}
+
+impl<T: Clone + std::fmt::Debug> KeepElementDetailed for &KeepElement<'_, T> {
+    type Kept = Option<T>;
+    fn __one_assert_keep(&self) -> Option<T> {
+        Some(self.0.clone())
+    }
+}
+
+/// No copy for elements that can't be printed
+pub trait KeepElementFallback {
+    fn __one_assert_keep(&self) -> NotKept;
+}
+
+impl<T> KeepElementFallback for KeepElement<'_, T> {
+    fn __one_assert_keep(&self) -> NotKept {
+        NotKept
+    }
+}
+
+/// Returned by [`KeepElementFallback`] for elements that are not `Clone + Debug`
+#[derive(Debug, Clone, Copy)]
+pub struct NotKept;
+
+/// The result of [`KeepElement`], printed as `(&&DescribeKept(&kept)).__one_assert_describe()` with
+/// [`DescribeKeptDetailed`] for copied elements, and [`DescribeKeptFallback`] otherwise
+#[derive(Debug)]
+pub struct DescribeKept<'a, T>(pub &'a T);
+
+/// Print a copied element
+pub trait DescribeKeptDetailed {
+    fn __one_assert_describe(&self) -> String;
+}
+
+impl<T: std::fmt::Debug> DescribeKeptDetailed for &DescribeKept<'_, Option<T>> {
+    fn __one_assert_describe(&self) -> String {
+        match self.0 {
+            Some(element) => format!("{element:?}"),
+            None => String::from("<element is not Clone + Debug>"),
+        }
+    }
+}
+
+/// The placeholder for elements that could not be copied
+pub trait DescribeKeptFallback {
+    fn __one_assert_describe(&self) -> String;
+}
+
+impl<T> DescribeKeptFallback for DescribeKept<'_, T> {
+    fn __one_assert_describe(&self) -> String {
+        String::from("<element is not Clone + Debug>")
+    }
+}
+
+/// A value that should be debug-printed with the `precision = N` option.
+///
+/// Printed as `(&&WithPrecision(&value, N)).__one_assert_debug()`, which uses autoref-specialization
+/// to pick [`FloatPrecision`] for `f32` and `f64`, and [`FloatPrecisionFallback`] for everything else.
+#[derive(Debug)]
+pub struct WithPrecision<'a, T: ?Sized>(pub &'a T, pub usize);
+
+/// Debug-print a float with the given precision
+pub trait FloatPrecision {
+    fn __one_assert_debug(&self) -> String;
+}
+
+macro_rules! impl_float_precision {
+    ($($ty:ty),*) => {$(
+        impl FloatPrecision for &WithPrecision<'_, $ty> {
+            fn __one_assert_debug(&self) -> String {
+                format!("{:.*?}", self.1, self.0)
+            }
+        }
+    )*};
+}
+// references are included, because method arguments like `v.contains(&1.5)` are often borrowed floats
+impl_float_precision!(f32, f64, &f32, &f64);
+
+/// Debug-print any other value as usual
+pub trait FloatPrecisionFallback {
+    fn __one_assert_debug(&self) -> String;
+}
+
+impl<T: std::fmt::Debug + ?Sized> FloatPrecisionFallback for WithPrecision<'_, T> {
+    fn __one_assert_debug(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Floats that can be compared with `assert_bits_eq!`. Implemented for `f32` and `f64` only
+pub trait FloatBits: Copy + std::fmt::Debug + sealed::Sealed {
+    /// Number of hex digits in the bit pattern
+    const HEX_DIGITS: usize;
+    /// The bit pattern of the value, same as `to_bits`
+    fn bits(self) -> u64;
+    /// Same as the inherent `is_nan`
+    fn is_nan(self) -> bool;
+    /// Same as the inherent `classify`
+    fn classify(self) -> std::num::FpCategory;
+}
+
+macro_rules! impl_float_bits {
+    ($($ty:ty: $digits:literal),*) => {$(
+        impl FloatBits for $ty {
+            const HEX_DIGITS: usize = $digits;
+            fn bits(self) -> u64 {
+                u64::from(self.to_bits())
+            }
+            fn is_nan(self) -> bool {
+                <$ty>::is_nan(self)
+            }
+            fn classify(self) -> std::num::FpCategory {
+                <$ty>::classify(self)
+            }
+        }
+    )*};
+}
+impl_float_bits!(f32: 8, f64: 16);
+
+/// Compare the bit patterns of two floats for `assert_bits_eq!`.
+/// Returns the details for the failure message if they differ.
+pub fn bits_eq_failure<T: FloatBits>(left: T, right: T) -> Option<String> {
+    let (left_bits, right_bits) = (left.bits(), right.bits());
+    if left_bits == right_bits {
+        return None;
+    }
+    let hex = |bits: u64| format!("{bits:#0width$x}", width = T::HEX_DIGITS + 2);
+    let mut values = vec![
+        ("left", format!("{left:?}")),
+        ("right", format!("{right:?}")),
+        ("left bits", hex(left_bits)),
+        ("right bits", hex(right_bits)),
+    ];
+
+    let sign = 1u64 << (T::HEX_DIGITS * 4 - 1);
+    let only_sign_differs = left_bits ^ right_bits == sign;
+    if left.is_nan() && right.is_nan() {
+        let note = if only_sign_differs {
+            "sign of NaN differs"
+        } else {
+            "NaN payload differs"
+        };
+        values.push(("note", note.to_owned()));
+    } else if only_sign_differs && left_bits & !sign == 0 {
+        values.push((
+            "note",
+            format!("sign of zero differs: {left:?} vs {right:?}"),
+        ));
+    }
+    Some(format_values(&values))
+}
+
+/// A compared value that should be printed with its discriminant with the `discriminant` option.
+///
+/// Printed as `(&&WithDiscriminant(&value)).__one_assert_discriminant()`, which uses autoref-specialization
+/// to pick [`DiscriminantSuffix`] for types that implement [`OneAssertDiscriminant`](crate::OneAssertDiscriminant),
+/// and [`DiscriminantSuffixFallback`] for everything else.
+#[derive(Debug)]
+pub struct WithDiscriminant<'a, T: ?Sized>(pub &'a T);
+
+/// The discriminant in parentheses, to be appended to the printed value
+pub trait DiscriminantSuffix {
+    fn __one_assert_discriminant(&self) -> String;
+}
+
+impl<T: crate::OneAssertDiscriminant + ?Sized> DiscriminantSuffix for &WithDiscriminant<'_, T> {
+    fn __one_assert_discriminant(&self) -> String {
+        format!(" ({:?})", self.0.discriminant_value())
+    }
+}
+
+/// Nothing to append for values without a discriminant
+pub trait DiscriminantSuffixFallback {
+    fn __one_assert_discriminant(&self) -> String;
+}
+
+impl<T: ?Sized> DiscriminantSuffixFallback for WithDiscriminant<'_, T> {
+    fn __one_assert_discriminant(&self) -> String {
+        String::new()
+    }
+}
+
+/// The receiver of an `unwrap()` or `expect(...)` call in a field access chain with the `verbose` option.
+///
+/// Checked as `(&&UnwrapCheck(&receiver)).__one_assert_unwrap_failure()` before the call, which uses
+/// autoref-specialization to pick [`UnwrapFailure`] for `Option`s and `Result`s, and [`UnwrapFailureFallback`]
+/// for types with their own `unwrap` method.
+#[derive(Debug)]
+pub struct UnwrapCheck<'a, T: ?Sized>(pub &'a T);
+
+/// Describes the value if the call would panic: `None` or `Err(...)`
+pub trait UnwrapFailure {
+    fn __one_assert_unwrap_failure(&self) -> Option<String>;
+}
+
+impl<T> UnwrapFailure for &UnwrapCheck<'_, Option<T>> {
+    fn __one_assert_unwrap_failure(&self) -> Option<String> {
+        self.0.is_none().then(|| "None".to_owned())
+    }
+}
+
+impl<T, E: std::fmt::Debug> UnwrapFailure for &UnwrapCheck<'_, Result<T, E>> {
+    fn __one_assert_unwrap_failure(&self) -> Option<String> {
+        let error = self.0.as_ref().err()?;
+        Some(truncate(format!("Err({error:?})")))
+    }
+}
+
+/// Nothing is known about other types, so the call is left to decide
+pub trait UnwrapFailureFallback {
+    fn __one_assert_unwrap_failure(&self) -> Option<String>;
+}
+
+impl<T: ?Sized> UnwrapFailureFallback for UnwrapCheck<'_, T> {
+    fn __one_assert_unwrap_failure(&self) -> Option<String> {
+        None
+    }
+}
+
+/// The result of a `lock()`, `read()` or `write()` call that is unwrapped in the condition.
+///
+/// Checked as `(&&LockCheck(&result)).__one_assert_poisoned()` before the `unwrap`, which uses
+/// autoref-specialization to pick [`LockPoisoned`] for the [`LockResult`](std::sync::LockResult)s of `Mutex`
+/// and `RwLock`, and [`LockPoisonedFallback`] for other types with a method of the same name.
+#[derive(Debug)]
+pub struct LockCheck<'a, T: ?Sized>(pub &'a T);
+
+/// Checks if the lock is poisoned
+pub trait LockPoisoned {
+    fn __one_assert_poisoned(&self) -> bool;
+}
+
+impl<G> LockPoisoned for &LockCheck<'_, std::sync::LockResult<G>> {
+    fn __one_assert_poisoned(&self) -> bool {
+        self.0.is_err()
+    }
+}
+
+/// Nothing is known about other types, so the call is left to decide
+pub trait LockPoisonedFallback {
+    fn __one_assert_poisoned(&self) -> bool;
+}
+
+impl<T: ?Sized> LockPoisonedFallback for LockCheck<'_, T> {
+    fn __one_assert_poisoned(&self) -> bool {
+        false
+    }
+}
+
+/// The value of an `assert_empty!`.
+///
+/// Checked as `(&&Leftover::new(value)).__one_assert_leftover()`, which uses autoref-specialization to pick
+/// [`LeftoverExhaustible`] for types that implement [`OneAssertExhaustible`](crate::OneAssertExhaustible),
+/// and [`LeftoverIterator`] for iterators.
+pub struct Leftover<T>(std::cell::RefCell<T>);
+
+impl<T> Leftover<T> {
+    pub fn new(value: T) -> Self {
+        Self(std::cell::RefCell::new(value))
+    }
+}
+
+impl<T> std::fmt::Debug for Leftover<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("Leftover")
+    }
+}
+
+/// Takes the next element and returns the details for the failure message if there is one
+pub trait LeftoverExhaustible {
+    fn __one_assert_leftover(&self) -> Option<String>;
+}
+
+impl<T: crate::OneAssertExhaustible> LeftoverExhaustible for &Leftover<T>
+where
+    T::Item: std::fmt::Debug,
+{
+    fn __one_assert_leftover(&self) -> Option<String> {
+        let element = self.0.borrow_mut().one_assert_next()?;
+        Some(format_values(&[(
+            "leftover",
+            truncate(format!("{element:?}")),
+        )]))
+    }
+}
+
+/// Same as [`LeftoverExhaustible`] for iterators
+pub trait LeftoverIterator {
+    fn __one_assert_leftover(&self) -> Option<String>;
+}
+
+impl<I: Iterator> LeftoverIterator for Leftover<I>
+where
+    I::Item: std::fmt::Debug,
+{
+    fn __one_assert_leftover(&self) -> Option<String> {
+        let element = self.0.borrow_mut().next()?;
+        Some(format_values(&[(
+            "leftover",
+            truncate(format!("{element:?}")),
+        )]))
+    }
+}
@@ -0,0 +1,106 @@
+/// The check behind [`assert_contains!`](crate::assert_contains).
+///
+/// The macro calls the methods with method-call syntax on a reference to the haystack, so
+/// auto-deref applies: A `String` or `&str` haystack uses the implementation for `str`, a `Vec<T>`
+/// or `&[T]` the one for collections.
+///
+/// Implement this for your own types to use them as a haystack:
+/// ```
+/// use one_assert::OneAssertContains;
+///
+/// #[derive(Debug)]
+/// struct Range(u32, u32);
+///
+/// impl OneAssertContains<u32> for Range {
+///     fn one_assert_contains(&self, needle: &u32) -> bool {
+///         (self.0..self.1).contains(needle)
+///     }
+/// }
+///
+/// one_assert::assert_contains!(Range(1, 10), 5);
+/// ```
+pub trait OneAssertContains<Needle: ?Sized> {
+    /// Checks if `self` contains `needle`
+    fn one_assert_contains(&self, needle: &Needle) -> bool;
+
+    /// Describes the part of `self` that comes closest to `needle`, if there is such a thing.
+    /// Only called if [`one_assert_contains`](Self::one_assert_contains) returned `false`.
+    fn one_assert_near_match(&self, needle: &Needle) -> Option<String> {
+        let _ = needle;
+        None
+    }
+}
+
+impl<'a> OneAssertContains<&'a str> for str {
+    fn one_assert_contains(&self, needle: &&'a str) -> bool {
+        self.contains(*needle)
+    }
+    fn one_assert_near_match(&self, needle: &&'a str) -> Option<String> {
+        closest_substring(self, needle)
+    }
+}
+
+impl OneAssertContains<String> for str {
+    fn one_assert_contains(&self, needle: &String) -> bool {
+        self.contains(needle.as_str())
+    }
+    fn one_assert_near_match(&self, needle: &String) -> Option<String> {
+        closest_substring(self, needle)
+    }
+}
+
+impl OneAssertContains<char> for str {
+    fn one_assert_contains(&self, needle: &char) -> bool {
+        self.contains(*needle)
+    }
+}
+
+impl<T: PartialEq> OneAssertContains<T> for [T] {
+    fn one_assert_contains(&self, needle: &T) -> bool {
+        self.contains(needle)
+    }
+}
+
+/// Any collection that can be iterated by reference. `str` and `[T]` are covered by the impls above,
+/// since they are unsized and thus don't match `C`.
+impl<C, T: PartialEq> OneAssertContains<T> for C
+where
+    for<'a> &'a C: IntoIterator<Item = &'a T>,
+{
+    fn one_assert_contains(&self, needle: &T) -> bool {
+        self.into_iter().any(|element| element == needle)
+    }
+}
+
+/// Finds the position in `haystack` where the most characters line up with `needle`
+fn closest_substring(haystack: &str, needle: &str) -> Option<String> {
+    let needle = needle.chars().collect::<Vec<_>>();
+    let haystack = haystack.char_indices().collect::<Vec<_>>();
+
+    let mut best = (0, 0); // (matching chars, start index into `haystack`)
+    for start in 0..haystack.len() {
+        let matching = haystack[start..]
+            .iter()
+            .zip(&needle)
+            .filter(|((_, a), b)| a == *b)
+            .count();
+        if matching > best.0 {
+            best = (matching, start);
+        }
+    }
+
+    let (matching, start) = best;
+    if matching == 0 {
+        return None;
+    }
+    let window = haystack[start..]
+        .iter()
+        .take(needle.len())
+        .map(|(_, c)| c)
+        .collect::<String>();
+    Some(format!(
+        "{window:?} at index {} ({matching} of {} characters match)",
+        haystack[start].0,
+        needle.len()
+    ))
+}
@@ -0,0 +1,21 @@
+/// Access to the numeric discriminant of an enum, for the `discriminant` option of [`assert!`](crate::assert).
+///
+/// Usually derived with the derive macro of the same name, which works for enums without fields:
+/// ```
+/// #[derive(Debug, PartialEq, one_assert::OneAssertDiscriminant)]
+/// #[repr(u8)]
+/// enum Tag {
+///     A = 1,
+///     B = 2,
+/// }
+///
+/// use one_assert::OneAssertDiscriminant;
+/// assert_eq!(Tag::B.discriminant_value(), 2u8);
+/// ```
+pub trait OneAssertDiscriminant {
+    /// The type of the discriminant, which is the type from `#[repr(...)]` for derived implementations
+    type Discriminant: std::fmt::Debug;
+
+    /// The numeric discriminant of `self`
+    fn discriminant_value(&self) -> Self::Discriminant;
+}
@@ -0,0 +1,52 @@
+use std::sync::mpsc::Receiver;
+
+/// Sources of elements that can be checked with [`assert_empty!`](crate::assert_empty), other than iterators.
+///
+/// Iterators are supported by the macro directly. This trait is implemented for the receivers of
+/// [`std::sync::mpsc`] channels, which are checked with `try_recv`, so the check never blocks.
+///
+/// Implement this for other channel types (or anything else that can be polled for a next element) to use
+/// them with the macro:
+/// ```
+/// use one_assert::OneAssertExhaustible;
+///
+/// struct Queue(Vec<u32>);
+///
+/// impl OneAssertExhaustible for Queue {
+///     type Item = u32;
+///     fn one_assert_next(&mut self) -> Option<u32> {
+///         self.0.pop()
+///     }
+/// }
+///
+/// one_assert::assert_empty!(Queue(vec![]));
+/// ```
+pub trait OneAssertExhaustible {
+    /// The elements that are produced
+    type Item;
+
+    /// Take the next element, if there is one. Must not block
+    fn one_assert_next(&mut self) -> Option<Self::Item>;
+}
+
+impl<T> OneAssertExhaustible for Receiver<T> {
+    type Item = T;
+    fn one_assert_next(&mut self) -> Option<T> {
+        (&*self).one_assert_next()
+    }
+}
+
+impl<T> OneAssertExhaustible for &Receiver<T> {
+    type Item = T;
+    fn one_assert_next(&mut self) -> Option<T> {
+        // a disconnected channel can't produce any more elements either, so both errors mean "empty"
+        self.try_recv().ok()
+    }
+}
+
+impl<T: OneAssertExhaustible + ?Sized> OneAssertExhaustible for &mut T {
+    type Item = T::Item;
+    fn one_assert_next(&mut self) -> Option<T::Item> {
+        (**self).one_assert_next()
+    }
+}
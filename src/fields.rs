@@ -0,0 +1,31 @@
+/// Field-by-field comparison of a struct, for [`assert_eq_ignoring!`](crate::assert_eq_ignoring) and
+/// [`assert_fields_eq!`](crate::assert_fields_eq).
+///
+/// Usually derived with the derive macro of the same name, which works for structs whose fields implement
+/// `PartialEq` and `Debug`:
+/// ```
+/// #[derive(Debug, one_assert::OneAssertFields)]
+/// struct Event {
+///     name: String,
+///     timestamp: u64,
+/// }
+///
+/// use one_assert::OneAssertFields;
+/// let a = Event { name: "start".to_string(), timestamp: 1 };
+/// let b = Event { name: "start".to_string(), timestamp: 2 };
+/// assert_eq!(Event::FIELD_NAMES, ["name", "timestamp"]);
+/// assert!(a.differing_fields(&b, &["timestamp"]).is_empty());
+/// ```
+pub trait OneAssertFields {
+    /// The names of all fields in declaration order. Fields of tuple structs are named by their index
+    const FIELD_NAMES: &'static [&'static str];
+
+    /// Compare the fields of `self` and `other` one by one, skipping the fields named in `ignored`.
+    ///
+    /// Returns the name and the `Debug` output of both values for every field that differs.
+    fn differing_fields(
+        &self,
+        other: &Self,
+        ignored: &[&str],
+    ) -> Vec<(&'static str, String, String)>;
+}
@@ -0,0 +1,73 @@
+use std::sync::OnceLock;
+
+/// The signature of a formatter for [`set_formatter`]
+pub type Formatter = fn(&AssertFailure) -> String;
+
+static FORMATTER: OnceLock<Formatter> = OnceLock::new();
+
+/// Replace the message format of all failed assertions with a custom one, e.g. to follow the house style
+/// of an application.
+///
+/// The `formatter` gets the parts of the message as an [`AssertFailure`] and returns the final message,
+/// which is then used for the panic (or the warning, or the error of [`try_assert!`](crate::try_assert)).
+/// This applies to [`assert!`](crate::assert), [`try_assert!`](crate::try_assert),
+/// [`assert_that!`](crate::assert_that) and [`assert_unchanged!`](crate::assert_unchanged). The other
+/// assertions keep their default message.
+///
+/// Similar to [`std::panic::set_hook`], this is global for the entire program. Unlike it, the formatter can only
+/// be set once, so that it can be read without locking. If a formatter was already set, the new one is returned
+/// as the error.
+/// ```
+/// fn house_style(failure: &one_assert::AssertFailure) -> String {
+///     let values = failure.values.iter().map(|(name, value)| format!("{name}={value}"));
+///     format!("CHECK FAILED [{}] {}", failure.expression, values.collect::<Vec<_>>().join(" "))
+/// }
+/// one_assert::set_formatter(house_style).unwrap();
+///
+/// let x = 1;
+/// let result = std::panic::catch_unwind(|| { one_assert::assert!(x == 2); });
+/// let message = result.unwrap_err().downcast::<String>().unwrap();
+/// assert_eq!(*message, "CHECK FAILED [x == 2] left=1 right=2");
+/// ```
+pub fn set_formatter(formatter: Formatter) -> Result<(), Formatter> {
+    FORMATTER.set(formatter)
+}
+
+/// The formatter from [`set_formatter`], if there is one
+pub(crate) fn formatter() -> Option<Formatter> {
+    FORMATTER.get().copied()
+}
+
+/// The parts of the message of a failed assertion, for a formatter that was set with [`set_formatter`].
+///
+/// All values are already printed with `Debug` (or the format from the options of the assertion), and contain
+/// the same text as the default message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AssertFailure {
+    /// The asserted expression as it was written, or the description of the check for macros like
+    /// [`assert_that!`](crate::assert_that)
+    pub expression: String,
+    /// The custom message of the assertion, if one was given
+    pub message: Option<String>,
+    /// The captured values of the expression as `(name, value)` pairs, like `("left", "1")`
+    pub values: Vec<(String, String)>,
+    /// Additional notes, like the keys that differ between two maps
+    pub notes: Vec<String>,
+    /// The "caused by" chain from the outermost to the innermost check, for conditions like `if` or `match`
+    pub causes: Vec<FailureCause>,
+    /// The message that is used without a formatter
+    pub default_message: String,
+}
+
+/// A "caused by" line of an [`AssertFailure`], along with the values that were captured below it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FailureCause {
+    /// The description of the check, like ``block return assertion `x > 0` failed``
+    pub description: String,
+    /// The captured values as `(name, value)` pairs
+    pub values: Vec<(String, String)>,
+    /// Additional notes
+    pub notes: Vec<String>,
+}
@@ -0,0 +1,60 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+
+/// Maps that get a key-level diff when an `==` comparison of two of them fails in [`assert!`](crate::assert).
+///
+/// Instead of only printing both maps, the failure message lists the keys that are only in one of the maps,
+/// and the keys whose values differ:
+/// ```text
+/// assertion `a == b` failed
+///      left: {"a": 1, "c": 3}
+///     right: {"a": 2, "b": 2}
+///   only in left: ["c"]
+///   only in right: ["b"]
+///   key "a": left=1 right=2
+/// ```
+/// Implemented for [`HashMap`] and [`BTreeMap`]. Other maps are printed as usual, unless they implement this trait.
+pub trait OneAssertMapLike {
+    /// The key type of the map
+    type Key: Debug;
+    /// The value type of the map
+    type Value: PartialEq + Debug;
+
+    /// All keys of the map, in a deterministic order
+    fn one_assert_keys(&self) -> Vec<&Self::Key>;
+
+    /// The value for `key`, if it is in the map
+    fn one_assert_get(&self, key: &Self::Key) -> Option<&Self::Value>;
+}
+
+impl<K: Eq + Hash + Debug, V: PartialEq + Debug, S: BuildHasher> OneAssertMapLike
+    for HashMap<K, V, S>
+{
+    type Key = K;
+    type Value = V;
+
+    fn one_assert_keys(&self) -> Vec<&K> {
+        // the iteration order of a `HashMap` is random, so the keys are sorted by their output instead
+        let mut keys: Vec<(String, &K)> = self.keys().map(|k| (format!("{k:?}"), k)).collect();
+        keys.sort_by(|(a, _), (b, _)| a.cmp(b));
+        keys.into_iter().map(|(_, k)| k).collect()
+    }
+
+    fn one_assert_get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+}
+
+impl<K: Ord + Debug, V: PartialEq + Debug> OneAssertMapLike for BTreeMap<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn one_assert_keys(&self) -> Vec<&K> {
+        self.keys().collect()
+    }
+
+    fn one_assert_get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+}
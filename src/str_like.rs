@@ -0,0 +1,68 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// String types that can be compared with [`assert_similar!`](crate::assert_similar) and
+/// [`assert_matches_glob!`](crate::assert_matches_glob).
+///
+/// Implemented for [`str`], [`String`], [`Cow<str>`](Cow), `Box<str>`, `Rc<str>`, `Arc<str>` and references
+/// to any of these. Implement this for your own string types to use them in the macro:
+/// ```
+/// use one_assert::OneAssertStrLike;
+///
+/// struct Name(String);
+///
+/// impl OneAssertStrLike for Name {
+///     fn one_assert_str(&self) -> &str {
+///         &self.0
+///     }
+/// }
+///
+/// one_assert::assert_similar!(Name("Jane Doe".to_string()), "Jane Dow");
+/// ```
+pub trait OneAssertStrLike {
+    /// The text that is compared
+    fn one_assert_str(&self) -> &str;
+}
+
+impl OneAssertStrLike for str {
+    fn one_assert_str(&self) -> &str {
+        self
+    }
+}
+
+impl OneAssertStrLike for String {
+    fn one_assert_str(&self) -> &str {
+        self
+    }
+}
+
+impl OneAssertStrLike for Cow<'_, str> {
+    fn one_assert_str(&self) -> &str {
+        self
+    }
+}
+
+impl OneAssertStrLike for Box<str> {
+    fn one_assert_str(&self) -> &str {
+        self
+    }
+}
+
+impl OneAssertStrLike for Rc<str> {
+    fn one_assert_str(&self) -> &str {
+        self
+    }
+}
+
+impl OneAssertStrLike for Arc<str> {
+    fn one_assert_str(&self) -> &str {
+        self
+    }
+}
+
+impl<T: OneAssertStrLike + ?Sized> OneAssertStrLike for &T {
+    fn one_assert_str(&self) -> &str {
+        (**self).one_assert_str()
+    }
+}
@@ -0,0 +1,100 @@
+/// An error from a `?` operator inside of a [`try_assert!`](crate::try_assert) condition.
+///
+/// ```
+/// fn parse_positive(s: &str) -> Result<bool, Box<dyn std::error::Error>> {
+///     one_assert::try_assert!(s.parse::<i32>()? > 0)?;
+///     Ok(true)
+/// }
+///
+/// let err = parse_positive("abc").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "`s.parse::<i32>()` failed while evaluating assertion `s.parse::<i32>()? > 0`: invalid digit found in string"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryAssertError<E> {
+    /// The entire condition of the assertion
+    pub assertion: &'static str,
+    /// The expression that the `?` was applied to
+    pub expression: &'static str,
+    /// Which of the `?`s in the condition failed, starting at 1 in the order of the source code
+    pub position: usize,
+    /// The number of `?`s in the condition
+    pub operators: usize,
+    /// The original error
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for TryAssertError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` failed", self.expression)?;
+        if self.operators > 1 {
+            write!(f, " (`?` {} of {})", self.position, self.operators)?;
+        }
+        write!(
+            f,
+            " while evaluating assertion `{}`: {}",
+            self.assertion, self.error
+        )
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for TryAssertError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A failed [`try_assert!`](crate::try_assert) condition.
+///
+/// Both the `Display` and the `Debug` output are the full message that [`assert!`](crate::assert) would
+/// panic with, so that the test harness prints it legibly when it is returned from a `#[test]` function:
+/// ```
+/// #[derive(Debug, PartialEq)]
+/// struct Config { retries: u32 }
+///
+/// fn check(config: &Config) -> Result<(), one_assert::OneAssertError> {
+///     one_assert::try_assert!(config.retries > 0, "retries are required")?;
+///     Ok(())
+/// }
+///
+/// let err = check(&Config { retries: 0 }).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "assertion `config.retries > 0` failed: retries are required
+///      left: 0
+///     right: 0"
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct OneAssertError {
+    message: String,
+}
+
+impl OneAssertError {
+    #[doc(hidden)]
+    pub fn new(message: String) -> Self {
+        Self { message }
+    }
+
+    /// The failure message, without any formatting added
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for OneAssertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// `Termination` for `Result` prints the error with `Debug`, which would escape the newlines of the message
+impl std::fmt::Debug for OneAssertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for OneAssertError {}
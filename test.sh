@@ -73,6 +73,7 @@ for dir in "${MSRV_DIR}" "${MIN_VERSIONS_DIR}"; do
     ln -s "../../Cargo.toml" "${dir}/Cargo.toml"
     ln -s "../../src" "${dir}/src"
     ln -s "../../tests" "${dir}/tests"
+    ln -s "../../one_assert_macro" "${dir}/one_assert_macro"
 done
 
 export RUSTFLAGS="-D warnings"
@@ -86,6 +87,7 @@ try_silent rustup update
 try_silent cargo update
 try_silent cargo +stable test
 try_silent cargo +nightly test
+try_silent cargo +stable test --features eager_print
 
 if [[ OVERWRITE -eq 1 ]]; then
     echo "Trybuild overwrite mode enabled"
@@ -125,6 +127,7 @@ try_silent cargo +nightly -Z minimal-versions update
 
 try_silent cargo +stable test
 try_silent cargo +nightly test
+try_silent cargo +stable test --features eager_print
 
 ########
 echo "All tests passed!"
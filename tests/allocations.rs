@@ -0,0 +1,95 @@
+//! The allocator is global, so allocations are counted in their own binary
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    /// Counted per thread, since the other tests might run in parallel
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn test_passing_comparison() {
+    let a = vec![1, 2, 3];
+    let b = a.clone();
+    let boxed: Box<[i32]> = b.clone().into_boxed_slice();
+
+    // the operands of comparisons are only borrowed, so they are only printed if the assertion fails
+    assert_eq!(
+        allocations(|| {
+            one_assert::assert!(a == b);
+        }),
+        0
+    );
+    assert_eq!(
+        allocations(|| {
+            one_assert::assert!(a.len() < 4);
+        }),
+        0
+    );
+    assert_eq!(
+        allocations(|| {
+            one_assert::assert!(*boxed == *a.as_slice());
+        }),
+        0
+    );
+    assert_eq!(
+        allocations(|| {
+            one_assert::assert!({ a != [4] });
+        }),
+        0
+    );
+
+    // values that might be moved by the condition are still printed in advance, as well as values with a
+    // custom format
+    assert_ne!(
+        allocations(|| {
+            one_assert::assert!(a.contains(&2));
+        }),
+        0
+    );
+    assert_ne!(
+        allocations(|| {
+            one_assert::assert!(a == b, clean);
+        }),
+        0
+    );
+}
+
+#[test]
+fn test_failing_comparison() {
+    let a = vec![1, 2, 3];
+    let b = vec![1, 2, 4];
+    let message = std::panic::catch_unwind(|| {
+        one_assert::assert!(a == b);
+    })
+    .unwrap_err();
+    assert_eq!(
+        *message.downcast::<String>().unwrap(),
+        "assertion `a == b` failed
+     left: [1, 2, 3]
+    right: [1, 2, 4]"
+    );
+}
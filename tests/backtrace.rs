@@ -0,0 +1,33 @@
+//! Only with the `backtrace` feature: `cargo test --features backtrace`
+#![cfg(feature = "backtrace")]
+
+#[test]
+fn test_backtrace() {
+    #[inline(never)]
+    fn check_positive(x: i32) {
+        one_assert::assert!(x > 0);
+    }
+    check_positive(1);
+
+    // `RUST_BACKTRACE` alone only affects the panic hook, not the message
+    std::env::set_var("RUST_BACKTRACE", "1");
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+    let error = std::panic::catch_unwind(|| check_positive(-1)).unwrap_err();
+    assert!(!error.downcast::<String>().unwrap().contains("backtrace"));
+
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    let error = std::panic::catch_unwind(|| check_positive(-1)).unwrap_err();
+    let message = error.downcast::<String>().unwrap();
+    let (values, backtrace) = message.split_once("\n\nbacktrace:\n").unwrap();
+    assert_eq!(
+        values,
+        "assertion `x > 0` failed
+     left: -1
+    right: 0"
+    );
+    assert!(backtrace.contains("check_positive"), "{backtrace}");
+
+    let result = one_assert::try_assert!(1 + 1 == 3);
+    assert!(result.unwrap_err().to_string().contains("\n\nbacktrace:\n"));
+}
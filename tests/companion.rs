@@ -0,0 +1,1188 @@
+macro_rules! assert_throws {
+    ( $block:block, $message:expr $(,)? ) => {
+        let error = std::panic::catch_unwind(|| $block).unwrap_err();
+        if let Some(s) = error.downcast_ref::<&'static str>() {
+            assert_eq!(*s, $message);
+        } else if let Some(s) = error.downcast_ref::<String>() {
+            assert_eq!(s, $message);
+        } else {
+            panic!("unexpected panic payload: {:?}", error);
+        }
+    };
+    ( $statement:expr, $message:expr $(,)? ) => {
+        assert_throws!({ $statement }, $message);
+    };
+}
+
+#[test]
+fn test_assert_contains() {
+    let s = String::from("Hello World");
+    one_assert::assert_contains!(s, "World");
+    one_assert::assert_contains!(s, 'W');
+    one_assert::assert_contains!("Hello World", String::from("lo W"));
+    assert_throws!(
+        one_assert::assert_contains!(s, "Wrold"),
+        r#"assertion `s contains "Wrold"` failed
+         haystack: "Hello World"
+           needle: "Wrold"
+    closest match: "World" at index 6 (3 of 5 characters match)"#
+    );
+    assert_throws!(
+        one_assert::assert_contains!(s, 'x', "no {} in sight", 'x'),
+        r#"assertion `s contains 'x'` failed: no x in sight
+    haystack: "Hello World"
+      needle: 'x'"#
+    );
+
+    let v = vec![1, 2, 3];
+    one_assert::assert_contains!(v, 2);
+    one_assert::assert_contains!(&v[1..], 3);
+    one_assert::assert_contains!([1, 2, 3], 1);
+    one_assert::assert_contains!(std::collections::HashSet::from([1, 2, 3]), 1);
+    assert_throws!(
+        one_assert::assert_contains!(v, 4),
+        "assertion `v contains 4` failed
+    haystack: [1, 2, 3]
+      needle: 4"
+    );
+    assert_eq!(v.len(), 3); // not moved
+
+    let long = "a".repeat(300);
+    assert_throws!(
+        one_assert::assert_contains!(long, "b"),
+        &format!(
+            r#"assertion `long contains "b"` failed
+    haystack: "{}... (46 more characters)
+      needle: "b""#,
+            "a".repeat(255)
+        )
+    );
+}
+
+#[test]
+fn test_should_panic_with() {
+    let x = 1;
+    one_assert::should_panic_with!(
+        one_assert::assert!(x == 2),
+        "assertion `x == 2` failed
+     left: 1
+    right: 2"
+    );
+    one_assert::should_panic_with!(panic!("literal"), "literal");
+    one_assert::should_panic_with!(panic!("formatted {}", x), String::from("formatted 1"));
+    one_assert::should_panic_with!(
+        {
+            let y = x + 1;
+            one_assert::assert!(y < 2, "y is {}", y);
+        },
+        format!(
+            "assertion `y < 2` failed: y is {}
+     left: 2
+    right: 2",
+            x + 1
+        )
+    );
+
+    assert_throws!(
+        one_assert::should_panic_with!(one_assert::assert!(x == 1), "anything"),
+        r#"assertion `one_assert::assert! (x == 1) panics with "anything"` failed
+    expected: "anything"
+      actual: <no panic>"#
+    );
+    assert_throws!(
+        one_assert::should_panic_with!(panic!("foo"), "bar", "wrong message for {}", x),
+        r#"assertion `panic! ("foo") panics with "bar"` failed: wrong message for 1
+    expected: "bar"
+      actual: "foo""#
+    );
+    assert_throws!(
+        one_assert::should_panic_with!(std::panic::panic_any(5), "5"),
+        r#"assertion `std::panic::panic_any(5) panics with "5"` failed
+    expected: "5"
+      actual: <panic payload is not a string>"#
+    );
+}
+
+#[test]
+fn test_try_assert() {
+    use one_assert::TryAssertError;
+    use std::num::ParseIntError;
+
+    fn check(s: &str, limit: i32) -> Result<(), Box<dyn std::error::Error>> {
+        one_assert::try_assert!(s.parse::<i32>()? < limit)?;
+        Ok(())
+    }
+    check("12", 20).unwrap();
+
+    let err = check("abc", 20).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`s.parse::<i32>()` failed while evaluating assertion `s.parse::<i32>()? < limit`: invalid digit found in string"
+    );
+    let err = err.downcast::<TryAssertError<ParseIntError>>().unwrap();
+    assert_eq!(err.assertion, "s.parse::<i32>()? < limit");
+    assert_eq!(err.expression, "s.parse::<i32>()");
+    assert_eq!(err.error, "abc".parse::<i32>().unwrap_err());
+
+    // a failed condition is an error with the message that `assert!` would panic with
+    let err = check("30", 20).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "assertion `s.parse::<i32>()? < limit` failed
+     left: 30
+    right: 20"
+    );
+    assert!(err.is::<one_assert::OneAssertError>());
+
+    fn check_block(a: &str, b: &str) -> Result<(), Box<dyn std::error::Error>> {
+        one_assert::try_assert!({
+            let a = a.parse::<u8>()?;
+            a < b.parse::<u8>()?
+        })?;
+        Ok(())
+    }
+    check_block("1", "2").unwrap();
+    let err = check_block("1", "x").unwrap_err();
+    let err = err.downcast::<TryAssertError<ParseIntError>>().unwrap();
+    assert_eq!(err.expression, "b.parse::<u8>()");
+    assert_eq!(
+        check_block("3", "2").unwrap_err().to_string(),
+        "assertion `{ let a = a.parse::<u8>()?; a < b.parse::<u8>()? }` failed
+  caused by: block return assertion `a < b.parse::<u8>()?` failed
+       left: 3
+      right: 2"
+    );
+
+    // each `?` of a chain says which step failed
+    struct Version(u32);
+    impl Version {
+        fn is_stable(&self) -> Result<bool, std::num::TryFromIntError> {
+            u8::try_from(self.0).map(|major| major >= 1)
+        }
+    }
+    fn check_chain(s: &str) -> Result<(), Box<dyn std::error::Error>> {
+        one_assert::try_assert!(s.parse::<u32>().map(Version)?.is_stable()?)?;
+        Ok(())
+    }
+    check_chain("2").unwrap();
+    let err = check_chain("x").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`s.parse::<u32>().map(Version)` failed (`?` 1 of 2) while evaluating assertion `s.parse::<u32>().map(Version)?.is_stable()?`: invalid digit found in string"
+    );
+    let err = err.downcast::<TryAssertError<ParseIntError>>().unwrap();
+    assert_eq!((err.position, err.operators), (1, 2));
+
+    let err = check_chain("300").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`s.parse::<u32>().map(Version)?.is_stable()` failed (`?` 2 of 2) while evaluating assertion `s.parse::<u32>().map(Version)?.is_stable()?`: out of range integral type conversion attempted"
+    );
+    let err = err
+        .downcast::<TryAssertError<std::num::TryFromIntError>>()
+        .unwrap();
+    assert_eq!((err.position, err.operators), (2, 2));
+
+    // `?` in closures belongs to the closure
+    fn check_closure(s: &str) -> Result<(), Box<dyn std::error::Error>> {
+        one_assert::try_assert!(
+            (|| -> Result<bool, ParseIntError> { Ok(s.parse::<i32>()? > 0) })().unwrap_or(true)
+        )?;
+        Ok(())
+    }
+    check_closure("x").unwrap();
+
+    // the error can also be handled directly, and is printed as-is by the test harness
+    let x = 1;
+    let err = one_assert::try_assert!(x == 2, "x should be {}", 2).unwrap_err();
+    let expected = "assertion `x == 2` failed: x should be 2
+     left: 1
+    right: 2";
+    assert_eq!(err.message(), expected);
+    assert_eq!(format!("{err:?}"), expected);
+    one_assert::try_assert!(x == 1).unwrap();
+
+    // options still work, and a warning never fails
+    one_assert::try_assert!(x == 2, level = "warning").unwrap();
+    let flags = vec![(true,)];
+    let err = one_assert::try_assert!(flags.get(5).unwrap().0, verbose).unwrap_err();
+    assert!(
+        err.message()
+            .ends_with("`flags.get(5)` is None, so `unwrap` would panic"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_try_assert_in_result_test() -> Result<(), one_assert::OneAssertError> {
+    let values = [1, 2, 3];
+    one_assert::try_assert!(values.len() == 3)?;
+    one_assert::try_assert!(values.iter().sum::<i32>() == 6)?;
+    Ok(())
+}
+
+#[test]
+fn test_assert_that() {
+    let x = 2;
+    one_assert::assert_that!(x, |v| *v < 3);
+    assert_throws!(
+        one_assert::assert_that!(x, |v| *v > 3),
+        "assertion `x satisfies | v | * v > 3` failed
+    value: 2"
+    );
+
+    fn is_sorted(v: &[i32; 3]) -> bool {
+        v.windows(2).all(|w| w[0] <= w[1])
+    }
+    let v = [1, 3, 2];
+    assert_throws!(
+        one_assert::assert_that!(v, is_sorted, "{} elements", v.len()),
+        "assertion `v satisfies is_sorted` failed: 3 elements
+    value: [1, 3, 2]"
+    );
+
+    // the predicate is called once
+    let mut calls = 0;
+    one_assert::assert_that!(String::from("abc"), |s| {
+        calls += 1;
+        s.starts_with('a')
+    });
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn test_assert_hash_eq() {
+    fn hash_of<T: std::hash::Hash>(value: &T) -> u64 {
+        use std::hash::Hasher;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // case-insensitive key, but the hash forgot about that
+    #[allow(clippy::derived_hash_with_manual_eq)]
+    #[derive(Debug, Hash)]
+    struct Key(&'static str);
+    impl PartialEq for Key {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.eq_ignore_ascii_case(other.0)
+        }
+    }
+
+    let (a, b) = (Key("a"), Key("A"));
+    one_assert::assert_hash_eq!(a, Key("a"));
+    one_assert::assert_hash_eq!("text", "text");
+    one_assert::assert_hash_ne!(a, b);
+    assert_throws!(
+        one_assert::assert_hash_eq!(a, b, "{} == {}", a.0, b.0),
+        &format!(
+            r#"assertion `a hashes equal to b` failed: a == A
+          left: Key("a")
+         right: Key("A")
+     left hash: {:#018x}
+    right hash: {:#018x}"#,
+            hash_of(&a),
+            hash_of(&b)
+        )
+    );
+    assert_throws!(
+        one_assert::assert_hash_ne!(a, Key("a")),
+        &format!(
+            r#"assertion `a hashes different from Key("a")` failed
+          left: Key("a")
+         right: Key("a")
+     left hash: {0:#018x}
+    right hash: {0:#018x}"#,
+            hash_of(&a)
+        )
+    );
+}
+
+#[test]
+fn test_assert_bits_eq() {
+    let x = 1.5f64;
+    one_assert::assert_bits_eq!(x, 1.5);
+    one_assert::assert_bits_eq!(f32::NAN, f32::NAN);
+
+    let zero = -0.0;
+    assert_throws!(
+        one_assert::assert_bits_eq!(zero, 0.0),
+        "assertion `zero bitwise equals 0.0` failed
+          left: -0.0
+         right: 0.0
+     left bits: 0x8000000000000000
+    right bits: 0x0000000000000000
+          note: sign of zero differs: -0.0 vs 0.0"
+    );
+
+    let nan = f32::from_bits(0x7fc0_0001);
+    assert_throws!(
+        one_assert::assert_bits_eq!(nan, f32::NAN, "payload {:#x}", 1),
+        "assertion `nan bitwise equals f32::NAN` failed: payload 0x1
+          left: NaN
+         right: NaN
+     left bits: 0x7fc00001
+    right bits: 0x7fc00000
+          note: NaN payload differs"
+    );
+    assert_throws!(
+        one_assert::assert_bits_eq!(-f32::NAN, f32::NAN),
+        "assertion `- f32::NAN bitwise equals f32::NAN` failed
+          left: NaN
+         right: NaN
+     left bits: 0xffc00000
+    right bits: 0x7fc00000
+          note: sign of NaN differs"
+    );
+
+    assert_throws!(
+        one_assert::assert_bits_eq!(x, 1.25),
+        "assertion `x bitwise equals 1.25` failed
+          left: 1.5
+         right: 1.25
+     left bits: 0x3ff8000000000000
+    right bits: 0x3ff4000000000000"
+    );
+}
+
+#[test]
+fn test_assert_expand() {
+    // the code is never compiled, so `x` doesn't even have to exist
+    let code: &str = one_assert::assert_expand!(x == 2, "message");
+    assert!(code.contains("if x =="), "{}", code);
+    assert!(code.contains("panic!"), "{}", code);
+    assert!(code.contains("assertion `x == 2` failed"), "{}", code);
+}
+
+#[test]
+fn test_assert_expand_verbatim() {
+    // `builtin #` is unstable syntax that syn can only store as raw tokens
+    let code: &str = one_assert::assert_expand!(builtin # offset_of(Foo, a));
+    assert!(code.contains("no values were captured"), "{}", code);
+    assert!(code.contains("builtin # offset_of"), "{}", code);
+}
+
+#[test]
+fn test_assert_one_of() {
+    let x = 2;
+    one_assert::assert_one_of!(x, [1, 2, 3]);
+    assert_throws!(
+        one_assert::assert_one_of!(x, [1, 3, 5]),
+        "assertion `x is one of 1, 3, or 5` failed
+      value: 2
+    allowed: [1, 3, 5]"
+    );
+    assert_throws!(
+        one_assert::assert_one_of!(x + 1, [1, 2], "x is {}", x),
+        "assertion `x + 1 is one of 1 or 2` failed: x is 2
+      value: 3
+    allowed: [1, 2]"
+    );
+
+    // the allowed values can have different types
+    let s = String::from("b");
+    one_assert::assert_one_of!(s, ["a", String::from("b")]);
+    assert_throws!(
+        one_assert::assert_one_of!(s, ["a"]),
+        r#"assertion `s is one of "a"` failed
+      value: "b"
+    allowed: ["a"]"#
+    );
+    assert_eq!(s, "b"); // not moved
+}
+
+#[test]
+fn test_assert_converges() {
+    one_assert::assert_converges!(|x| x / 2, 100, max_steps = 10, done = |x| *x == 1);
+
+    assert_throws!(
+        one_assert::assert_converges!(|x| x / 2, 100, max_steps = 3, done = |x| *x == 1),
+        "assertion `| x | x / 2 reaches | x | * x == 1 from 100 within 3 steps` failed
+         steps: 3
+    last state: 12"
+    );
+
+    // the step function is called at most `max_steps` times
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    let step = |v: Vec<i32>| {
+        calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        v.into_iter().skip(1).collect::<Vec<_>>()
+    };
+    assert_throws!(
+        one_assert::assert_converges!(
+            step,
+            vec![1, 2, 3],
+            max_steps = 2,
+            done = Vec::is_empty,
+            "{}",
+            "empty"
+        ),
+        "assertion `step reaches Vec::is_empty from vec! [1, 2, 3] within 2 steps` failed: empty
+         steps: 2
+    last state: [3]"
+    );
+    assert_eq!(calls.into_inner(), 2);
+}
+
+#[test]
+fn test_assert_within_steps() {
+    fn binary_search(values: &[u32], target: u32, steps: &mut usize) -> Option<usize> {
+        let (mut low, mut high) = (0, values.len());
+        while low < high {
+            *steps += 1;
+            let mid = (low + high) / 2;
+            match values[mid].cmp(&target) {
+                std::cmp::Ordering::Equal => return Some(mid),
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+            }
+        }
+        None
+    }
+    let values: Vec<u32> = (0..1000).collect();
+
+    let index = one_assert::assert_within_steps!(10, {
+        binary_search(&values, 123, &mut one_assert_steps)
+    });
+    one_assert::assert!(index == Some(123));
+    one_assert::assert_within_steps!(0, {});
+
+    assert_throws!(
+        one_assert::assert_within_steps!(100, {
+            for value in &values {
+                one_assert_steps += 1;
+                if *value == 127 {
+                    break;
+                }
+            }
+        }),
+        "assertion `computation takes at most 100 steps` failed
+    steps: 128
+      max: 100"
+    );
+    let max = 3;
+    assert_throws!(
+        one_assert::assert_within_steps!(max, { one_assert_steps += 4 }, "linear {}", "scan"),
+        "assertion `computation takes at most max steps` failed: linear scan
+    steps: 4
+      max: 3"
+    );
+}
+
+#[test]
+fn test_assert_ready() {
+    async fn double(x: i32) -> i32 {
+        x * 2
+    }
+    struct Never;
+    impl std::future::Future for Never {
+        type Output = i32;
+        fn poll(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context,
+        ) -> std::task::Poll<i32> {
+            std::task::Poll::Pending
+        }
+    }
+
+    one_assert::assert_ready!(async {});
+    let value = one_assert::assert_ready!(async { double(2).await + 1 });
+    one_assert::assert!(value == 5);
+    one_assert::assert!(one_assert::assert_ready!(double(3)) == 6);
+
+    assert_throws!(
+        one_assert::assert_ready!(Never),
+        "assertion `Never is ready` failed
+    poll: Pending
+  the future did not complete synchronously"
+    );
+    assert_throws!(
+        one_assert::assert_ready!(async { double(Never.await).await }, "in round {}", 2),
+        "assertion `async { double(Never.await).await } is ready` failed: in round 2
+    poll: Pending
+  the future did not complete synchronously"
+    );
+}
+
+#[test]
+fn test_assert_same_elements() {
+    let names = vec!["a", "c", "a"];
+    one_assert::assert_same_elements!(&names, &["a", "a", "c"]);
+    one_assert::assert_same_elements!(names.iter().rev(), names.iter());
+    one_assert::assert_same_elements!(Vec::<u8>::new(), []);
+
+    assert_throws!(
+        one_assert::assert_same_elements!(&names, &["a", "b"]),
+        r#"assertion `& names has the same elements as & ["a", "b"]` failed
+    "a": expected 1, found 2
+    "c": expected 0, found 1
+    "b": expected 1, found 0"#
+    );
+    // multiplicities matter, unlike for sets
+    assert_throws!(
+        one_assert::assert_same_elements!([1, 1, 2], [1, 2, 2], "round {}", 3),
+        "assertion `[1, 1, 2] has the same elements as [1, 2, 2]` failed: round 3
+    1: expected 1, found 2
+    2: expected 2, found 1"
+    );
+}
+
+#[test]
+fn test_assert_same_output() {
+    one_assert::assert_same_output!(|x| x * 2, |x| x + x, [1, 2, 3]);
+    one_assert::assert_same_output!(|s: &&str| s.len(), |s| s.chars().count(), ["a", "bc"]);
+
+    assert_throws!(
+        one_assert::assert_same_output!(|x| x * 2, |x| x * x, 1..5),
+        "assertion `| x | x * 2 and | x | x * x produce the same output for 1 .. 5` failed
+    index: 0
+    input: 1
+     left: 2
+    right: 1"
+    );
+
+    // both functions are called once per input, until the first difference
+    fn double(x: &i32) -> i32 {
+        x * 2
+    }
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    let square = |x: &i32| {
+        calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        x * x
+    };
+    assert_throws!(
+        one_assert::assert_same_output!(double, square, vec![0, 2, 3, 4], "{} inputs", 4),
+        "assertion `double and square produce the same output for vec! [0, 2, 3, 4]` failed: 4 inputs
+    index: 2
+    input: 3
+     left: 6
+    right: 9"
+    );
+    assert_eq!(calls.into_inner(), 3);
+}
+
+#[test]
+fn test_assert_similar() {
+    one_assert::assert_similar!("kitten", "kitten");
+    one_assert::assert_similar!("kitten", "sitten");
+    one_assert::assert_similar!("kitten", "sittin", max_distance = 2);
+    one_assert::assert_similar!(String::from("Grüße"), "Grüsse", max_distance = 2);
+
+    let a = "kitten";
+    let b = String::from("sitting");
+    assert_throws!(
+        one_assert::assert_similar!(a, b, max_distance = 1),
+        r#"assertion `a is within edit distance 1 of b` failed
+            left: "kitten"
+           right: "sitting"
+        distance: 3
+    max distance: 1"#
+    );
+    assert_throws!(
+        one_assert::assert_similar!(a, "", "{} is gone", a),
+        r#"assertion `a is within edit distance 2 of ""` failed: kitten is gone
+            left: "kitten"
+           right: ""
+        distance: 6
+    max distance: 2"#
+    );
+}
+
+#[test]
+fn test_assert_all_close() {
+    let expected = [1.0, 2.0, 3.0, 4.0];
+    one_assert::assert_all_close!(expected, expected, 0.0);
+    one_assert::assert_all_close!([1.05, 1.95, 3.0, 4.1], expected, 0.11);
+    one_assert::assert_all_close!(vec![10u8, 20], [12, 18], 2);
+
+    let signal = vec![1.0, 2.5, 3.0, f64::NAN];
+    assert_throws!(
+        one_assert::assert_all_close!(signal, expected, 0.25),
+        "assertion `signal is element-wise within 0.25 of expected` failed
+    tolerance: 0.25
+          [1]: left=2.5 right=2.0 diff=0.5
+          [3]: left=NaN right=4.0 diff=NaN"
+    );
+    let head = &signal[..2];
+    assert_throws!(
+        one_assert::assert_all_close!(head, [1, 2, 3].map(f64::from), 0.5, "{} samples", 2),
+        "assertion `head is element-wise within 0.5 of [1, 2, 3].map(f64::from)` failed: 2 samples
+       tolerance: 0.5
+     left length: 2
+    right length: 3"
+    );
+}
+
+#[test]
+fn test_assert_matrix_close() {
+    let identity = [[1.0, 0.0], [0.0, 1.0]];
+    one_assert::assert_matrix_close!(identity, identity, 0.0);
+    one_assert::assert_matrix_close!(vec![vec![1.05, 0.0], vec![0.0, 0.95]], identity, 0.1);
+    one_assert::assert_matrix_close!([[10u8, 20]], vec![[12, 18]], 2);
+
+    let rotated = vec![vec![0.0, -1.0], vec![1.0, f64::NAN]];
+    assert_throws!(
+        one_assert::assert_matrix_close!(rotated, identity, 0.5),
+        "assertion `rotated is element-wise within 0.5 of identity` failed
+    tolerance: 0.5
+       [0, 0]: left=0.0 right=1.0 diff=1.0
+       [0, 1]: left=-1.0 right=0.0 diff=1.0
+       [1, 0]: left=1.0 right=0.0 diff=1.0
+       [1, 1]: left=NaN right=1.0 diff=NaN"
+    );
+    let ragged = vec![vec![1.0, 0.0, 0.0], vec![0.0, 2.0], vec![0.0]];
+    assert_throws!(
+        one_assert::assert_matrix_close!(ragged, identity, 0.5, "{} rows", 3),
+        "assertion `ragged is element-wise within 0.5 of identity` failed: 3 rows
+       tolerance: 0.5
+       left rows: 3
+      right rows: 2
+    row 0 length: left=3 right=2
+          [1, 1]: left=2.0 right=1.0 diff=1.0"
+    );
+}
+
+#[test]
+fn test_assert_drop_count() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Guard(Arc<AtomicUsize>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let guards = vec![Guard(drops.clone()), Guard(drops.clone())];
+    one_assert::assert_drop_count!(drops, 0);
+    drop(guards);
+    one_assert::assert_drop_count!(drops, 2);
+
+    static STATIC_DROPS: AtomicUsize = AtomicUsize::new(5);
+    one_assert::assert_drop_count!(STATIC_DROPS, 5);
+
+    assert_throws!(
+        one_assert::assert_drop_count!(drops, 3),
+        "assertion `drops counted 3 drops` failed
+       dropped: 2
+      expected: 3
+    difference: 1 missing"
+    );
+    assert_throws!(
+        one_assert::assert_drop_count!(STATIC_DROPS, 1, "after {} frees", 1),
+        "assertion `STATIC_DROPS counted 1 drops` failed: after 1 frees
+       dropped: 5
+      expected: 1
+    difference: 4 too many"
+    );
+}
+
+#[test]
+fn test_assert_count() {
+    let words = ["apple", "", "banana", "", "cherry"];
+    one_assert::assert_count!(words.iter().filter(|w| w.is_empty()), 2);
+    one_assert::assert_count!(&words, 5);
+    one_assert::assert_count!(std::iter::empty::<u8>(), 0);
+
+    // the iterator is consumed, unless it is borrowed
+    let mut chars = "abc".chars();
+    one_assert::assert_count!(chars.by_ref().take(2), 2);
+    one_assert::assert_count!(chars, 1);
+
+    assert_throws!(
+        one_assert::assert_count!(words.iter().filter(|w| !w.is_empty()), 2),
+        "assertion `words.iter().filter(| w | ! w.is_empty()) yields 2 items` failed
+       counted: 3
+      expected: 2
+    difference: 1 too many"
+    );
+    assert_throws!(
+        one_assert::assert_count!(0..2, 4, "for {} pages", 4),
+        "assertion `0 .. 2 yields 4 items` failed: for 4 pages
+       counted: 2
+      expected: 4
+    difference: 2 missing"
+    );
+}
+
+#[test]
+fn test_assert_err_contains() {
+    #[derive(Debug)]
+    struct LoadError(std::num::ParseIntError);
+    impl std::fmt::Display for LoadError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "failed to load the config")
+        }
+    }
+    impl std::error::Error for LoadError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    let invalid = "x".parse::<u8>().unwrap_err();
+    let result: Result<(), LoadError> = Err(LoadError(invalid));
+    one_assert::assert_err_contains!(result, "load");
+    one_assert::assert_err_contains!(result, String::from("invalid digit"));
+    one_assert::assert_err_contains!("x".parse::<u8>(), "invalid digit");
+
+    assert_throws!(
+        one_assert::assert_err_contains!(result, "not found"),
+        r#"assertion `result is an error containing "not found"` failed
+       error: failed to load the config
+    source 1: invalid digit found in string"#
+    );
+    assert_throws!(
+        one_assert::assert_err_contains!("12".parse::<u8>(), "invalid", "{} parsed", 12),
+        r#"assertion `"12".parse::<u8>() is an error containing "invalid"` failed: 12 parsed
+    result: Ok(_)"#
+    );
+}
+
+#[test]
+fn test_assert_matches_glob() {
+    one_assert::assert_matches_glob!("src/lib.rs", "src/*.rs");
+    one_assert::assert_matches_glob!("src/lib.rs", "*");
+    one_assert::assert_matches_glob!("", "*");
+    one_assert::assert_matches_glob!("a.rs.rs", "*.rs");
+    one_assert::assert_matches_glob!(String::from("log_01.txt"), "log_??.*");
+    one_assert::assert_matches_glob!("Grüße", "Gr??e");
+
+    let path = "src/lib.txt";
+    assert_throws!(
+        one_assert::assert_matches_glob!(path, "src/*.rs"),
+        r#"assertion `path matches the glob "src/*.rs"` failed
+      string: "src/lib.txt"
+     pattern: "src/*.rs"
+     matched: "src/lib." with "src/*."
+    mismatch: "txt" against "rs""#
+    );
+    assert_throws!(
+        one_assert::assert_matches_glob!("log_1.txt", "log_??.txt", "bad name"),
+        r#"assertion `"log_1.txt" matches the glob "log_??.txt"` failed: bad name
+      string: "log_1.txt"
+     pattern: "log_??.txt"
+     matched: "log_1." with "log_??"
+    mismatch: "txt" against ".txt""#
+    );
+    assert_throws!(
+        one_assert::assert_matches_glob!("main.rs", "main"),
+        r#"assertion `"main.rs" matches the glob "main"` failed
+      string: "main.rs"
+     pattern: "main"
+     matched: "main" with "main"
+    mismatch: ".rs" against """#
+    );
+}
+
+#[test]
+fn test_assert_starts_with() {
+    let packet = vec![1, 2, 9, 4, 5, 6, 7, 8, 9, 10];
+    one_assert::assert_starts_with!(packet, [1, 2]);
+    one_assert::assert_starts_with!(packet, packet);
+    one_assert::assert_starts_with!(&packet[..3], Vec::<i32>::new());
+    one_assert::assert_starts_with!(["a".to_string()], ["a"]);
+
+    assert_throws!(
+        one_assert::assert_starts_with!(packet, [1, 2, 3]),
+        "assertion `packet starts with [1, 2, 3]` failed
+    first difference: index 2
+      haystack[0..6]: [1, 2, 9, 4, 5, 6]
+        prefix[0..3]: [1, 2, 3]"
+    );
+    assert_throws!(
+        one_assert::assert_starts_with!(packet[8..], [9, 10, 11], "{} is short", "packet"),
+        "assertion `packet [8 ..] starts with [9, 10, 11]` failed: packet is short
+    first difference: index 2 (end of haystack)
+      haystack[0..2]: [9, 10]
+        prefix[0..3]: [9, 10, 11]"
+    );
+}
+
+#[test]
+fn test_assert_subsequence() {
+    let log = ["start", "read", "write", "read", "stop"];
+    one_assert::assert_subsequence!(log, ["start", "write", "stop"]);
+    one_assert::assert_subsequence!(log, ["read", "read"]);
+    one_assert::assert_subsequence!(log, [""; 0]);
+
+    assert_throws!(
+        one_assert::assert_subsequence!(log, ["start", "write", "write"]),
+        r#"assertion `log contains the subsequence ["start", "write", "write"]` failed
+            matched: 2 of 3 elements at indices [0, 2]
+    missing element: needle[2] = "write"
+      searched from: index 3
+     haystack[3..5]: ["read", "stop"]"#
+    );
+    assert_throws!(
+        one_assert::assert_subsequence!(vec![1, 2], vec![3], "no {}", 3),
+        "assertion `vec! [1, 2] contains the subsequence vec! [3]` failed: no 3
+            matched: 0 of 1 elements at indices []
+    missing element: needle[0] = 3
+      searched from: index 0
+     haystack[0..2]: [1, 2]"
+    );
+}
+
+#[test]
+fn test_assert_empty() {
+    one_assert::assert_empty!(std::iter::empty::<i32>());
+    one_assert::assert_empty!(Vec::<i32>::new().into_iter());
+
+    assert_throws!(
+        one_assert::assert_empty!([1, 2].iter().skip(1), "iter should be done"),
+        "assertion `[1, 2].iter().skip(1) is empty` failed: iter should be done
+    leftover: 2"
+    );
+
+    // the leftover element is consumed by the check
+    let mut iter = [1].iter();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        one_assert::assert_empty!(&mut iter);
+    }));
+    assert!(result.is_err());
+    one_assert::assert_empty!(iter);
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    one_assert::assert_empty!(&rx);
+    tx.send("unexpected message").unwrap();
+    assert_throws!(
+        one_assert::assert_empty!(&rx),
+        r#"assertion `& rx is empty` failed
+    leftover: "unexpected message""#
+    );
+    drop(tx);
+    one_assert::assert_empty!(rx);
+}
+
+#[test]
+fn test_assert_monotonic() {
+    one_assert::assert_monotonic!([1, 2, 2, 3]);
+    one_assert::assert_monotonic!([1, 2, 3], strict = true);
+    one_assert::assert_monotonic!(vec![3.0, 2.5, 2.5], direction = "decreasing");
+    one_assert::assert_monotonic!(Vec::<i32>::new(), strict = true);
+    one_assert::assert_monotonic!("bdf".chars(), direction = "increasing", strict = true);
+
+    let readings = vec![1, 3, 5, 5, 7];
+    one_assert::assert_monotonic!(&readings);
+    assert_throws!(
+        one_assert::assert_monotonic!(&readings, strict = true),
+        "assertion `& readings is strictly increasing` failed
+  caused by: element 2 < element 3 is false
+      element 2: 5
+      element 3: 5"
+    );
+    assert_throws!(
+        one_assert::assert_monotonic!(
+            readings.iter(),
+            direction = "decreasing",
+            strict = false,
+            "readings should {}",
+            "only shrink"
+        ),
+        "assertion `readings.iter() is decreasing` failed: readings should only shrink
+  caused by: element 0 >= element 1 is false
+      element 0: 1
+      element 1: 3"
+    );
+}
+
+#[test]
+fn test_assert_unchanged() {
+    let mut values = vec![1, 2, 3];
+    one_assert::assert_unchanged!(values.iter().sum::<i32>(), observing = values.clone());
+    one_assert::assert_unchanged!(values.sort(), observing = values.len());
+
+    assert_throws!(
+        {
+            let mut values = vec![1, 2, 3];
+            one_assert::assert_unchanged!(values.push(4), observing = values.len());
+        },
+        "assertion `values.push(4) leaves values.len() unchanged` failed
+    before: 3
+     after: 4"
+    );
+
+    // the state is a snapshot, even for plain variables
+    assert_throws!(
+        {
+            let mut counter = 0;
+            one_assert::assert_unchanged!(
+                counter += 1,
+                observing = counter,
+                "counter was {}",
+                "touched"
+            );
+        },
+        "assertion `counter += 1 leaves counter unchanged` failed: counter was touched
+    before: 0
+     after: 1"
+    );
+
+    assert_throws!(
+        {
+            let mut map = std::collections::BTreeMap::from([("a", 1)]);
+            one_assert::assert_unchanged!(map.insert("b", 2), observing = map.clone());
+        },
+        r#"assertion `map.insert("b", 2) leaves map.clone() unchanged` failed
+    before: {"a": 1}
+     after: {"a": 1, "b": 2}
+  only in after: ["b"]"#
+    );
+    assert_throws!(
+        {
+            let mut slot = None;
+            one_assert::assert_unchanged!(slot.get_or_insert(1), observing = slot);
+        },
+        "assertion `slot.get_or_insert(1) leaves slot unchanged` failed
+    before: None
+     after: Some(1)
+  before is None, after is Some(_)"
+    );
+}
+
+#[test]
+fn test_assert_eq_by() {
+    let (a, b) = ("  Hello World", "hello world ");
+    one_assert::assert_eq_by!(a, b, |s| s.trim().to_lowercase());
+    let (x, y) = (vec![3, 1, 2], vec![2, 3, 1]);
+    one_assert::assert_eq_by!(x, y, |v| {
+        let mut sorted = v.clone();
+        sorted.sort();
+        sorted
+    });
+
+    let c = "hello  world";
+    assert_throws!(
+        one_assert::assert_eq_by!(a, c, |s| s.trim().to_lowercase()),
+        r#"assertion `a equals c after | s | s.trim().to_lowercase()` failed
+                left: "  Hello World"
+               right: "hello  world"
+     normalized left: "hello world"
+    normalized right: "hello  world""#
+    );
+
+    // the normalization is called once per side
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    let count = |n: &i32| {
+        calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        n % 10
+    };
+    one_assert::assert_eq_by!(13, 23, count);
+    assert_throws!(
+        one_assert::assert_eq_by!(13, 24, count, "modulo {}", 10),
+        "assertion `13 equals 24 after count` failed: modulo 10
+                left: 13
+               right: 24
+     normalized left: 3
+    normalized right: 4"
+    );
+    assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 4);
+}
+
+#[test]
+fn test_assert_eq_ignoring() {
+    #[derive(Debug, one_assert::OneAssertFields)]
+    struct Event {
+        name: &'static str,
+        id: u32,
+        timestamp: u64,
+    }
+    let a = Event {
+        name: "start",
+        id: 1,
+        timestamp: 100,
+    };
+    let b = Event {
+        name: "start",
+        id: 2,
+        timestamp: 200,
+    };
+    one_assert::assert_eq_ignoring!(a, b, [timestamp, id]);
+    one_assert::assert_eq_ignoring!(a, a, []);
+
+    let c = Event {
+        name: "stop",
+        id: 3,
+        timestamp: 300,
+    };
+    assert_throws!(
+        one_assert::assert_eq_ignoring!(a, c, [timestamp]),
+        r#"assertion `a == c ignoring timestamp` failed
+     left.name: "start"
+    right.name: "stop"
+       left.id: 1
+      right.id: 3"#
+    );
+    assert_throws!(
+        one_assert::assert_eq_ignoring!(a, b, [time, id], "{} events", 2),
+        r#"assertion `a == b ignoring time, id` failed: 2 events
+  caused by: `time` is not one of the fields ["name", "id", "timestamp"]"#
+    );
+
+    #[derive(Debug, one_assert::OneAssertFields)]
+    struct Pair<T>(T, T);
+    one_assert::assert_eq_ignoring!(Pair(1, 2), Pair(1, 3), [1]);
+    assert_throws!(
+        one_assert::assert_eq_ignoring!(Pair("a", "b"), Pair("c", "b"), [1]),
+        r#"assertion `Pair("a", "b") == Pair("c", "b") ignoring 1` failed
+     left.0: "a"
+    right.0: "c""#
+    );
+}
+
+#[test]
+fn test_assert_fields_eq() {
+    #[derive(Debug, one_assert::OneAssertFields)]
+    struct Event {
+        name: &'static str,
+        id: u32,
+        timestamp: u64,
+    }
+    let a = Event {
+        name: "start",
+        id: 1,
+        timestamp: 100,
+    };
+    let b = Event {
+        name: "stop",
+        id: 1,
+        timestamp: 200,
+    };
+    one_assert::assert_fields_eq!(a, a);
+    assert_throws!(
+        one_assert::assert_fields_eq!(a, b, "{} events", 2),
+        r#"assertion `a == b field by field` failed: 2 events
+          left.name: "start"
+         right.name: "stop"
+     left.timestamp: 100
+    right.timestamp: 200"#
+    );
+}
+
+#[test]
+fn test_assert_maintains() {
+    let capacity = 3;
+    let mut queue = vec![1, 2];
+    let len = one_assert::assert_maintains!(queue.len() <= capacity, {
+        queue.push(3);
+        queue.len()
+    });
+    assert_eq!(len, 3);
+
+    // the body needs mutable access, so the queue is created inside of the panicking closure
+    assert_throws!(
+        {
+            let mut queue = vec![1, 2, 3];
+            one_assert::assert_maintains!(queue.len() <= capacity, { queue.push(4) })
+        },
+        "assertion `queue.len() <= capacity holds after { queue.push(4) }` failed
+     left: 4
+    right: 3"
+    );
+    assert_throws!(
+        {
+            let mut queue = vec![1, 2, 3, 4];
+            one_assert::assert_maintains!(queue.len() <= capacity, { queue.clear() }, "at {}", 1)
+        },
+        "assertion `queue.len() <= capacity holds before { queue.clear() }` failed: at 1
+     left: 4
+    right: 3"
+    );
+}
+
+#[test]
+fn test_assert_returning() {
+    let mut calls = 0;
+    let mut compute = |n: i32| {
+        calls += 1;
+        n * 2
+    };
+    let value = one_assert::assert_returning!(v = compute(3); v > 0 && v % 2 == 0);
+    assert_eq!(value, 6);
+    let name = one_assert::assert_returning!(s = String::from("abc"); s.len() == 3, "for {}", s);
+    assert_eq!(name, "abc");
+    assert_eq!(calls, 1);
+
+    assert_throws!(
+        one_assert::assert_returning!(v = -4; v > 0, "got {}", v),
+        "assertion `v > 0` failed: got -4
+     left: -4
+    right: 0"
+    );
+    assert_throws!(
+        one_assert::assert_returning!(items = vec![1, 2]; items.is_empty(), types),
+        "assertion `items.is_empty()` failed
+         self: [1, 2]
+    self type: alloc::vec::Vec<i32>"
+    );
+}
+
+one_assert::assert_impl!(String: Clone + Send + Sync + 'static);
+
+#[test]
+fn test_assert_impl() {
+    #[derive(Clone, Debug)]
+    struct Config;
+    one_assert::assert_impl!(Config: Clone + std::fmt::Debug);
+    one_assert::assert_impl!(str: std::fmt::Display + ?Sized);
+    one_assert::assert_impl!(Vec<u8>: Extend<u8> + AsRef<[u8]>,);
+    one_assert::assert_impl!(std::vec::IntoIter<u8>: Iterator<Item = u8>);
+}
+
+one_assert::assert_size!(u64, 8);
+
+#[test]
+fn test_assert_size_and_align() {
+    #[repr(C)]
+    struct Header {
+        tag: u8,
+        length: u32,
+    }
+    one_assert::assert_size!(Header, 8);
+    one_assert::assert_size!([u16; 3], 3 * std::mem::size_of::<u16>());
+    one_assert::assert_size!(Option<Box<u8>>, std::mem::size_of::<usize>(),);
+    one_assert::assert_align!(Header, 4);
+    one_assert::assert_align!(u8, 1);
+}
+
+#[test]
+fn test_assert_order() {
+    fn run_hooks(hooks: &[&dyn Fn()]) -> usize {
+        hooks.iter().rev().for_each(|hook| hook());
+        hooks.len()
+    }
+
+    let count = one_assert::assert_order!(|step| run_hooks(&[&|| step(2), &|| step(1)]), [1, 2]);
+    assert_eq!(count, 2);
+    one_assert::assert_order!(|_: &dyn Fn(u8)| {}, []);
+
+    assert_throws!(
+        one_assert::assert_order!(
+            |step| run_hooks(&[&|| step("a"), &|| step("b")]),
+            ["a", "b"]
+        ),
+        r#"assertion `| step | run_hooks(& [& | | step("a"), & | | step("b")]) reaches the steps ["a", "b"] in order` failed
+            expected: ["a", "b"]
+              actual: ["b", "a"]
+    first difference: step 0: expected "a", found "b""#
+    );
+    let expected = vec!["open", "read", "close"];
+    assert_throws!(
+        one_assert::assert_order!(
+            |step| {
+                step("open");
+                step("read");
+            },
+            expected,
+            "{} steps",
+            3
+        ),
+        r#"assertion `| step | { step("open"); step("read"); } reaches the steps expected in order` failed: 3 steps
+            expected: ["open", "read", "close"]
+              actual: ["open", "read"]
+    first difference: step 2: expected "close", found nothing"#
+    );
+}
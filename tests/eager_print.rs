@@ -0,0 +1,39 @@
+//! Only with the `eager_print` feature: `cargo test --features eager_print`
+#![cfg(feature = "eager_print")]
+
+use std::process::Command;
+
+/// Set for the child process of [`test_eager_print`], which runs the failing assertion
+const CHILD_VAR: &str = "ONE_ASSERT_EAGER_PRINT_CHILD";
+
+#[test]
+fn test_eager_print() {
+    if std::env::var_os(CHILD_VAR).is_some() {
+        // nothing else prints the message, so anything on stderr comes from the assertion itself
+        std::panic::set_hook(Box::new(|_| {}));
+        let x = -1;
+        one_assert::assert!(x > 0);
+        return;
+    }
+
+    // the panic hook is global, so the assertion runs in a separate process to keep its output apart
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "test_eager_print",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(CHILD_VAR, "1")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let expected = "assertion `x > 0` failed
+     left: -1
+    right: 0
+";
+    assert!(stderr.contains(expected), "{stderr}");
+    assert!(!stderr.contains("panicked at"), "{stderr}");
+}
@@ -1,3 +1,14 @@
+// the tests intentionally use unusual expressions to cover every kind of syntax
+#![allow(
+    clippy::missing_transmute_annotations,
+    clippy::never_loop,
+    clippy::nonminimal_bool,
+    clippy::too_many_arguments,
+    clippy::transmute_int_to_bool,
+    clippy::unnecessary_cast,
+    clippy::useless_concat
+)]
+
 #[macro_export]
 macro_rules! assert_throws {
     ( $block:block, $message:expr $(,)? ) => {
@@ -52,6 +63,124 @@ fn test_await() {
     );
 }
 
+#[test]
+fn test_poll() {
+    use std::task::Poll;
+
+    let pending: Poll<i32> = Poll::Pending;
+    let ready = Poll::Ready(1);
+
+    one_assert::assert!(ready == Poll::Ready(1));
+    one_assert::assert!(ready.is_ready());
+    one_assert::assert!(!pending.is_ready());
+    assert_throws!(
+        one_assert::assert!(pending == Poll::Ready(1)),
+        "assertion `pending == Poll::Ready(1)` failed
+     left: Pending
+    right: Ready(1)
+  left is Pending, right is Ready(_)"
+    );
+    assert_throws!(
+        one_assert::assert!(ready == Poll::Ready(2)),
+        "assertion `ready == Poll::Ready(2)` failed
+     left: Ready(1)
+    right: Ready(2)"
+    );
+    assert_throws!(
+        one_assert::assert!(pending.is_ready()),
+        "assertion `pending.is_ready()` failed
+    self: Pending
+  self is Pending, expected Ready(_)"
+    );
+    assert_throws!(
+        one_assert::assert!(!ready.is_ready()),
+        "assertion `! ready.is_ready()` failed
+    assertion negated: true
+                 self: Ready(1)
+  self is Ready(_), expected Pending"
+    );
+    assert_throws!(
+        one_assert::assert!(ready.is_pending()),
+        "assertion `ready.is_pending()` failed
+    self: Ready(1)
+  self is Ready(_), expected Pending"
+    );
+}
+
+#[test]
+fn test_float_class() {
+    let (x, tiny, nan) = (1.5_f64, 1e-310_f64, f32::NAN);
+    one_assert::assert!(x.is_finite() && x.is_normal());
+    one_assert::assert!(!tiny.is_normal());
+    assert_throws!(
+        one_assert::assert!((x / 0.0).is_finite()),
+        "assertion `(x / 0.0).is_finite()` failed
+    self: inf
+  self is inf (infinite)"
+    );
+    assert_throws!(
+        one_assert::assert!(tiny.is_normal()),
+        "assertion `tiny.is_normal()` failed
+    self: 1e-310
+  self is 1e-310 (subnormal)"
+    );
+    assert_throws!(
+        one_assert::assert!(!nan.is_nan()),
+        "assertion `! nan.is_nan()` failed
+    assertion negated: true
+                 self: NaN
+  self is NaN (not a number)"
+    );
+}
+
+#[test]
+fn test_poisoned_lock() {
+    use std::sync::{Mutex, RwLock};
+
+    let mutex = Mutex::new(vec![1, 2]);
+    let rwlock = RwLock::new(3_i32);
+    one_assert::assert!(*mutex.lock().unwrap() == [1, 2]);
+    one_assert::assert!(mutex.lock().unwrap().len() == 2 && *rwlock.read().unwrap() == 3);
+    assert_throws!(
+        one_assert::assert!(*mutex.lock().unwrap() == [1]),
+        "assertion `* mutex.lock().unwrap() == [1]` failed
+     left: [1, 2]
+    right: [1]"
+    );
+
+    let _ = std::thread::scope(|s| {
+        s.spawn(|| {
+            let _guard = mutex.lock();
+            let _write = rwlock.write();
+            panic!("poisoning the locks");
+        })
+        .join()
+    });
+    assert_throws!(
+        one_assert::assert!(*mutex.lock().unwrap() == [1, 2]),
+        "assertion `* mutex.lock().unwrap() == [1, 2]` failed
+  `mutex` is poisoned, so `lock().unwrap` would panic"
+    );
+    assert_throws!(
+        one_assert::assert!(rwlock.read().expect("readable").is_positive()),
+        "assertion `rwlock.read().expect(\"readable\").is_positive()` failed
+  `rwlock` is poisoned, so `read().expect` would panic"
+    );
+    // a warning doesn't panic, so the lock is never taken
+    one_assert::assert!(*mutex.lock().unwrap() == [1, 2], level = "warning");
+
+    // locks in blocks and branches are left to the code itself
+    let use_it = false;
+    one_assert::assert!(0 <= if use_it { *rwlock.read().unwrap() } else { 0 });
+    let mutex = Mutex::new(5);
+    one_assert::assert!(
+        5 == {
+            let local = &mutex;
+            *local.lock().unwrap()
+        }
+    );
+}
+
 #[test]
 fn test_binary() {
     let a = 1;
@@ -109,8 +238,9 @@ fn test_binary() {
     assert_throws!(
         one_assert::assert!(b && false),
         "assertion `b && false` failed
-     left: true
-    right: false"
+    operand 1: true
+    operand 2: false
+  operand 2 of 2 (false) was false"
     );
 
     one_assert::assert!(b & true);
@@ -126,8 +256,9 @@ fn test_binary() {
     assert_throws!(
         one_assert::assert!(b || false),
         "assertion `b || false` failed
-     left: false
-    right: false"
+    operand 1: false
+    operand 2: false
+  all 2 operands were false"
     );
 
     one_assert::assert!(b | true);
@@ -175,6 +306,445 @@ fn test_binary() {
     test_op_to_bool!(>>, Shr, shr);
 }
 
+#[test]
+fn test_binary_logical_chain() {
+    let (a, b, c) = (1, 2, 3);
+    one_assert::assert!(a < b && b < c && a != c);
+    assert_throws!(
+        one_assert::assert!(a < b && c < b && a != c),
+        "assertion `a < b && c < b && a != c` failed
+     operand 1 left: 1
+    operand 1 right: 2
+     operand 2 left: 3
+    operand 2 right: 2
+     operand 3 left: 1
+    operand 3 right: 3
+  operand 2 of 3 (c < b) was false"
+    );
+
+    // only the first false operand is reported, numbered in source order
+    assert_throws!(
+        one_assert::assert!(a > b && c < b && a == c),
+        "assertion `a > b && c < b && a == c` failed
+     operand 1 left: 1
+    operand 1 right: 2
+     operand 2 left: 3
+    operand 2 right: 2
+     operand 3 left: 1
+    operand 3 right: 3
+  operand 1 of 3 (a > b) was false"
+    );
+
+    // a different operator forms a single operand, whose comparisons are captured as well
+    assert_throws!(
+        one_assert::assert!(a > b || c < b && a != c || (a == c || b == c)),
+        "assertion `a > b || c < b && a != c || (a == c || b == c)` failed
+       operand 1 left: 1
+      operand 1 right: 2
+     operand 2.1 left: 3
+    operand 2.1 right: 2
+     operand 2.2 left: 1
+    operand 2.2 right: 3
+     operand 3.1 left: 1
+    operand 3.1 right: 3
+     operand 3.2 left: 2
+    operand 3.2 right: 3
+  all 3 operands were false"
+    );
+
+    // operands that are not comparisons are printed as they are
+    let (v, flag) = (vec![1, 2], false);
+    assert_throws!(
+        one_assert::assert!(v.len() == 2 && flag || v[0] != 1),
+        "assertion `v.len() == 2 && flag || v [0] != 1` failed
+     operand 1.1 left: 2
+    operand 1.1 right: 2
+          operand 1.2: false
+       operand 2 left: 1
+      operand 2 right: 1
+  all 2 operands were false"
+    );
+
+    // the note would be wrong inside of a negation
+    assert_throws!(
+        one_assert::assert!(!(a < b && b < c)),
+        "assertion `! (a < b && b < c)` failed
+    assertion negated: true
+       operand 1 left: 1
+      operand 1 right: 2
+       operand 2 left: 2
+      operand 2 right: 3"
+    );
+}
+
+#[test]
+fn test_binary_logical_chain_comparisons() {
+    let (a, b, c, d) = ("x", "x", 4, 4);
+    one_assert::assert!(a == b && c != d + 1);
+    assert_throws!(
+        one_assert::assert!(a == b && c != d),
+        "assertion `a == b && c != d` failed
+     operand 1 left: \"x\"
+    operand 1 right: \"x\"
+     operand 2 left: 4
+    operand 2 right: 4
+  operand 2 of 2 (c != d) was false"
+    );
+
+    // tuple fields keep their names, and values behind pointers are compared in place
+    let pair = (1, 2);
+    assert_throws!(
+        {
+            let boxed = Box::new(Box::new(String::from("a")));
+            one_assert::assert!(pair.0 < pair.1 && **boxed == "b")
+        },
+        "assertion `pair.0 < pair.1 && * * boxed == \"b\"` failed
+             pair.0: 1
+             pair.1: 2
+     operand 2 left: \"a\"
+    operand 2 right: \"b\"
+  operand 2 of 2 (* * boxed == \"b\") was false"
+    );
+}
+
+#[test]
+fn test_binary_reference() {
+    let (a, b) = (1, 2);
+    one_assert::assert!(&a < &b);
+    assert_throws!(
+        one_assert::assert!(&a == &b),
+        "assertion `& a == & b` failed
+     left: 1
+    right: 2"
+    );
+
+    // references don't hide the code of tuple fields, and the comparison still uses the reference
+    let pair = (1, 2);
+    assert_throws!(
+        one_assert::assert!(&pair.0 == &pair.1),
+        "assertion `& pair.0 == & pair.1` failed
+    pair.0: 1
+    pair.1: 2"
+    );
+    assert_throws!(
+        {
+            let mut pair = pair;
+            one_assert::assert!(&mut pair.0 == &2);
+        },
+        "assertion `& mut pair.0 == & 2` failed
+    pair.0: 1
+     right: 2"
+    );
+}
+
+#[test]
+fn test_binary_tuple_field() {
+    let pair = (1, 2);
+    one_assert::assert!(pair.0 < pair.1);
+    assert_throws!(
+        one_assert::assert!(pair.0 == pair.1),
+        "assertion `pair.0 == pair.1` failed
+    pair.0: 1
+    pair.1: 2"
+    );
+
+    let nested = ((1, 2), 3);
+    assert_throws!(
+        one_assert::assert!((nested.0).1 >= nested.1),
+        "assertion `(nested.0).1 >= nested.1` failed
+    (nested.0).1: 2
+        nested.1: 3"
+    );
+
+    // other operands keep their position as name, even if they contain tuple fields
+    assert_throws!(
+        one_assert::assert!(pair.1 + 1 == 4),
+        "assertion `pair.1 + 1 == 4` failed
+     left: 3
+    right: 4"
+    );
+    assert_throws!(
+        one_assert::assert!(pair.1 * 2 != pair.1 + pair.1),
+        "assertion `pair.1 * 2 != pair.1 + pair.1` failed
+     left: 4
+    right: 4"
+    );
+    assert_throws!(
+        one_assert::assert!((pair.0 & pair.1) == 1),
+        "assertion `(pair.0 & pair.1) == 1` failed
+     left: 0
+    right: 1"
+    );
+}
+
+#[test]
+fn test_binary_min_max() {
+    let (a, b, expected) = (3, 7, 5);
+    one_assert::assert!(std::cmp::max(a, b) == 7);
+    assert_throws!(
+        one_assert::assert!(std::cmp::max(a, b) == expected),
+        "assertion `std::cmp::max(a, b) == expected` failed
+        a: 3
+        b: 7
+     left: 7
+    right: 5"
+    );
+    assert_throws!(
+        one_assert::assert!(a.min(b + 1) > 3),
+        "assertion `a.min(b + 1) > 3` failed
+        a: 3
+    b + 1: 8
+     left: 3
+    right: 3"
+    );
+
+    // literal inputs are already visible in the code
+    let x = 12.5_f64;
+    assert_throws!(
+        one_assert::assert!(x.clamp(0.0, 10.0) < 10.0),
+        "assertion `x.clamp(0.0, 10.0) < 10.0` failed
+        x: 12.5
+     left: 10.0
+    right: 10.0"
+    );
+
+    // the inputs are still only evaluated once
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    let next = || calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+    assert_throws!(
+        one_assert::assert!(std::cmp::min(next(), next()) == 2),
+        "assertion `std::cmp::min(next(), next()) == 2` failed
+    next(): 1
+    next(): 2
+      left: 1
+     right: 2"
+    );
+    assert_eq!(calls.into_inner(), 2);
+}
+
+#[test]
+fn test_binary_option() {
+    let none: Option<i32> = None;
+    let some = Some(5);
+    one_assert::assert!(some == Some(5));
+    one_assert::assert!(none != some);
+
+    assert_throws!(
+        one_assert::assert!(none == some),
+        "assertion `none == some` failed
+     left: None
+    right: Some(5)
+  left is None, right is Some(_)"
+    );
+    assert_throws!(
+        one_assert::assert!(some == Some(6)),
+        "assertion `some == Some(6)` failed
+     left: Some(5)
+    right: Some(6)
+  inner values of Some(_) differ: 5 != 6"
+    );
+
+    let nested = Some(Some("a"));
+    assert_throws!(
+        one_assert::assert!(nested == Some(Some("b"))),
+        r#"assertion `nested == Some(Some("b"))` failed
+     left: Some(Some("a"))
+    right: Some(Some("b"))
+  inner values of Some(Some(_)) differ: "a" != "b""#
+    );
+    assert_throws!(
+        one_assert::assert!(nested == Some(None)),
+        r#"assertion `nested == Some(None)` failed
+     left: Some(Some("a"))
+    right: Some(None)
+  left is Some(Some(_)), right is Some(None)"#
+    );
+
+    // notes are indented with the rest of the nested output
+    assert_throws!(
+        one_assert::assert!({ some == Some(6) }),
+        "assertion `{ some == Some(6) }` failed
+  caused by: block return assertion `some == Some(6)` failed
+       left: Some(5)
+      right: Some(6)
+    inner values of Some(_) differ: 5 != 6"
+    );
+
+    // same debug output, but not equal
+    assert_throws!(
+        one_assert::assert!(Some(f64::NAN) == Some(f64::NAN)),
+        "assertion `Some(f64::NAN) == Some(f64::NAN)` failed
+     left: Some(NaN)
+    right: Some(NaN)"
+    );
+
+    // only for `==`
+    assert_throws!(
+        one_assert::assert!(some != Some(5)),
+        "assertion `some != Some(5)` failed
+     left: Some(5)
+    right: Some(5)"
+    );
+}
+
+#[test]
+fn test_binary_trait_object() {
+    use std::fmt::Debug;
+    use std::panic::RefUnwindSafe;
+
+    trait Shape: Debug + RefUnwindSafe {
+        fn area(&self) -> u32;
+    }
+    impl PartialEq for dyn Shape {
+        fn eq(&self, other: &Self) -> bool {
+            self.area() == other.area()
+        }
+    }
+    #[derive(Debug)]
+    struct Square(u32);
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+    #[derive(Debug)]
+    struct Rect(u32, u32);
+    impl Shape for Rect {
+        fn area(&self) -> u32 {
+            self.0 * self.1
+        }
+    }
+
+    let a: Box<dyn Shape> = Box::new(Square(2));
+    let b: Box<dyn Shape> = Box::new(Rect(1, 4));
+    one_assert::assert!(*a == *b);
+
+    let c: Box<dyn Shape> = Box::new(Rect(2, 3));
+    assert_throws!(
+        one_assert::assert!(*a == *c),
+        "assertion `* a == * c` failed
+     left: Square(2)
+    right: Rect(2, 3)"
+    );
+
+    // other unsized values work the same way
+    let slice: Box<[i32]> = vec![1, 2].into_boxed_slice();
+    assert_throws!(
+        one_assert::assert!(*slice > *vec![1, 3]),
+        "assertion `* slice > * vec! [1, 3]` failed
+     left: [1, 2]
+    right: [1, 3]"
+    );
+}
+
+#[test]
+fn test_binary_map() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let left = BTreeMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    let right = BTreeMap::from([("a", 5), ("b", 2), ("d", 4)]);
+    one_assert::assert!(left == left.clone());
+    assert_throws!(
+        one_assert::assert!(left == right),
+        r#"assertion `left == right` failed
+     left: {"a": 1, "b": 2, "c": 3}
+    right: {"a": 5, "b": 2, "d": 4}
+  only in left: ["c"]
+  only in right: ["d"]
+  key "a": left=1 right=5"#
+    );
+
+    // keys of `HashMap`s are sorted for the output
+    let left: HashMap<_, _> = (0..5).map(|i| (i, i)).collect();
+    let mut right = left.clone();
+    right.insert(3, 0);
+    right.insert(1, 0);
+    right.remove(&4);
+    right.remove(&0);
+    assert_throws!(
+        one_assert::assert!({ left == right }),
+        &format!(
+            "assertion `{{ left == right }}` failed
+  caused by: block return assertion `left == right` failed
+       left: {left:?}
+      right: {right:?}
+    only in left: [0, 4]
+    key 1: left=1 right=0
+    key 3: left=3 right=0"
+        )
+    );
+}
+
+#[test]
+fn test_binary_nan_elements() {
+    let samples = vec![1.0, f64::NAN, 3.0, f64::NAN];
+    let expected = [1.0, f64::NAN, 2.0, 4.0];
+    assert_throws!(
+        one_assert::assert!(samples == expected),
+        "assertion `samples == expected` failed
+     left: [1.0, NaN, 3.0, NaN]
+    right: [1.0, NaN, 2.0, 4.0]
+  index 1: left=NaN right=NaN, NaN never equals itself"
+    );
+    let same = &samples[..2];
+    assert_throws!(
+        one_assert::assert!(same == &expected[..2]),
+        "assertion `same == & expected [.. 2]` failed
+     left: [1.0, NaN]
+    right: [1.0, NaN]
+  index 1: left=NaN right=NaN, NaN never equals itself"
+    );
+    // only collections of floats get the note
+    let (left, right) = (vec![Some(f32::NAN)], vec![Some(f32::NAN)]);
+    assert_throws!(
+        one_assert::assert!(left == right),
+        "assertion `left == right` failed
+     left: [Some(NaN)]
+    right: [Some(NaN)]"
+    );
+}
+
+#[test]
+fn test_binary_time() {
+    use std::time::{Duration, Instant, SystemTime};
+
+    let start = Instant::now();
+    let end = start + Duration::from_millis(1200);
+    one_assert::assert!(end > start);
+    assert_throws!(
+        one_assert::assert!(end < start),
+        &format!(
+            "assertion `end < start` failed
+     left: {end:?}
+    right: {start:?}
+  end - start = 1.2s"
+        )
+    );
+    assert_throws!(
+        one_assert::assert!(start >= end),
+        &format!(
+            "assertion `start >= end` failed
+     left: {start:?}
+    right: {end:?}
+  start - end = -1.2s"
+        )
+    );
+
+    let deadline = SystemTime::UNIX_EPOCH + Duration::from_secs(60);
+    let timeout = Duration::from_secs(5);
+    assert_throws!(
+        one_assert::assert!(deadline + timeout == SystemTime::UNIX_EPOCH),
+        &format!(
+            "assertion `deadline + timeout == SystemTime::UNIX_EPOCH` failed
+     left: {:?}
+    right: {:?}
+  (deadline + timeout) - SystemTime::UNIX_EPOCH = 65s",
+            deadline + timeout,
+            SystemTime::UNIX_EPOCH
+        )
+    );
+}
+
 #[test]
 fn test_block() {
     one_assert::assert!({
@@ -190,8 +760,8 @@ fn test_block() {
             }),
             "assertion `{ let a = 1 ; a == 2 }` failed
   caused by: block return assertion `a == 2` failed
-     left: 1
-    right: 2"
+       left: 1
+      right: 2"
         );
     } else {
         assert_throws!(
@@ -201,8 +771,8 @@ fn test_block() {
             }),
             "assertion `{ let a = 1; a == 2 }` failed
   caused by: block return assertion `a == 2` failed
-     left: 1
-    right: 2"
+       left: 1
+      right: 2"
         );
     }
 }
@@ -357,8 +927,8 @@ fn test_const() {
             ),
             "assertion `const { let a = 1 ; a == 2 }` failed
   caused by: block return assertion `a == 2` failed
-     left: 1
-    right: 2"
+       left: 1
+      right: 2"
         );
     } else {
         assert_throws!(
@@ -370,8 +940,8 @@ fn test_const() {
             ),
             "assertion `const { let a = 1; a == 2 }` failed
   caused by: block return assertion `a == 2` failed
-     left: 1
-    right: 2"
+       left: 1
+      right: 2"
         );
     }
 }
@@ -398,8 +968,41 @@ fn test_field() {
 // #[test]
 // fn test_forloop() {}
 
-// #[test]
-// fn test_group() {}
+#[test]
+fn test_group() {
+    // `$cond:expr` wraps the condition in an invisible group
+    macro_rules! wrapper {
+        ($cond:expr) => {
+            one_assert::assert!($cond)
+        };
+        ($cond:expr, $($arg:tt)*) => {
+            one_assert::assert!($cond, $($arg)*)
+        };
+    }
+
+    let x = 1i32;
+    wrapper!(x == 1);
+    wrapper!(x.is_positive());
+
+    assert_throws!(
+        wrapper!(x == 2),
+        "assertion `x == 2` failed
+     left: 1
+    right: 2"
+    );
+    assert_throws!(
+        wrapper!(x == 2, "x={}", x),
+        "assertion `x == 2` failed: x=1
+     left: 1
+    right: 2"
+    );
+    assert_throws!(
+        wrapper!(!x.is_positive()),
+        "assertion `! x.is_positive()` failed
+    assertion negated: true
+                 self: 1"
+    );
+}
 
 #[test]
 fn test_if() {
@@ -419,8 +1022,8 @@ fn test_if() {
         "assertion `if x == 2 { true } else { y == 3 }` failed
     condition `x == 2`: false
   caused by: block return assertion `y == 3` failed
-     left: 2
-    right: 3"
+       left: 2
+      right: 3"
     );
 
     assert_throws!(
@@ -438,8 +1041,8 @@ fn test_if() {
     condition `x == 0`: false
     condition `x == 1`: true
   caused by: block return assertion `y == x` failed
-     left: 2
-    right: 1"
+       left: 2
+      right: 1"
     );
 
     assert_throws!(
@@ -465,10 +1068,10 @@ fn test_if() {
      condition `false`: false
     condition `x == 2`: false
   caused by: block return assertion `if x == 1 { y == 3 } else { false }` failed
-    condition `x == 1`: true
-  caused by: block return assertion `y == 3` failed
-     left: 2
-    right: 3"
+      condition `x == 1`: true
+    caused by: block return assertion `y == 3` failed
+         left: 2
+        right: 3"
     );
 }
 
@@ -568,8 +1171,8 @@ fn test_match() {
             "assertion `match(x, y) { (2, _) => true, (_, 2) => z == 5, _ => false, }` failed
     matched value: (1, 2)
   caused by: match (x, y) entered arm `(_, 2)` where assertion `z == 5` failed
-     left: 3
-    right: 5"
+       left: 3
+      right: 5"
         );
 
         assert_throws!(
@@ -584,9 +1187,10 @@ fn test_match() {
             "assertion `match x { 2 => true, _ if y < 5 => { let w = 4 ; z == w } _ => false, }` failed
     matched value: 1
   caused by: match x entered arm `_ if y < 5` where assertion `{ let w = 4 ; z == w }` failed
-  caused by: block return assertion `z == w` failed
-     left: 3
-    right: 4"
+      y: 2
+    caused by: block return assertion `z == w` failed
+         left: 3
+        right: 4"
         );
     } else {
         assert_throws!(
@@ -598,8 +1202,8 @@ fn test_match() {
             "assertion `match (x, y) { (2, _) => true, (_, 2) => z == 5, _ => false, }` failed
     matched value: (1, 2)
   caused by: match (x, y) entered arm `(_, 2)` where assertion `z == 5` failed
-     left: 3
-    right: 5"
+       left: 3
+      right: 5"
         );
 
         assert_throws!(
@@ -614,13 +1218,86 @@ fn test_match() {
             "assertion `match x { 2 => true, _ if y < 5 => { let w = 4; z == w } _ => false, }` failed
     matched value: 1
   caused by: match x entered arm `_ if y < 5` where assertion `{ let w = 4; z == w }` failed
-  caused by: block return assertion `z == w` failed
-     left: 3
-    right: 4"
+      y: 2
+    caused by: block return assertion `z == w` failed
+         left: 3
+        right: 4"
         );
     }
 }
 
+#[test]
+fn test_match_guard() {
+    let max = 10;
+    let n = 12;
+    one_assert::assert!(match n {
+        v if v < max => true,
+        v if v >= max => v - max < 3,
+        _ => false,
+    });
+
+    // variables from outside of the arm are printed, the bindings of the pattern are part of the assertion
+    assert_throws!(
+        one_assert::assert!(match n {
+            v if v < max => true,
+            v if v >= max => v - max < 2,
+            _ => false,
+        }),
+        "assertion `match n { v if v < max => true, v if v >= max => v - max < 2, _ => false, }` failed
+    matched value: 12
+  caused by: match n entered arm `v if v >= max` where assertion `v - max < 2` failed
+        max: 10
+       left: 2
+      right: 2"
+    );
+}
+
+#[test]
+fn test_match_catch_all() {
+    let n = 7;
+    one_assert::assert!(match n {
+        0 => false,
+        _ => n > 5,
+    });
+
+    assert_throws!(
+        one_assert::assert!(match n {
+            0 => false,
+            _ => n > 10,
+        }),
+        "assertion `match n { 0 => false, _ => n > 10, }` failed
+    matched value: 7
+  caused by: match n entered catch-all arm `_` where assertion `n > 10` failed
+       left: 7
+      right: 10"
+    );
+
+    // a binding is a catch-all as well, while arms with a guard or an uppercase name are not
+    assert_throws!(
+        one_assert::assert!(match Some(n) {
+            None => false,
+            other => other == Some(10),
+        }),
+        "assertion `match Some(n) { None => false, other => other == Some(10), }` failed
+    matched value: Some(7)
+  caused by: match Some(n) entered catch-all arm `other` where assertion `other == Some(10)` failed
+       left: Some(7)
+      right: Some(10)
+    inner values of Some(_) differ: 7 != 10"
+    );
+    assert_throws!(
+        one_assert::assert!(match n {
+            other if other > 5 => other > 10,
+            _ => true,
+        }),
+        "assertion `match n { other if other > 5 => other > 10, _ => true, }` failed
+    matched value: 7
+  caused by: match n entered arm `other if other > 5` where assertion `other > 10` failed
+       left: 7
+      right: 10"
+    );
+}
+
 #[test]
 fn test_methodcall() {
     let s = String::from("hello");
@@ -634,6 +1311,148 @@ fn test_methodcall() {
     );
 }
 
+#[test]
+fn test_methodcall_all_any() {
+    let v = [1, 2, -3, 4];
+    one_assert::assert!(v.iter().any(|x| *x < 0));
+    one_assert::assert!(v.iter().all(|x| *x != 0));
+
+    assert_throws!(
+        one_assert::assert!(v.iter().all(|x| *x > 0)),
+        "assertion `v.iter().all(| x | * x > 0)` failed
+                     self: Iter([1, 2, -3, 4])
+    first failing element: -3 (at index 2)"
+    );
+    assert_throws!(
+        one_assert::assert!(v.iter().any(|x| *x > 10)),
+        "assertion `v.iter().any(| x | * x > 10)` failed
+                      self: Iter([1, 2, -3, 4])
+    first matching element: none (checked 4 elements)"
+    );
+
+    // the predicate is only applied once per element, and only until the result is known
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    let calls = AtomicUsize::new(0);
+    assert_throws!(
+        one_assert::assert!(v.iter().all(|x| {
+            calls.fetch_add(1, SeqCst);
+            *x > 0
+        })),
+        "assertion `v.iter().all(| x | { calls.fetch_add(1, SeqCst); * x > 0 })` failed
+                     self: Iter([1, 2, -3, 4])
+    first failing element: -3 (at index 2)"
+    );
+    assert_eq!(calls.load(SeqCst), 3);
+
+    // a variable as the receiver is only borrowed, same as with the normal method call
+    let mut iter = v.iter();
+    one_assert::assert!(iter.any(|x| *x == 2));
+    assert_eq!(iter.next(), Some(&-3));
+
+    // a mutable reference is reborrowed, same as with the normal method call
+    fn skip_to_two(iter: &mut std::slice::Iter<i32>) {
+        one_assert::assert!(iter.any(|x| *x == 2));
+    }
+    let mut iter = v.iter();
+    skip_to_two(&mut iter);
+    assert_eq!(iter.next(), Some(&-3));
+
+    // patterns, type annotations and `return` in the predicate work as without the macro
+    assert_throws!(
+        one_assert::assert!(v.iter().all(|&x: &i32| -> bool {
+            if x == 2 {
+                return false;
+            }
+            true
+        })),
+        "assertion `v.iter().all(| & x : & i32 | -> bool { if x == 2 { return false; } true })` failed
+                     self: Iter([1, 2, -3, 4])
+    first failing element: 2 (at index 1)"
+    );
+
+    // receivers that are not an `Iterator`, but have their own `all` or `any`
+    #[derive(Debug)]
+    struct Grid(Vec<i32>);
+    impl Grid {
+        fn all(&self, predicate: impl Fn(&i32) -> bool) -> bool {
+            self.0.iter().all(predicate)
+        }
+    }
+    let grid = Grid(vec![3, 0]);
+    assert_throws!(
+        one_assert::assert!(grid.all(|cell| *cell > 0)),
+        "assertion `grid.all(| cell | * cell > 0)` failed
+                     self: Grid([3, 0])
+    first failing element: 0 (at index 1)"
+    );
+
+    // elements that are not Clone + Debug can only report the index
+    #[derive(Debug)]
+    struct NoClone(i32);
+    let v = vec![NoClone(1), NoClone(-1)];
+    assert_throws!(
+        one_assert::assert!(v.into_iter().all(|x| x.0 > 0)),
+        "assertion `v.into_iter().all(| x | x.0 > 0)` failed
+                     self: IntoIter([NoClone(1), NoClone(-1)])
+    first failing element: <element is not Clone + Debug> (at index 1)"
+    );
+}
+
+#[test]
+fn test_methodcall_pointer() {
+    // `is_aligned` on pointers is newer than the minimum supported Rust version. Inherent methods take
+    // precedence, so this is only used by older compilers
+    #[allow(dead_code)]
+    trait IsAligned {
+        fn is_aligned(&self) -> bool;
+    }
+    impl<T> IsAligned for *const T {
+        fn is_aligned(&self) -> bool {
+            *self as usize % std::mem::align_of::<T>() == 0
+        }
+    }
+
+    let null = std::ptr::null::<u32>();
+    let value = 5u32;
+    let ptr = &value as *const u32;
+    one_assert::assert!(null.is_null());
+    one_assert::assert!(!ptr.is_null());
+    one_assert::assert!(ptr.is_aligned());
+
+    let ptr = 0x12 as *const u32;
+    assert_throws!(
+        one_assert::assert!(ptr.is_null()),
+        "assertion `ptr.is_null()` failed
+    self: 0x12"
+    );
+    assert_throws!(
+        one_assert::assert!(ptr.is_aligned(), "misaligned"),
+        "assertion `ptr.is_aligned()` failed: misaligned
+    self: 0x12
+  required alignment: 4
+    actual alignment: 2"
+    );
+
+    // other types with an `is_aligned` method don't get the note
+    struct Offset(usize);
+    impl std::fmt::Debug for Offset {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Offset({})", self.0)
+        }
+    }
+    impl Offset {
+        fn is_aligned(&self) -> bool {
+            self.0 % 8 == 0
+        }
+    }
+    let offset = Offset(12);
+    assert_throws!(
+        one_assert::assert!(offset.is_aligned()),
+        "assertion `offset.is_aligned()` failed
+    self: Offset(12)"
+    );
+}
+
 #[test]
 fn test_paren() {
     one_assert::assert!((true));
@@ -658,6 +1477,7 @@ fn test_path() {
         pub struct Generic<const N: isize>;
         impl<const N: isize> Generic<N> {
             pub const IS_POSITIVE: bool = N > 0;
+            pub const OFFSET: isize = N;
         }
     }
 
@@ -665,22 +1485,34 @@ fn test_path() {
 
     assert_throws!(
         one_assert::assert!(foo::bar::FALSE),
-        "assertion `foo :: bar :: FALSE` failed"
+        "assertion `foo::bar::FALSE` failed"
     );
 
     one_assert::assert!(foo::Generic::<1>::IS_POSITIVE);
 
-    if rustc_version::version().unwrap() < rustc_version::Version::new(1, 75, 0) {
-        assert_throws!(
-            one_assert::assert!(foo::Generic::<-1>::IS_POSITIVE),
-            "assertion `foo :: Generic :: < - 1 > :: IS_POSITIVE` failed"
-        );
-    } else {
-        assert_throws!(
-            one_assert::assert!(foo::Generic::<-1>::IS_POSITIVE),
-            "assertion `foo :: Generic :: < -1 > :: IS_POSITIVE` failed"
-        );
+    assert_throws!(
+        one_assert::assert!(foo::Generic::<-1>::IS_POSITIVE),
+        "assertion `foo::Generic::<-1>::IS_POSITIVE` failed"
+    );
+
+    fn check_offset<const N: isize>() {
+        one_assert::assert!(foo::Generic::<N>::OFFSET > 0);
     }
+    check_offset::<1>();
+    assert_throws!(
+        check_offset::<-2>(),
+        "assertion `foo::Generic::<N>::OFFSET > 0` failed
+     left: -2
+    right: 0"
+    );
+
+    one_assert::assert!(foo::Generic::<{ 1 + 1 }>::OFFSET == 2);
+    assert_throws!(
+        one_assert::assert!(foo::Generic::<{ 1 + 1 }>::OFFSET < foo::Generic::<1>::OFFSET),
+        "assertion `foo::Generic::<{ 1 + 1 }>::OFFSET < foo::Generic::<1>::OFFSET` failed
+     left: 2
+    right: 1"
+    );
 }
 
 // #[test]
@@ -689,8 +1521,26 @@ fn test_path() {
 // #[test]
 // fn test_reference() {}
 
-// #[test]
-// fn test_repeat() {}
+#[test]
+fn test_repeat() {
+    let arr = [0, 0, 1];
+    one_assert::assert!([0; 2] == &arr[..2]);
+    assert_throws!(
+        one_assert::assert!([0; 3] == arr),
+        "assertion `[0; 3] == arr` failed
+     left: [0, 0, 0]
+    right: [0, 0, 1]"
+    );
+
+    let map =
+        std::collections::HashMap::<[i32; 2], bool>::from_iter([([0, 0], false), ([1, 1], true)]);
+    one_assert::assert!(map[&[1; 2]]);
+    assert_throws!(
+        one_assert::assert!(map[&[0; 2]]),
+        "assertion `map [& [0; 2]]` failed
+    index: [0, 0]"
+    );
+}
 
 // #[test]
 // fn test_return() {}
@@ -715,7 +1565,25 @@ fn test_try() {
             Ok(())
         })()
         .unwrap(),
-        "assertion `x ?` failed"
+        "assertion `x?` failed"
+    );
+    assert_throws!(
+        (|| -> Option<()> {
+            let x = Some(Ok::<_, ()>(false));
+            one_assert::assert!(x?.ok()?);
+            None
+        })()
+        .unwrap(),
+        "assertion `x?.ok()?` failed"
+    );
+    assert_throws!(
+        (|| -> Option<()> {
+            let x = Some(Some(false));
+            one_assert::assert!(x??);
+            None
+        })()
+        .unwrap(),
+        "assertion `x??` failed"
     );
 }
 
@@ -796,11 +1664,26 @@ fn test_unary() {
 fn test_unsafe() {
     one_assert::assert!(unsafe { std::mem::transmute(1u8) });
 
+    unsafe fn one() -> i32 {
+        1
+    }
+    unsafe fn two() -> i32 {
+        2
+    }
+    one_assert::assert!(unsafe { one() } < unsafe { two() });
+    one_assert::assert!(unsafe { one() } != unsafe { two() }, warn_unused);
+
     assert_throws!(
         one_assert::assert!(unsafe { std::mem::transmute(0u8) }),
-        "assertion `unsafe { std :: mem :: transmute(0u8) }` failed
-  caused by: block return assertion `std :: mem :: transmute(0u8)` failed
-    arg 0: 0"
+        "assertion `unsafe { std::mem::transmute(0u8) }` failed
+  caused by: block return assertion `std::mem::transmute(0u8)` failed
+      arg 0: 0"
+    );
+    assert_throws!(
+        one_assert::assert!(unsafe { one() } == unsafe { two() }),
+        "assertion `unsafe { one() } == unsafe { two() }` failed
+     left: 1
+    right: 2"
     );
 }
 
@@ -813,3 +1696,47 @@ fn test_unsafe() {
 // Experimental syntax:
 // test_tryblock
 // test_yield
+
+#[test]
+fn test_ordering_tiers() {
+    use std::cmp::Ordering;
+    // (age, name, id)
+    let x = (30, "Alice", 1);
+    let y = (30, "Bob", 2);
+
+    one_assert::assert!(x.0.cmp(&y.0).then(x.1.cmp(y.1)) == Ordering::Less);
+    assert_throws!(
+        one_assert::assert!(x
+            .0
+            .cmp(&y.0)
+            .then(x.1.cmp(y.1))
+            .then_with(|| x.2.cmp(&y.2))
+            .is_eq()),
+        "assertion `x.0.cmp(& y.0).then(x.1.cmp(y.1)).then_with(| | x.2.cmp(& y.2)).is_eq()` failed
+    x.0.cmp(& y.0): Equal
+      x.1.cmp(y.1): Less
+    x.2.cmp(& y.2): <not evaluated>
+              self: Less"
+    );
+
+    // `then_with` is still only called if the previous tiers are equal
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    let by_id = |p: &(u32, &str, u32), q: &(u32, &str, u32)| {
+        calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        p.2.cmp(&q.2)
+    };
+    one_assert::assert!(y.0.cmp(&x.0).then_with(|| by_id(&y, &x)) == Ordering::Greater);
+    one_assert::assert!(y.1.cmp(x.1).then_with(|| by_id(&y, &x)) == Ordering::Greater);
+    assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_throws!(
+        one_assert::assert!(x.0.cmp(&y.0).then_with(|| by_id(&x, &y)) == Ordering::Equal),
+        "assertion `x.0.cmp(& y.0).then_with(| | by_id(& x, & y)) == Ordering::Equal` failed
+     x.0.cmp(& y.0): Equal
+    by_id(& x, & y): Less
+               left: Less
+              right: Equal"
+    );
+
+    // `bool::then` is not an `Ordering` tier
+    one_assert::assert!((x.0 > 20).then(|| x.2 + 1) == Some(2));
+}
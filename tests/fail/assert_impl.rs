@@ -0,0 +1,7 @@
+use std::rc::Rc;
+
+one_assert::assert_impl!(Rc<i32>: Send);
+
+fn main() {
+    one_assert::assert_impl!(Vec<f64>: Eq + Clone);
+}
@@ -0,0 +1,4 @@
+fn main() {
+    one_assert::assert_bits_eq!(1, 2);
+    one_assert::assert_bits_eq!(1.0f32, 1.0f64);
+}
@@ -0,0 +1,41 @@
+fn main() {
+    let v = vec![1, 2, 3];
+    one_assert::assert_contains!();
+    one_assert::assert_contains!(v);
+    one_assert::assert_contains!(v 1);
+    one_assert::assert_contains!(v, 1 "message");
+
+    one_assert::assert_one_of!(1, vec![1, 2]);
+    one_assert::assert_one_of!(1, []);
+
+    one_assert::assert_converges!(|x| x, 1, 10, done = |x| *x == 1);
+    one_assert::assert_converges!(|x| x, 1, max_steps = 10, until = |x| *x == 1);
+
+    one_assert::assert_similar!("a", "b", distance = 1);
+    one_assert::assert_similar!("a", "b", max_distance = 1 "message");
+    one_assert::assert_monotonic!([1, 2], strict = yes);
+    one_assert::assert_monotonic!([1, 2], direction = "up");
+    one_assert::assert_monotonic!([1, 2], strict = true, strict = false);
+    one_assert::assert_unchanged!(x, 1);
+    one_assert::assert_eq_ignoring!(1, 2, id);
+    one_assert::assert_eq_ignoring!(1, 2, [a.b]);
+    one_assert::assert_starts_with!([1, 2]);
+    one_assert::assert_all_close!([1.0], [1.0] 0.1);
+    one_assert::assert_matches_glob!("a.rs");
+    one_assert::assert_err_contains!(Err::<(), std::fmt::Error>(std::fmt::Error));
+    one_assert::assert_drop_count!(std::sync::atomic::AtomicUsize::new(0) 0);
+    one_assert::assert_within_steps!(10);
+    one_assert::assert_ready!();
+    one_assert::assert_same_elements!([1]);
+    one_assert::assert_hash_ne!(1);
+    one_assert::assert_eq_by!(1, 2);
+    one_assert::assert_fields_eq!(1);
+    one_assert::assert_maintains!(true);
+    one_assert::assert_matrix_close!([[1.0]], [[1.0]]);
+    one_assert::assert_returning!(compute(); true);
+    one_assert::assert_returning!(v = 1, v > 0);
+    one_assert::assert_impl!(String Clone);
+    one_assert::assert_size!(u8 1);
+    one_assert::assert_order!(vec![1], [1]);
+    one_assert::assert_count!(0..3 3);
+}
@@ -0,0 +1,6 @@
+fn main() {
+    one_assert::assert!(1 > 2);
+    one_assert::assert!(5u8 == 6);
+    one_assert::assert!(true == false, "message");
+    one_assert::assert!(3 != 3);
+}
@@ -0,0 +1,16 @@
+#[derive(one_assert::OneAssertDiscriminant)]
+struct NotAnEnum(u8);
+
+#[derive(one_assert::OneAssertDiscriminant)]
+enum WithFields {
+    A,
+    B(u8),
+    C { x: u8 },
+}
+
+#[derive(one_assert::OneAssertFields)]
+enum NotAStruct {
+    A,
+}
+
+fn main() {}
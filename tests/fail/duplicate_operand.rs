@@ -0,0 +1,29 @@
+#![deny(deprecated)]
+
+fn compute() -> i32 {
+    1
+}
+
+fn other() -> i32 {
+    2
+}
+
+fn main() {
+    let v = vec![1, 2];
+    one_assert::assert!(compute() == compute(), warn_duplicates);
+    one_assert::assert!(v.len() <= v.len(), warn_duplicates);
+    one_assert::assert!(compute() == compute() && other() == other(), warn_duplicates);
+
+    // nothing is evaluated twice
+    one_assert::assert!(v == v, warn_duplicates);
+    one_assert::assert!(v[0] == v[0], warn_duplicates);
+    one_assert::assert!(compute() == compute() + 0, warn_duplicates);
+    one_assert::assert!(Some(1) == Some(1), warn_duplicates);
+    #[allow(deprecated)]
+    {
+        one_assert::assert!(compute() == compute(), warn_duplicates);
+    }
+
+    // only with the option
+    one_assert::assert!(compute() == compute() && other() == other());
+}
@@ -10,4 +10,16 @@ fn main() {
     one_assert::assert!({
         let x = 5;
     });
+    one_assert::assert!({
+        let Some(x) = Some(5) else { return };
+    });
+    one_assert::assert!({
+        let x = 5;
+        fn helper() {}
+    });
+    one_assert::assert!({});
+    one_assert::assert!({
+        let x = 5;
+        matches!(x, 1..=9)
+    });
 }
@@ -0,0 +1,12 @@
+macro_rules! wrapper {
+    ($cond:expr) => {
+        one_assert::assert!($cond)
+    };
+}
+
+fn main() {
+    let x = 1i32;
+    wrapper!(x);
+    wrapper!(x + 1);
+    wrapper!(x.pow(2));
+}
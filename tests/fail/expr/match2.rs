@@ -2,3 +2,12 @@ fn main() {
     // the other match.rs file checks for types and stops checking, so this file is needed to check for later errors
     one_assert::assert!(match 0 {});
 }
+
+fn non_exhaustive() {
+    // the arms are rewritten, but the match itself still has to be exhaustive
+    let x = Some(1);
+    one_assert::assert!(match x {
+        Some(1) => true,
+        None => false,
+    });
+}
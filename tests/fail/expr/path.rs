@@ -1,4 +1,17 @@
+mod config {
+    pub const LIMIT: u32 = 5;
+    pub mod nested {
+        pub static COUNT: u32 = 0;
+    }
+}
+
 fn main() {
     one_assert::assert!(std::vec);
     one_assert::assert!(std::f32::consts::PI);
+
+    // the whole path is underlined, not just the first segment
+    one_assert::assert!(config::LIMIT);
+    one_assert::assert!(crate::config::nested::COUNT);
+    let some_u32 = 1u32;
+    one_assert::assert!(some_u32);
 }
@@ -0,0 +1,14 @@
+fn main() {
+    fn hex(value: &i32) -> String {
+        format!("{value:#x}")
+    }
+    fn not_a_string(value: &i32) -> i32 {
+        *value
+    }
+    let (a, b) = (1, 2);
+    one_assert::assert!(#[fmt_with] a == b);
+    one_assert::assert!(#[fmt_with(hex)] #[fmt_with(hex)] a == b);
+    one_assert::assert!(#[fmt_with(not_a_string)] a == b);
+    one_assert::assert!(#[display(hex)] a == b);
+    one_assert::assert!(#[display] #[fmt_with(hex)] a == b);
+}
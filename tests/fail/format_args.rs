@@ -0,0 +1,19 @@
+fn main() {
+    let x = 1;
+    let name = "x";
+
+    // valid
+    one_assert::assert!(x == 1, "{{}} {name} {}", x);
+    one_assert::assert!(x == 1, "{1} {0} {}", x, 2);
+    one_assert::assert!(x == 1, "{:.*} {:>0$} {value}", 2, 1.5, x, value = x);
+    one_assert::assert!(x == 1, concat!("{}", "{}"), x, x);
+    one_assert::assert!(x == 1, "{condition} {}", x);
+
+    // invalid
+    one_assert::assert!(x == 2, "value: {}");
+    one_assert::assert!(x == 2, "{} and {:?}", x);
+    one_assert::assert!(x == 2, "{{}} {name}", x);
+    one_assert::assert!(x == 2, "{2}", x, x);
+    one_assert::assert!(x == 2, "{:.*}", 2);
+    one_assert::assert!(x == 2, "{condition}", condition = x);
+}
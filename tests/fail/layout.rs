@@ -0,0 +1,11 @@
+#[repr(C)]
+struct Header {
+    tag: u8,
+    length: u64,
+}
+
+one_assert::assert_size!(Header, 12);
+
+fn main() {
+    one_assert::assert_align!(Header, 4);
+}
@@ -1,8 +1,13 @@
-fn main() {
-    one_assert::assert!(1 ==);
-    one_assert::assert!(1 ==, "hi");
-    one_assert::assert!(== 1);
-    one_assert::assert!(== 1, "hi");
-    one_assert::assert!(==);
-    one_assert::assert!(==, "hi");
-}
+fn main() {
+    one_assert::assert!(1 ==);
+    one_assert::assert!(1 ==, "hi");
+    one_assert::assert!(== 1);
+    one_assert::assert!(== 1, "hi");
+    one_assert::assert!(==);
+    one_assert::assert!(==, "hi");
+    one_assert::assert!(1 < 2 &&);
+    one_assert::assert!(1 < 2 ||, "hi");
+    one_assert::assert!(!);
+    one_assert::assert!(x.);
+    one_assert::assert!(1 as);
+}
@@ -1,6 +1,11 @@
 fn main() {
-    one_assert::assert!(1 == 2, "{}");
-    one_assert::assert!(1 == 2 "{}");
-    one_assert::assert!(1 == 2, , "{}");
-    one_assert::assert!(1 == 2, "{}", 1, 2);
+    let x = 1;
+    one_assert::assert!(x == 2, "{}");
+    one_assert::assert!(x == 2 "{}");
+    one_assert::assert!(x == 2, , "{}");
+    one_assert::assert!(x == 2, "{}", 1, 2);
+    one_assert::assert!(actual = x, x == 2);
+    one_assert::assert!(actual = x);
+    one_assert::assert!(actual = x, actual = 2);
+    one_assert::assert!(x == 2, expected = 2);
 }
@@ -1,3 +1,4 @@
 fn main() {
     one_assert::assert!();
+    one_assert::assert!(invariant:);
 }
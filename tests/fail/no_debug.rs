@@ -5,3 +5,17 @@ fn main() {
     let x = NoDebugImpl(1);
     one_assert::assert!(x == NoDebugImpl(2));
 }
+
+fn trait_object() {
+    trait Shape {}
+    impl PartialEq for dyn Shape {
+        fn eq(&self, _: &Self) -> bool {
+            true
+        }
+    }
+    struct Square;
+    impl Shape for Square {}
+
+    let a: Box<dyn Shape> = Box::new(Square);
+    one_assert::assert!(*a == *a);
+}
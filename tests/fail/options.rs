@@ -0,0 +1,18 @@
+fn main() {
+    one_assert::assert!(1 == 2, unknown_option);
+    one_assert::assert!(1 == 2, layout = "nonsense");
+    one_assert::assert!(1 == 2, layout = 5);
+    one_assert::assert!(1 == 2, layout);
+    one_assert::assert!(1 == 2, layout = "default", layout = "default");
+    one_assert::assert!(1 == 2, clean = true);
+    one_assert::assert!(1 == 2, precision = "2");
+    one_assert::assert!(1 == 2, precision);
+    one_assert::assert!(1 == 2, level = "fatal");
+    one_assert::assert!(1 == 2, message_position = "footer");
+    one_assert::assert!(1 == 2, fmt_with = "hex");
+    one_assert::assert!(1 == 2, capture = 0);
+    one_assert::assert!(i32::max(1, 2) == 1, capture = [0, 2]);
+    one_assert::assert!(1 == 2, captures = [x + 1]);
+    one_assert::assert!(1 == 2, captures = [x]);
+    one_assert::assert!(1 == 2, redact = "secret");
+}
@@ -0,0 +1,4 @@
+// unbalanced delimiters are rejected by the compiler before the macro is called
+fn main() {
+    one_assert::assert!((1 == 2);
+}
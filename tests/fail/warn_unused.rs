@@ -0,0 +1,12 @@
+#![deny(unused)]
+
+fn main() {
+    one_assert::assert!(
+        {
+            let unused = 5;
+            true
+        },
+        warn_unused
+    );
+    one_assert::assert!((1 == 1), warn_unused);
+}
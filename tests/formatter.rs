@@ -0,0 +1,84 @@
+//! The formatter is global, so it is tested in its own binary to not affect the other tests
+
+fn debug_formatter(failure: &one_assert::AssertFailure) -> String {
+    format!("{failure:#?}")
+}
+
+fn catch_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    let error = std::panic::catch_unwind(f).unwrap_err();
+    *error.downcast::<String>().unwrap()
+}
+
+#[test]
+fn test_set_formatter() {
+    one_assert::set_formatter(debug_formatter).unwrap();
+    assert!(one_assert::set_formatter(debug_formatter).is_err());
+
+    let message = catch_message(|| {
+        let x = 1;
+        let v = [Some(1), Some(2)];
+        one_assert::assert!(v[x] == Some(x), "{} != {}", "a", "b");
+    });
+    assert_eq!(
+        message,
+        r#"AssertFailure {
+    expression: "v [x] == Some(x)",
+    message: Some(
+        "a != b",
+    ),
+    values: [
+        (
+            "left",
+            "Some(2)",
+        ),
+        (
+            "right",
+            "Some(1)",
+        ),
+    ],
+    notes: [
+        "inner values of Some(_) differ: 2 != 1",
+    ],
+    causes: [],
+    default_message: "assertion `v [x] == Some(x)` failed: a != b\n     left: Some(2)\n    right: Some(1)\n  inner values of Some(_) differ: 2 != 1",
+}"#
+    );
+
+    let message = catch_message(|| {
+        let x = 1;
+        one_assert::assert!({
+            let y = x + 1;
+            y > 2
+        });
+    });
+    assert_eq!(
+        message,
+        r#"AssertFailure {
+    expression: "{ let y = x + 1; y > 2 }",
+    message: None,
+    values: [],
+    notes: [],
+    causes: [
+        FailureCause {
+            description: "block return assertion `y > 2` failed",
+            values: [
+                (
+                    "left",
+                    "2",
+                ),
+                (
+                    "right",
+                    "2",
+                ),
+            ],
+            notes: [],
+        },
+    ],
+    default_message: "assertion `{ let y = x + 1; y > 2 }` failed\n  caused by: block return assertion `y > 2` failed\n       left: 2\n      right: 2",
+}"#
+    );
+
+    // `try_assert!` returns the formatted message as the error
+    let result = one_assert::try_assert!(1 + 1 == 3);
+    assert!(result.unwrap_err().message().starts_with("AssertFailure {"));
+}
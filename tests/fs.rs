@@ -0,0 +1,65 @@
+//! Only with the `fs` feature: `cargo test --features fs`
+#![cfg(feature = "fs")]
+
+macro_rules! assert_throws {
+    ( $block:block, $message:expr $(,)? ) => {
+        let error = std::panic::catch_unwind(|| $block).unwrap_err();
+        if let Some(s) = error.downcast_ref::<&'static str>() {
+            assert_eq!(*s, $message);
+        } else if let Some(s) = error.downcast_ref::<String>() {
+            assert_eq!(s, $message);
+        } else {
+            panic!("unexpected panic payload: {:?}", error);
+        }
+    };
+    ( $statement:expr, $message:expr $(,)? ) => {
+        assert_throws!({ $statement }, $message);
+    };
+}
+
+#[test]
+fn test_assert_files_eq() {
+    let dir = std::env::temp_dir().join(format!("one_assert_files_eq_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let hello = dir.join("hello.txt");
+    let hello_copy = dir.join("hello_copy.txt");
+    let hello_upper = dir.join("hello_upper.txt");
+    let hello_long = dir.join("hello_long.txt");
+    std::fs::write(&hello, "hello world\n").unwrap();
+    std::fs::write(&hello_copy, "hello world\n").unwrap();
+    std::fs::write(&hello_upper, "hello World\n").unwrap();
+    std::fs::write(&hello_long, "hello world\nagain").unwrap();
+
+    one_assert::assert_files_eq!(hello, hello_copy);
+    one_assert::assert_files_eq!(&hello, hello.to_str().unwrap());
+
+    assert_throws!(
+        one_assert::assert_files_eq!(hello, hello_upper),
+        r#"assertion `files hello and hello_upper have equal contents` failed
+           left size: 12 bytes
+          right size: 12 bytes
+    first difference: byte 6
+         left[3..10]: b"lo worl"
+        right[3..10]: b"lo Worl""#
+    );
+    assert_throws!(
+        one_assert::assert_files_eq!(hello_long, hello, "after {} runs", 2),
+        r#"assertion `files hello_long and hello have equal contents` failed: after 2 runs
+           left size: 17 bytes
+          right size: 12 bytes
+    first difference: byte 12 (end of right)
+         left[9..16]: b"ld\nagai"
+        right[9..12]: b"ld\n""#
+    );
+
+    let missing = dir.join("missing.txt");
+    let message = format!(
+        "assertion `files hello and missing have equal contents` failed
+    right: couldn't read {:?}: {}",
+        missing,
+        std::fs::read(&missing).unwrap_err()
+    );
+    assert_throws!(one_assert::assert_files_eq!(hello, missing), &*message);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,79 @@
+//! Only with the `serde_json` feature: `cargo test --features serde_json`
+#![cfg(feature = "serde_json")]
+
+macro_rules! assert_throws {
+    ( $block:block, $message:expr $(,)? ) => {
+        let error = std::panic::catch_unwind(|| $block).unwrap_err();
+        if let Some(s) = error.downcast_ref::<&'static str>() {
+            assert_eq!(*s, $message);
+        } else if let Some(s) = error.downcast_ref::<String>() {
+            assert_eq!(s, $message);
+        } else {
+            panic!("unexpected panic payload: {:?}", error);
+        }
+    };
+    ( $statement:expr, $message:expr $(,)? ) => {
+        assert_throws!({ $statement }, $message);
+    };
+}
+
+#[test]
+fn test_json_index() {
+    let value = serde_json::json!({ "name": "one_assert", "tags": ["a", "b"], "version": 1 });
+
+    one_assert::assert!(value["name"] == "one_assert");
+    one_assert::assert!(value["tags"][1] == "b");
+    assert_throws!(
+        one_assert::assert!(value["nmae"] == "one_assert"),
+        r#"assertion `value ["nmae"] == "one_assert"` failed
+     left: Null
+    right: "one_assert"
+  keys of value: ["name", "tags", "version"]"#
+    );
+    assert_throws!(
+        one_assert::assert!(value["tags"][2] == "c"),
+        r#"assertion `value ["tags"] [2] == "c"` failed
+     left: Null
+    right: "c"
+  length of value ["tags"]: 2"#
+    );
+
+    let tags = ["a", "b"];
+    one_assert::assert!(tags[0] == "a");
+    assert_throws!(
+        one_assert::assert!(tags[1] == "a"),
+        r#"assertion `tags [1] == "a"` failed
+     left: "b"
+    right: "a""#
+    );
+}
+
+#[test]
+fn test_json_predicates() {
+    let value = serde_json::json!({ "name": "one_assert", "version": 1.5 });
+
+    one_assert::assert!(value["name"].is_string());
+    one_assert::assert!(value.is_object());
+    one_assert::assert!(!value["version"].is_u64());
+    assert_throws!(
+        one_assert::assert!(value["version"].is_u64()),
+        r#"assertion `value ["version"].is_u64()` failed
+    self: Number(1.5)
+  keys of value: ["name", "version"]
+  self is the number 1.5, expected a number that fits in a u64"#
+    );
+    assert_throws!(
+        one_assert::assert!(value["nmae"].is_string()),
+        r#"assertion `value ["nmae"].is_string()` failed
+    self: Null
+  keys of value: ["name", "version"]
+  self is null, expected a string"#
+    );
+    assert_throws!(
+        one_assert::assert!(!value.is_object()),
+        r#"assertion `! value.is_object()` failed
+    assertion negated: true
+                 self: Object {"name": String("one_assert"), "version": Number(1.5)}
+  self is an object, expected anything but an object"#
+    );
+}
@@ -1,3 +1,14 @@
+// the tests intentionally use unusual expressions to cover every kind of syntax
+#![allow(
+    clippy::missing_transmute_annotations,
+    clippy::never_loop,
+    clippy::nonminimal_bool,
+    clippy::too_many_arguments,
+    clippy::transmute_int_to_bool,
+    clippy::unnecessary_cast,
+    clippy::useless_concat
+)]
+
 #[macro_export]
 macro_rules! assert_throws {
     ( $block:block, $message:expr $(,)? ) => {
@@ -117,8 +128,8 @@ fn test_negated_binary() {
         one_assert::assert!(!(b && true)),
         "assertion `! (b && true)` failed
     assertion negated: true
-                 left: true
-                right: true"
+            operand 1: true
+            operand 2: true"
     );
 
     one_assert::assert!(!(b & false));
@@ -136,8 +147,8 @@ fn test_negated_binary() {
         one_assert::assert!(!(b || true)),
         "assertion `! (b || true)` failed
     assertion negated: true
-                 left: false
-                right: true"
+            operand 1: false
+            operand 2: true"
     );
 
     one_assert::assert!(!(b | false));
@@ -203,8 +214,8 @@ fn test_negated_block() {
             "assertion `! { let a = 1 ; a == 1 }` failed
     assertion negated: true
   caused by: block return assertion `a == 1` failed
-     left: 1
-    right: 1"
+       left: 1
+      right: 1"
         );
     } else {
         assert_throws!(
@@ -215,8 +226,8 @@ fn test_negated_block() {
             "assertion `! { let a = 1; a == 1 }` failed
     assertion negated: true
   caused by: block return assertion `a == 1` failed
-     left: 1
-    right: 1"
+       left: 1
+      right: 1"
         );
     }
 }
@@ -381,8 +392,8 @@ fn test_negated_const() {
             "assertion `! const { let a = 1 ; a == 1 }` failed
     assertion negated: true
   caused by: block return assertion `a == 1` failed
-     left: 1
-    right: 1"
+       left: 1
+      right: 1"
         );
     } else {
         assert_throws!(
@@ -395,8 +406,8 @@ fn test_negated_const() {
             "assertion `! const { let a = 1; a == 1 }` failed
     assertion negated: true
   caused by: block return assertion `a == 1` failed
-     left: 1
-    right: 1"
+       left: 1
+      right: 1"
         );
     }
 }
@@ -447,8 +458,8 @@ fn test_negated_if() {
      assertion negated: true
     condition `x == 2`: false
   caused by: block return assertion `y == 3` failed
-     left: 3
-    right: 3"
+       left: 3
+      right: 3"
     );
 
     assert_throws!(
@@ -467,8 +478,8 @@ else { panic! () }` failed
     condition `x == 0`: false
     condition `x == 1`: true
   caused by: block return assertion `y == x + 2` failed
-     left: 3
-    right: 3"
+       left: 3
+      right: 3"
     );
 
     assert_throws!(
@@ -491,12 +502,12 @@ x == 2 { false } else { ! if x == 1 { ! (y == 3) } else { false } }` failed
      condition `false`: false
     condition `x == 2`: false
   caused by: block return assertion `! if x == 1 { ! (y == 3) } else { false }` failed
-     assertion negated: true
-    condition `x == 1`: true
-  caused by: block return assertion `! (y == 3)` failed
-    assertion negated: true
-                 left: 3
-                right: 3"
+       assertion negated: true
+      condition `x == 1`: true
+    caused by: block return assertion `! (y == 3)` failed
+        assertion negated: true
+                     left: 3
+                    right: 3"
     );
 }
 
@@ -609,9 +620,9 @@ fn test_negated_match() {
     assertion negated: true
         matched value: (1, 2)
   caused by: match (x, y) entered arm `(_, 2)` where assertion `! (z == 5)` failed
-    assertion negated: true
-                 left: 3
-                right: 5"
+      assertion negated: true
+                   left: 3
+                  right: 5"
         );
 
         assert_throws!(
@@ -627,9 +638,10 @@ fn test_negated_match() {
     assertion negated: true
         matched value: 1
   caused by: match x entered arm `_ if y < 5` where assertion `{ let w = 4 ; z != w }` failed
-  caused by: block return assertion `z != w` failed
-     left: 3
-    right: 4"
+      y: 2
+    caused by: block return assertion `z != w` failed
+         left: 3
+        right: 4"
         );
     } else {
         assert_throws!(
@@ -642,9 +654,9 @@ fn test_negated_match() {
     assertion negated: true
         matched value: (1, 2)
   caused by: match (x, y) entered arm `(_, 2)` where assertion `! (z == 5)` failed
-    assertion negated: true
-                 left: 3
-                right: 5"
+      assertion negated: true
+                   left: 3
+                  right: 5"
         );
 
         assert_throws!(
@@ -660,9 +672,10 @@ fn test_negated_match() {
     assertion negated: true
         matched value: 1
   caused by: match x entered arm `_ if y < 5` where assertion `{ let w = 4; z != w }` failed
-  caused by: block return assertion `z != w` failed
-     left: 3
-    right: 4"
+      y: 2
+    caused by: block return assertion `z != w` failed
+         left: 3
+        right: 4"
         );
     }
 }
@@ -721,7 +734,7 @@ fn test_negated_path() {
 
     assert_throws!(
         one_assert::assert!(!foo::bar::TRUE),
-        "assertion `! foo :: bar :: TRUE` failed
+        "assertion `! foo::bar::TRUE` failed
     assertion negated: true"
     );
 
@@ -729,7 +742,7 @@ fn test_negated_path() {
 
     assert_throws!(
         one_assert::assert!(!foo::Generic::<3>::IS_POSITIVE),
-        "assertion `! foo :: Generic :: < 3 > :: IS_POSITIVE` failed
+        "assertion `! foo::Generic::<3>::IS_POSITIVE` failed
     assertion negated: true"
     );
 }
@@ -766,7 +779,7 @@ fn test_negated_try() {
             Ok(())
         })()
         .unwrap(),
-        "assertion `! x ?` failed
+        "assertion `! x?` failed
     assertion negated: true"
     );
 }
@@ -865,10 +878,10 @@ fn test_negated_unsafe() {
 
     assert_throws!(
         one_assert::assert!(!unsafe { std::mem::transmute(1u8) }),
-        "assertion `! unsafe { std :: mem :: transmute(1u8) }` failed
+        "assertion `! unsafe { std::mem::transmute(1u8) }` failed
     assertion negated: true
-  caused by: block return assertion `std :: mem :: transmute(1u8)` failed
-    arg 0: 1"
+  caused by: block return assertion `std::mem::transmute(1u8)` failed
+      arg 0: 1"
     );
 }
 
@@ -0,0 +1,1063 @@
+#![allow(clippy::neg_cmp_op_on_partial_ord, clippy::nonminimal_bool)]
+
+macro_rules! assert_throws {
+    ( $block:block, $message:expr $(,)? ) => {
+        let error = std::panic::catch_unwind(|| $block).unwrap_err();
+        if let Some(s) = error.downcast_ref::<&'static str>() {
+            assert_eq!(*s, $message);
+        } else if let Some(s) = error.downcast_ref::<String>() {
+            assert_eq!(s, $message);
+        } else {
+            panic!("unexpected panic payload: {:?}", error);
+        }
+    };
+    ( $statement:expr, $message:expr $(,)? ) => {
+        assert_throws!({ $statement }, $message);
+    };
+}
+
+#[test]
+fn test_layout_pretty_assertions() {
+    #[derive(Debug, PartialEq)]
+    struct Foo {
+        lorem: &'static str,
+        ipsum: u32,
+    }
+
+    let x = Foo {
+        lorem: "Hello World!",
+        ipsum: 42,
+    };
+    let y = Foo {
+        lorem: "Hello Wrold!",
+        ipsum: 42,
+    };
+    one_assert::assert!(x == x, layout = "pretty_assertions");
+
+    assert_throws!(
+        one_assert::assert!(x == y, layout = "pretty_assertions"),
+        "assertion `x == y` failed
+
+\u{1b}[1mDiff\u{1b}[0m \u{1b}[31m<\u{1b}[0m \u{1b}[31mleft\u{1b}[0m / \u{1b}[32mright\u{1b}[0m \u{1b}[32m>\u{1b}[0m :
+ Foo {
+\u{1b}[31m<    lorem: \"Hello World!\",\u{1b}[0m
+\u{1b}[32m>    lorem: \"Hello Wrold!\",\u{1b}[0m
+     ipsum: 42,
+ }"
+    );
+
+    let one = 1;
+    assert_throws!(
+        one_assert::assert!(one == 2, layout = "pretty_assertions", "x={}", 5),
+        "assertion `one == 2` failed: x=5
+
+\u{1b}[1mDiff\u{1b}[0m \u{1b}[31m<\u{1b}[0m \u{1b}[31mleft\u{1b}[0m / \u{1b}[32mright\u{1b}[0m \u{1b}[32m>\u{1b}[0m :
+\u{1b}[31m<1\u{1b}[0m
+\u{1b}[32m>2\u{1b}[0m"
+    );
+
+    // non-`==` assertions keep the default layout
+    assert_throws!(
+        one_assert::assert!(one > 2, layout = "pretty_assertions"),
+        "assertion `one > 2` failed
+     left: 1
+    right: 2"
+    );
+    assert_throws!(
+        one_assert::assert!(!(1 == 1), layout = "pretty_assertions"),
+        "assertion `! (1 == 1)` failed
+    assertion negated: true
+                 left: 1
+                right: 1"
+    );
+}
+
+#[test]
+fn test_clean() {
+    use std::marker::PhantomData;
+
+    #[derive(Debug, PartialEq)]
+    struct Typed<T> {
+        value: u32,
+        text: &'static str,
+        _marker: PhantomData<T>,
+    }
+    let x = Typed::<Vec<Option<fn(u8) -> u8>>> {
+        value: 1,
+        text: "PhantomData<u8>",
+        _marker: PhantomData,
+    };
+    let y = Typed::<Vec<Option<fn(u8) -> u8>>> {
+        value: 2,
+        text: "PhantomData<u8>",
+        _marker: PhantomData,
+    };
+
+    assert_throws!(
+        one_assert::assert!(x == y, clean),
+        r#"assertion `x == y` failed
+     left: Typed { value: 1, text: "PhantomData<u8>", _marker: PhantomData }
+    right: Typed { value: 2, text: "PhantomData<u8>", _marker: PhantomData }"#
+    );
+
+    assert_throws!(
+        one_assert::assert!(x == y, clean, "with {}", "message"),
+        r#"assertion `x == y` failed: with message
+     left: Typed { value: 1, text: "PhantomData<u8>", _marker: PhantomData }
+    right: Typed { value: 2, text: "PhantomData<u8>", _marker: PhantomData }"#
+    );
+
+    assert_throws!(
+        one_assert::assert!(x == y),
+        r#"assertion `x == y` failed
+     left: Typed { value: 1, text: "PhantomData<u8>", _marker: PhantomData<alloc::vec::Vec<core::option::Option<fn(u8) -> u8>>> }
+    right: Typed { value: 2, text: "PhantomData<u8>", _marker: PhantomData<alloc::vec::Vec<core::option::Option<fn(u8) -> u8>>> }"#
+    );
+}
+
+#[test]
+#[deny(unused)]
+fn test_warn_unused() {
+    let x = 1;
+    let s = String::from("hello");
+    one_assert::assert!(x == 1, warn_unused);
+    one_assert::assert!(s.starts_with("he"), warn_unused);
+    one_assert::assert!(!s.is_empty(), warn_unused, "{}", s);
+    one_assert::assert!(true || x == 2, warn_unused);
+    one_assert::assert!(
+        {
+            let y = x + 1;
+            y == 2
+        },
+        warn_unused
+    );
+    one_assert::assert!(if x == 1 { s.len() == 5 } else { false }, warn_unused);
+    one_assert::assert!(
+        match x {
+            1 => true,
+            _ => false,
+        },
+        warn_unused
+    );
+
+    assert_throws!(
+        one_assert::assert!(x == 2, warn_unused),
+        "assertion `x == 2` failed
+     left: 1
+    right: 2"
+    );
+}
+
+#[test]
+fn test_warn_duplicates() {
+    static CALLS: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(0);
+    fn next() -> i32 {
+        CALLS.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1
+    }
+    // no warning without the option, which would fail the build with `-D warnings`
+    one_assert::assert!(next() != next() && next() < next());
+
+    #[allow(deprecated)]
+    {
+        one_assert::assert!(next() != next() && next() < next(), warn_duplicates);
+        assert_throws!(
+            one_assert::assert!(next() == next(), warn_duplicates),
+            "assertion `next() == next()` failed
+     left: 9
+    right: 10"
+        );
+    }
+}
+
+#[test]
+fn test_precision() {
+    let x = std::f64::consts::PI;
+    let y = 3.15_f32;
+    one_assert::assert!(x < 4.0, precision = 2);
+
+    assert_throws!(
+        one_assert::assert!(x > 3.2, precision = 2),
+        "assertion `x > 3.2` failed
+     left: 3.14
+    right: 3.20"
+    );
+    assert_throws!(
+        one_assert::assert!(x as f32 == y, precision = 1),
+        "assertion `x as f32 == y` failed
+     left: 3.1
+    right: 3.2"
+    );
+    assert_throws!(
+        one_assert::assert!(x.max(1.0) == 2.0 / 3.0, precision = 3),
+        "assertion `x.max(1.0) == 2.0 / 3.0` failed
+        x: 3.142
+     left: 3.142
+    right: 0.667"
+    );
+
+    // non-float values are not affected
+    let v = vec![1.23456];
+    assert_throws!(
+        one_assert::assert!(v.len() == 2, precision = 2),
+        "assertion `v.len() == 2` failed
+     left: 1
+    right: 2"
+    );
+    assert_throws!(
+        one_assert::assert!(v[0] == 0.5, precision = 2),
+        "assertion `v [0] == 0.5` failed
+     left: 1.23
+    right: 0.50"
+    );
+    assert_throws!(
+        one_assert::assert!(v.contains(&0.5), precision = 2),
+        "assertion `v.contains(& 0.5)` failed
+     self: [1.23456]
+    arg 0: 0.50"
+    );
+
+    assert_throws!(
+        one_assert::assert!(x > 3.2),
+        "assertion `x > 3.2` failed
+     left: 3.141592653589793
+    right: 3.2"
+    );
+}
+
+#[test]
+fn test_simplify_negation() {
+    let a = 1;
+    let b = true;
+    one_assert::assert!(!(a == 2), simplify_negation);
+
+    assert_throws!(
+        one_assert::assert!(!(a == 1), simplify_negation),
+        "assertion `a != 1` failed
+     left: 1
+    right: 1"
+    );
+    assert_throws!(
+        one_assert::assert!(!(a != 2), simplify_negation),
+        "assertion `a == 2` failed
+     left: 1
+    right: 2"
+    );
+    assert_throws!(
+        one_assert::assert!(!(a < 2), simplify_negation),
+        "assertion `a >= 2` failed
+     left: 1
+    right: 2"
+    );
+    assert_throws!(
+        one_assert::assert!(!(a <= 1), simplify_negation),
+        "assertion `a > 1` failed
+     left: 1
+    right: 1"
+    );
+    assert_throws!(
+        one_assert::assert!(!(a > 0), simplify_negation),
+        "assertion `a <= 0` failed
+     left: 1
+    right: 0"
+    );
+    assert_throws!(
+        one_assert::assert!(!(a >= 0), simplify_negation, "a is {}", a),
+        "assertion `a < 0` failed: a is 1
+     left: 1
+    right: 0"
+    );
+
+    // nested in a block
+    assert_throws!(
+        one_assert::assert!({ !(a == 1) }, simplify_negation),
+        "assertion `{ a != 1 }` failed
+  caused by: block return assertion `a != 1` failed
+       left: 1
+      right: 1"
+    );
+
+    // every negated comparison is shown simplified, with parentheses where the `!` bound more tightly
+    assert_throws!(
+        one_assert::assert!(!(a < 2) || !(a < 2), simplify_negation),
+        "assertion `a >= 2 || a >= 2` failed
+    operand 1: false
+    operand 2: false
+  all 2 operands were false"
+    );
+    assert_throws!(
+        one_assert::assert!(!(a == 1) == b, simplify_negation),
+        "assertion `(a != 1) == b` failed
+     left: false
+    right: true"
+    );
+
+    // the condition is still evaluated as written: `!(NaN < 1.0)` is true, while `NaN >= 1.0` is false
+    let nan = f64::NAN;
+    one_assert::assert!(!(nan < 1.0), simplify_negation);
+
+    // other negations are not affected
+    assert_throws!(
+        one_assert::assert!(!b, simplify_negation),
+        "assertion `! b` failed
+    assertion negated: true"
+    );
+    assert_throws!(
+        one_assert::assert!(!(a == 1), layout = "default"),
+        "assertion `! (a == 1)` failed
+    assertion negated: true
+                 left: 1
+                right: 1"
+    );
+}
+
+#[test]
+fn test_module() {
+    let a = 1;
+    one_assert::assert!(a == 1, module);
+
+    assert_throws!(
+        one_assert::assert!(a == 2, module),
+        "assertion `a == 2` failed [in options]
+     left: 1
+    right: 2"
+    );
+
+    mod inner {
+        pub fn check(a: i32) {
+            one_assert::assert!(a == 2, module, "a is {}", a);
+        }
+    }
+    assert_throws!(
+        inner::check(a),
+        "assertion `a == 2` failed [in options::inner]: a is 1
+     left: 1
+    right: 2"
+    );
+}
+
+#[test]
+fn test_level() {
+    let a = 1;
+    assert_throws!(
+        one_assert::assert!(a == 2, level = "critical"),
+        "[critical] assertion `a == 2` failed
+     left: 1
+    right: 2"
+    );
+    assert_throws!(
+        one_assert::assert!(a == 2, module, level = "critical", "a is {}", a),
+        "[critical] assertion `a == 2` failed [in options]: a is 1
+     left: 1
+    right: 2"
+    );
+
+    // warnings are printed to stderr instead of panicking
+    one_assert::assert!(a == 2, level = "warning");
+    let mut evaluations = 0;
+    one_assert::assert!(
+        {
+            evaluations += 1;
+            a > 1
+        },
+        level = "warning"
+    );
+    assert_eq!(evaluations, 1);
+}
+
+#[test]
+fn test_types() {
+    trait Describe {
+        fn is_small(&self) -> bool;
+    }
+    impl Describe for u8 {
+        fn is_small(&self) -> bool {
+            *self < 10
+        }
+    }
+    impl Describe for String {
+        fn is_small(&self) -> bool {
+            self.len() < 3
+        }
+    }
+
+    let x = 5u8;
+    one_assert::assert!(x.is_small(), types);
+
+    let s = String::from("long");
+    assert_throws!(
+        one_assert::assert!(s.is_small(), types),
+        r#"assertion `s.is_small()` failed
+         self: "long"
+    self type: alloc::string::String"#
+    );
+    assert_throws!(
+        one_assert::assert!((x + 10).is_small(), types),
+        "assertion `(x + 10).is_small()` failed
+         self: 15
+    self type: u8"
+    );
+
+    // the type is taken before the receiver is consumed
+    let v = vec![1, 2];
+    assert_throws!(
+        one_assert::assert!(v.into_iter().any(|x| x > 2), types),
+        "assertion `v.into_iter().any(| x | x > 2)` failed
+                      self: IntoIter([1, 2])
+                 self type: alloc::vec::into_iter::IntoIter<i32>
+    first matching element: none (checked 2 elements)"
+    );
+}
+
+#[test]
+fn test_discriminant() {
+    #[derive(Debug, PartialEq, one_assert::OneAssertDiscriminant)]
+    #[repr(u8)]
+    enum Tag {
+        A = 1,
+        B = 5,
+    }
+    #[derive(Debug, PartialEq, PartialOrd, one_assert::OneAssertDiscriminant)]
+    enum Plain {
+        First,
+        Second,
+    }
+
+    let tag = Tag::A;
+    one_assert::assert!(tag == Tag::A, discriminant);
+    assert_throws!(
+        one_assert::assert!(tag == Tag::B, discriminant),
+        "assertion `tag == Tag::B` failed
+     left: A (1)
+    right: B (5)"
+    );
+    assert_throws!(
+        one_assert::assert!(Plain::First > Plain::Second, discriminant),
+        "assertion `Plain::First > Plain::Second` failed
+     left: First (0)
+    right: Second (1)"
+    );
+
+    // other values are printed as usual
+    let x = 1;
+    assert_throws!(
+        one_assert::assert!(x == 2, discriminant),
+        "assertion `x == 2` failed
+     left: 1
+    right: 2"
+    );
+}
+
+#[test]
+fn test_message_position() {
+    let x = 1;
+    let flag = true;
+    assert_throws!(
+        one_assert::assert!(
+            if flag { x == 2 } else { x == 3 },
+            message_position = "cause",
+            "x is {}",
+            x
+        ),
+        "assertion `if flag { x == 2 } else { x == 3 }` failed
+    condition `flag`: true
+  caused by: block return assertion `x == 2` failed: x is 1
+       left: 1
+      right: 2"
+    );
+    assert_throws!(
+        one_assert::assert!(
+            if !flag { x == 2 } else { x == 3 },
+            message_position = "cause",
+            module,
+            "x is {}",
+            x
+        ),
+        "assertion `if ! flag { x == 2 } else { x == 3 }` failed [in options]
+    condition `! flag`: false
+  caused by: block return assertion `x == 3` failed: x is 1
+       left: 1
+      right: 3"
+    );
+
+    // without a cause, the message stays in the header
+    assert_throws!(
+        one_assert::assert!(x == 2, message_position = "cause", "x is {}", x),
+        "assertion `x == 2` failed: x is 1
+     left: 1
+    right: 2"
+    );
+
+    // the message stays in place with `simplify_negation`
+    assert_throws!(
+        one_assert::assert!(
+            { !(x < 2) },
+            message_position = "cause",
+            simplify_negation,
+            "x is {}",
+            x
+        ),
+        "assertion `{ x >= 2 }` failed
+  caused by: block return assertion `x >= 2` failed: x is 1
+       left: 1
+      right: 2"
+    );
+}
+
+#[test]
+fn test_spans() {
+    fn sum_is(x: i32, y: i32, expected: i32) -> bool {
+        x + y == expected
+    }
+    let (a, b) = (1, 2);
+    let message = std::panic::catch_unwind(|| {
+        one_assert::assert!(sum_is(a, b * 10, 4), spans);
+    })
+    .unwrap_err();
+    let message = message.downcast_ref::<String>().unwrap();
+
+    // locations are only available since Rust 1.88
+    if rustc_version::version().unwrap() < rustc_version::Version::new(1, 88, 0) {
+        assert_eq!(
+            message,
+            "assertion `sum_is(a, b * 10, 4)` failed
+    arg 0: 1
+    arg 1: 20
+    arg 2: 4"
+        );
+    } else {
+        assert_eq!(
+            message,
+            "assertion `sum_is(a, b * 10, 4)` failed
+       arg 0 (col 36): 1
+    arg 1 (col 39-44): 20
+       arg 2 (col 47): 4"
+        );
+    }
+}
+
+#[test]
+fn test_fmt_with() {
+    fn hex<T: std::fmt::LowerHex>(value: &T) -> String {
+        format!("{value:#x}")
+    }
+    fn bracketed(value: &i32) -> String {
+        format!("[{value}]")
+    }
+
+    let (a, b) = (255, 16);
+    assert_throws!(
+        one_assert::assert!(a == b, fmt_with = hex),
+        "assertion `a == b` failed
+     left: 0xff
+    right: 0x10"
+    );
+
+    // the attribute only applies to its operand, and takes precedence over the option
+    assert_throws!(
+        one_assert::assert!(
+            #[fmt_with(hex)]
+            a == b
+        ),
+        "assertion `a == b` failed
+     left: 0xff
+    right: 16"
+    );
+    assert_throws!(
+        one_assert::assert!(
+            a == #[fmt_with(bracketed)]
+            b,
+            fmt_with = hex
+        ),
+        "assertion `a == b` failed
+     left: 0xff
+    right: [16]"
+    );
+}
+
+#[test]
+fn test_display_attribute() {
+    let name = String::from("Bob");
+    let greeting = "Hello Alice";
+    assert_throws!(
+        one_assert::assert!(
+            #[display]
+            greeting
+                == format!("Hello {name}")
+        ),
+        r#"assertion `greeting == format! ("Hello {name}")` failed
+     left: Hello Alice
+    right: "Hello Bob""#
+    );
+
+    let invalid = "x".parse::<i32>().unwrap_err();
+    let empty = "".parse::<i32>().unwrap_err();
+    assert_throws!(
+        one_assert::assert!(
+            #[display]
+            invalid
+                == empty
+        ),
+        "assertion `invalid == empty` failed
+     left: invalid digit found in string
+    right: ParseIntError { kind: Empty }"
+    );
+
+    // the attribute takes precedence over the `fmt_with` option, which still applies to the other operand
+    fn hex(value: &u8) -> String {
+        format!("{value:#x}")
+    }
+    let (a, b) = (255u8, 16u8);
+    assert_throws!(
+        one_assert::assert!(
+            #[display]
+            a == b,
+            fmt_with = hex
+        ),
+        "assertion `a == b` failed
+     left: 255
+    right: 0x10"
+    );
+}
+
+#[test]
+fn test_verbose() {
+    #[derive(Debug)]
+    struct Tls {
+        enabled: bool,
+    }
+    #[derive(Debug)]
+    struct Server {
+        tls: Tls,
+        backup: Option<Tls>,
+    }
+    impl Server {
+        fn tls(&self, _index: usize) -> &Tls {
+            &self.tls
+        }
+    }
+    #[derive(Debug)]
+    struct Config {
+        server: Server,
+    }
+
+    let config = Config {
+        server: Server {
+            tls: Tls { enabled: false },
+            backup: None,
+        },
+    };
+    assert_throws!(
+        one_assert::assert!(config.server.tls.enabled),
+        "assertion `config.server.tls.enabled` failed"
+    );
+    assert_throws!(
+        one_assert::assert!(config.server.tls.enabled, verbose),
+        "assertion `config.server.tls.enabled` failed
+               config: Config { server: Server { tls: Tls { enabled: false }, backup: None } }
+        config.server: Server { tls: Tls { enabled: false }, backup: None }
+    config.server.tls: Tls { enabled: false }"
+    );
+
+    // method calls in the chain are only called once
+    let index = 0;
+    assert_throws!(
+        one_assert::assert!(config.server.tls(index).enabled, verbose),
+        "assertion `config.server.tls(index).enabled` failed
+                      config: Config { server: Server { tls: Tls { enabled: false }, backup: None } }
+               config.server: Server { tls: Tls { enabled: false }, backup: None }
+                       index: 0
+    config.server.tls(index): Tls { enabled: false }"
+    );
+
+    // unwrapping a `None` fails the assertion instead of panicking in `unwrap`
+    assert_throws!(
+        one_assert::assert!(config.server.backup.as_ref().unwrap().enabled, verbose),
+        "assertion `config.server.backup.as_ref().unwrap().enabled` failed
+                           config: Config { server: Server { tls: Tls { enabled: false }, backup: None } }
+                    config.server: Server { tls: Tls { enabled: false }, backup: None }
+             config.server.backup: None
+    config.server.backup.as_ref(): None
+  `config.server.backup.as_ref()` is None, so `unwrap` would panic"
+    );
+    let backup = Some(Tls { enabled: true });
+    one_assert::assert!(backup.as_ref().unwrap().enabled, verbose);
+
+    let result = config.server.backup.as_ref().ok_or("no backup");
+    assert_throws!(
+        one_assert::assert!(result.expect("backup").enabled, verbose),
+        r#"assertion `result.expect("backup").enabled` failed
+    result: Err("no backup")
+  `result` is Err("no backup"), so `expect` would panic"#
+    );
+
+    // a warning doesn't panic, so the rest of the assertion is skipped instead of panicking in `unwrap`
+    one_assert::assert!(
+        config.server.backup.as_ref().unwrap().enabled,
+        verbose,
+        level = "warning"
+    );
+    let result = one_assert::try_assert!(
+        config.server.backup.as_ref().unwrap().enabled,
+        verbose,
+        level = "warning"
+    );
+    assert!(result.is_ok());
+
+    // operands of comparisons are captured the same way
+    let port: Option<u16> = None;
+    let server = (port, "localhost");
+    assert_throws!(
+        one_assert::assert!(server.0.unwrap() == 80, verbose),
+        "assertion `server.0.unwrap() == 80` failed
+      server: (None, \"localhost\")
+    server.0: None
+  `server.0` is None, so `unwrap` would panic"
+    );
+    assert_throws!(
+        one_assert::assert!(config.server.tls.enabled == backup.is_some(), verbose),
+        "assertion `config.server.tls.enabled == backup.is_some()` failed
+               config: Config { server: Server { tls: Tls { enabled: false }, backup: None } }
+        config.server: Server { tls: Tls { enabled: false }, backup: None }
+    config.server.tls: Tls { enabled: false }
+                 left: false
+                right: true"
+    );
+}
+
+#[test]
+fn test_fingerprint() {
+    fn fingerprint<T: std::hash::Hash>(value: &T) -> u64 {
+        use std::hash::Hasher;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let a = vec![1, 2, 3];
+    let b = vec![1, 2, 4];
+    one_assert::assert!(a != b, fingerprint);
+    assert_throws!(
+        one_assert::assert!(a == b, fingerprint),
+        &format!(
+            "assertion `a == b` failed
+     left: [1, 2, 3]
+    right: [1, 2, 4]
+   left fingerprint: {:#018x}
+  right fingerprint: {:#018x}",
+            fingerprint(&a),
+            fingerprint(&b)
+        )
+    );
+
+    // the values are only hashed if they implement `Hash`
+    let x = 1.5;
+    assert_throws!(
+        one_assert::assert!(x == 2.5, fingerprint),
+        "assertion `x == 2.5` failed
+     left: 1.5
+    right: 2.5"
+    );
+}
+
+#[test]
+fn test_dedup() {
+    #[allow(clippy::too_many_arguments)]
+    fn ten_arg_fn(a0: u8, a1: u8, _: u8, _: u8, _: u8, _: u8, _: u8, _: u8, _: u8, _: u8) -> bool {
+        a0 == a1
+    }
+
+    let (a, b) = (1, 2);
+    one_assert::assert!(ten_arg_fn(a, a, 0, 0, 0, 0, 0, 0, 0, 0), dedup);
+    assert_throws!(
+        one_assert::assert!(ten_arg_fn(a, b, 0, 0, 0, 0, 0, 0, 0, 0), dedup),
+        "assertion `ten_arg_fn(a, b, 0, 0, 0, 0, 0, 0, 0, 0)` failed
+       arg 0: 1
+       arg 1: 2
+    arg 2..9: 0 (×8)"
+    );
+    assert_throws!(
+        one_assert::assert!(ten_arg_fn(b, a, 0, 0, 5, 5, 5, 0, 0, 1), dedup),
+        "assertion `ten_arg_fn(b, a, 0, 0, 5, 5, 5, 0, 0, 1)` failed
+       arg 0: 2
+       arg 1: 1
+    arg 2..3: 0 (×2)
+    arg 4..6: 5 (×3)
+    arg 7..8: 0 (×2)
+       arg 9: 1"
+    );
+
+    // only numbered names are collapsed, and the output stays the same if all values differ
+    assert_throws!(
+        one_assert::assert!(a != a, dedup),
+        "assertion `a != a` failed
+     left: 1
+    right: 1"
+    );
+    assert_throws!(
+        one_assert::assert!(ten_arg_fn(a, b, 3, 4, 5, 6, 7, 8, 9, 10), dedup),
+        "assertion `ten_arg_fn(a, b, 3, 4, 5, 6, 7, 8, 9, 10)` failed
+    arg 0: 1
+    arg 1: 2
+    arg 2: 3
+    arg 3: 4
+    arg 4: 5
+    arg 5: 6
+    arg 6: 7
+    arg 7: 8
+    arg 8: 9
+    arg 9: 10"
+    );
+}
+
+#[test]
+fn test_capture() {
+    fn check(id: u32, _name: &str, _retries: u8, limit: u32) -> bool {
+        id < limit
+    }
+
+    let (id, limit) = (7, 5);
+    one_assert::assert!(check(1, "name", 3, limit), capture = [0, 3]);
+    assert_throws!(
+        one_assert::assert!(check(id, "name", 3, limit), capture = [0, 3]),
+        r#"assertion `check(id, "name", 3, limit)` failed
+    arg 0: 7
+    arg 3: 5"#
+    );
+
+    // receivers are always printed, and the skipped arguments are still evaluated in order
+    let order = std::sync::Mutex::new(vec![]);
+    let next = |value: u32| {
+        order.lock().unwrap().push(value);
+        value
+    };
+    let values = [1, 2, 3];
+    assert_throws!(
+        one_assert::assert!(values.contains(&next(4)), capture = []),
+        "assertion `values.contains(& next(4))` failed
+    self: [1, 2, 3]"
+    );
+    assert_throws!(
+        one_assert::assert!(
+            check(next(5), "name", next(6) as u8, next(2)),
+            capture = [2]
+        ),
+        r#"assertion `check(next(5), "name", next(6) as u8, next(2))` failed
+    arg 2: 6"#
+    );
+    assert_eq!(*order.lock().unwrap(), [4, 5, 6, 2]);
+}
+
+#[test]
+fn test_captures() {
+    struct Config {
+        limit: u32,
+    }
+    let config = Config { limit: 3 };
+    let min = 5;
+    let in_range = |value: u32| value >= min && value <= config.limit;
+    one_assert::assert!(!in_range(4), captures = [min, config.limit]);
+    assert_throws!(
+        one_assert::assert!(in_range(4), captures = [min, config.limit]),
+        "assertion `in_range(4)` failed
+             min: 5
+    config.limit: 3
+           arg 0: 4"
+    );
+
+    // closures without arguments, which may mutate their captures
+    assert_throws!(
+        {
+            let mut calls = 0;
+            let mut count = || {
+                calls += 1;
+                calls > 2
+            };
+            one_assert::assert!(count(), captures = [calls])
+        },
+        "assertion `count()` failed
+    calls: 1"
+    );
+}
+
+#[test]
+fn test_deref() {
+    use std::{cell::RefCell, rc::Rc};
+
+    let boxed = Box::new(Box::new(5));
+    let name = Rc::new(String::from("inner"));
+    one_assert::assert!(**boxed == 5 && *name == "inner", deref);
+    assert_throws!(
+        one_assert::assert!(boxed == Box::new(Box::new(3)), deref),
+        "assertion `boxed == Box::new(Box::new(3))` failed
+     left: 5
+    right: 3
+  **left is 5
+  **right is 3"
+    );
+    // values that are not pointers don't get a note
+    assert_throws!(
+        one_assert::assert!(**boxed < 3, deref),
+        "assertion `* * boxed < 3` failed
+     left: 5
+    right: 3"
+    );
+    // `Rc<String>` is followed all the way to the `str`
+    assert_throws!(
+        {
+            let name = Rc::new(String::from("inner"));
+            one_assert::assert!(name.is_empty(), deref)
+        },
+        r#"assertion `name.is_empty()` failed
+    self: "inner"
+  **self is "inner""#
+    );
+
+    // a `Ref` that points into a shared `RefCell` is followed to the value, without moving anything
+    assert_throws!(
+        {
+            let cell = Rc::new(RefCell::new(vec![1, 2]));
+            one_assert::assert!(cell.borrow().is_empty(), deref)
+        },
+        "assertion `cell.borrow().is_empty()` failed
+    self: [1, 2]
+  **self is [1, 2]"
+    );
+}
+
+#[test]
+fn test_auto_context() {
+    const LIMIT: usize = 3;
+    let items = vec![1, 2, 3, 4];
+    let offset = 2;
+    let name = String::from("items");
+    one_assert::assert!(items.len() - offset < LIMIT, auto_context);
+    assert_throws!(
+        one_assert::assert!(items.len() + offset < LIMIT, auto_context),
+        "assertion `items.len() + offset < LIMIT` failed
+     items: [1, 2, 3, 4]
+    offset: 2
+      left: 6
+     right: 3"
+    );
+
+    // closure parameters, called functions and the variables of `let` are left out, and each local is printed once
+    let check = |len: usize| len == offset;
+    assert_throws!(
+        one_assert::assert!(
+            check(items.len()) && items.iter().any(|x| *x > 9) && name.is_empty(),
+            auto_context
+        ),
+        r#"assertion `check(items.len()) && items.iter().any(| x | * x > 9) && name.is_empty()` failed
+        items: [1, 2, 3, 4]
+         name: "items"
+    operand 1: false
+    operand 2: false
+    operand 3: false
+  operand 1 of 3 (check(items.len())) was false"#
+    );
+}
+
+#[test]
+fn test_show_result() {
+    #[derive(Debug)]
+    struct AddsToBool(i32);
+    impl std::ops::Add for AddsToBool {
+        type Output = bool;
+        fn add(self, rhs: Self) -> bool {
+            self.0 == rhs.0
+        }
+    }
+
+    one_assert::assert!(AddsToBool(1) + AddsToBool(1), show_result);
+    assert_throws!(
+        one_assert::assert!(AddsToBool(1) + AddsToBool(2), show_result),
+        "assertion `AddsToBool(1) + AddsToBool(2)` failed
+      left: AddsToBool(1)
+     right: AddsToBool(2)
+    result: false"
+    );
+    // the result is taken before the negation
+    assert_throws!(
+        one_assert::assert!(!(AddsToBool(3) + AddsToBool(3)), show_result),
+        "assertion `! (AddsToBool(3) + AddsToBool(3))` failed
+    assertion negated: true
+                 left: AddsToBool(3)
+                right: AddsToBool(3)
+               result: true"
+    );
+}
+
+#[test]
+fn test_redact() {
+    let token = "hunter2";
+    let expected = "letmein";
+    one_assert::assert!(token != expected, redact);
+    assert_throws!(
+        one_assert::assert!(token == expected, redact),
+        "assertion `<redacted>` failed
+     left: \"hunter2\"
+    right: \"letmein\""
+    );
+    assert_throws!(
+        one_assert::assert!(token == expected, redact = "values"),
+        "assertion `token == expected` failed
+     left: <redacted>
+    right: <redacted>"
+    );
+    assert_throws!(
+        one_assert::assert!(token.len() == 3, redact = "all", "bad token"),
+        "assertion `<redacted>` failed: bad token
+     left: <redacted>
+    right: <redacted>"
+    );
+
+    // nested conditions redact the "caused by" lines as well
+    assert_throws!(
+        one_assert::assert!(
+            if token.len() > 20 {
+                false
+            } else {
+                token == expected
+            },
+            redact
+        ),
+        "assertion `<redacted>` failed
+    condition `<redacted>`: false
+  caused by: <redacted>
+       left: \"hunter2\"
+      right: \"letmein\""
+    );
+
+    // the code is hidden everywhere, not only in the first line
+    let pair = (token, expected);
+    assert_throws!(
+        one_assert::assert!(token.len() == 7 && pair.0 == pair.1, redact),
+        "assertion `<redacted>` failed
+     operand 1 left: 7
+    operand 1 right: 7
+     operand 2 left: \"hunter2\"
+    operand 2 right: \"letmein\"
+  operand 2 of 2 (<redacted>) was false"
+    );
+    assert_throws!(
+        one_assert::assert!(pair.0 == pair.1, redact, "{condition} was false"),
+        "assertion `<redacted>` failed: <redacted> was false
+     left: \"hunter2\"
+    right: \"letmein\""
+    );
+    assert_throws!(
+        one_assert::assert!(token == expected, redact, auto_context),
+        "assertion `<redacted>` failed
+    <redacted>: \"hunter2\"
+    <redacted>: \"letmein\"
+          left: \"hunter2\"
+         right: \"letmein\""
+    );
+    let is_expected = |t: &str| t == expected;
+    assert_throws!(
+        one_assert::assert!(is_expected(token), redact, captures = [expected]),
+        "assertion `<redacted>` failed
+    <redacted>: \"letmein\"
+         arg 0: \"hunter2\""
+    );
+}
@@ -0,0 +1,47 @@
+//! Only with the `output` feature: `cargo test --features output`
+#![cfg(feature = "output")]
+
+use std::io::Write;
+
+macro_rules! assert_throws {
+    ( $block:block, $message:expr $(,)? ) => {
+        let error = std::panic::catch_unwind(|| $block).unwrap_err();
+        if let Some(s) = error.downcast_ref::<&'static str>() {
+            assert_eq!(*s, $message);
+        } else if let Some(s) = error.downcast_ref::<String>() {
+            assert_eq!(s, $message);
+        } else {
+            panic!("unexpected panic payload: {:?}", error);
+        }
+    };
+    ( $statement:expr, $message:expr $(,)? ) => {
+        assert_throws!({ $statement }, $message);
+    };
+}
+
+fn greet(name: &str, out: &mut dyn Write) -> std::io::Result<usize> {
+    writeln!(out, "Hello, {name}!")?;
+    writeln!(out, "Bye")?;
+    Ok(name.len())
+}
+
+#[test]
+fn test_assert_output_contains() {
+    let len = one_assert::assert_output_contains!(|out| greet("world", out), "Hello, world");
+    assert_eq!(len.unwrap(), 5);
+    let expected = String::from("Bye\n");
+    one_assert::assert_output_contains!(|out| out.write_all(b"Bye\n"), expected).unwrap();
+
+    assert_throws!(
+        one_assert::assert_output_contains!(|out| greet("you", out), "world"),
+        r#"assertion `output of | out | greet("you", out) contains "world"` failed
+    output: "Hello, you!\nBye\n"
+    needle: "world""#
+    );
+    assert_throws!(
+        one_assert::assert_output_contains!(|_| (), "anything", "{} lines", 0),
+        r#"assertion `output of | _ | () contains "anything"` failed: 0 lines
+    output: ""
+    needle: "anything""#
+    );
+}
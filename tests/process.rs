@@ -0,0 +1,60 @@
+//! Only with the `process` feature: `cargo test --features process`
+#![cfg(feature = "process")]
+
+use std::process::Command;
+
+macro_rules! assert_throws {
+    ( $block:block, $message:expr $(,)? ) => {
+        let error = std::panic::catch_unwind(|| $block).unwrap_err();
+        if let Some(s) = error.downcast_ref::<&'static str>() {
+            assert_eq!(*s, $message);
+        } else if let Some(s) = error.downcast_ref::<String>() {
+            assert_eq!(s, $message);
+        } else {
+            panic!("unexpected panic payload: {:?}", error);
+        }
+    };
+    ( $statement:expr, $message:expr $(,)? ) => {
+        assert_throws!({ $statement }, $message);
+    };
+}
+
+#[test]
+fn test_assert_success() {
+    let cargo = env!("CARGO");
+    let output = one_assert::assert_success!(Command::new(cargo).arg("--version"));
+    assert!(output.stdout.starts_with(b"cargo "));
+    let mut command = Command::new(cargo);
+    command.arg("--version");
+    one_assert::assert_success!(command);
+
+    let error = std::panic::catch_unwind(|| {
+        one_assert::assert_success!(
+            Command::new("one_assert_missing_program"),
+            "for {}",
+            "setup"
+        );
+    })
+    .unwrap_err();
+    let message = error.downcast_ref::<String>().unwrap();
+    assert!(message.starts_with(
+        "assertion `Command::new(\"one_assert_missing_program\") exits successfully` failed: for setup
+    command: \"one_assert_missing_program\"
+      error: couldn't run the command: "
+    ));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_assert_success_output() {
+    assert_throws!(
+        one_assert::assert_success!(
+            Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"])
+        ),
+        r#"assertion `Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]) exits successfully` failed
+      command: "sh" "-c" "echo out; echo err >&2; exit 3"
+    exit code: 3
+       stdout: "out\n"
+       stderr: "err\n""#
+    );
+}
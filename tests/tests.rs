@@ -83,8 +83,9 @@ fn test_one_assert() {
     assert_throws!(
         one_assert::assert!(x && false),
         "assertion `x && false` failed
-     left: true
-    right: false",
+    operand 1: true
+    operand 2: false
+  operand 2 of 2 (false) was false",
     );
 }
 
@@ -102,8 +103,89 @@ fn test_one_assert_message() {
     assert_throws!(
         one_assert::assert!(x && false, "x={}", x),
         "assertion `x && false` failed: x=true
-     left: true
-    right: false",
+    operand 1: true
+    operand 2: false
+  operand 2 of 2 (false) was false",
+    );
+}
+
+#[test]
+fn test_condition_placeholder() {
+    let (x, i) = (1, 3);
+    assert_throws!(
+        one_assert::assert!(x == 2, "check {condition} failed in loop {}", i),
+        "assertion `x == 2` failed: check x == 2 failed in loop 3
+     left: 1
+    right: 2",
+    );
+
+    // braces in the condition are escaped, and `{{condition}}` stays literal
+    let items = [1, 2];
+    assert_throws!(
+        one_assert::assert!(items.iter().all(|x| { *x > 1 }), "{condition} vs {{condition}}"),
+        "assertion `items.iter().all(| x | { * x > 1 })` failed: items.iter().all(| x | { * x > 1 }) vs {condition}
+                     self: Iter([1, 2])
+    first failing element: 1 (at index 0)",
+    );
+}
+
+#[test]
+fn test_actual_expected() {
+    let computed = 6 * 7;
+    one_assert::assert!(actual = computed, expected = 42);
+    one_assert::assert!(expected = 42, actual = computed, "with a message");
+
+    assert_throws!(
+        one_assert::assert!(actual = computed + 1, expected = 42),
+        "assertion `computed + 1 == 42` failed
+      actual: 43
+    expected: 42",
+    );
+    assert_throws!(
+        one_assert::assert!(
+            expected = true,
+            actual = computed < 10,
+            clean,
+            "x={}",
+            computed
+        ),
+        "assertion `(computed < 10) == true` failed: x=42
+      actual: false
+    expected: true",
+    );
+}
+
+#[test]
+fn test_group_label() {
+    let (a, b) = (1, 2);
+    one_assert::assert!(invariant: a < b);
+    one_assert::assert!(a < b);
+    one_assert::assert!(std::cmp::min(a, b) == a); // paths are not labels
+
+    assert_throws!(
+        one_assert::assert!(invariant: a == b),
+        "[invariant] assertion `a == b` failed
+     left: 1
+    right: 2",
+    );
+    assert_throws!(
+        one_assert::assert!(a == b),
+        "assertion `a == b` failed
+     left: 1
+    right: 2",
+    );
+    assert_throws!(
+        one_assert::assert!(setup: actual = a, expected = 2, level = "critical", "a is {}", a),
+        "[critical] [setup] assertion `a == 2` failed: a is 1
+      actual: 1
+    expected: 2",
+    );
+    let result = one_assert::try_assert!(ordering: b < a);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "[ordering] assertion `b < a` failed
+     left: 2
+    right: 1"
     );
 }
 
@@ -112,6 +194,22 @@ fn test_misc() {
     one_assert::assert!(!"abc123".replace(|c: char| c.is_alphabetic(), "").is_empty());
 }
 
+#[test]
+fn test_literal_comparison() {
+    // always true comparisons of literals don't generate any checks
+    one_assert::assert!(1 < 2);
+    one_assert::assert!(5u8 == 5, "{} is not {}", 5, 5);
+    one_assert::assert!(true != false);
+
+    // anything that isn't a comparison of two integer or bool literals is evaluated as usual
+    assert_throws!(
+        one_assert::assert!(1.5 > 2.5),
+        "assertion `1.5 > 2.5` failed
+     left: 1.5
+    right: 2.5",
+    );
+}
+
 #[test]
 fn test_single_evaluation() {
     fn create_caller() -> impl FnMut() -> bool {
@@ -140,6 +238,51 @@ fn test_single_evaluation() {
     );
 }
 
+#[test]
+fn test_single_evaluation_method_calls() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    fn counted<T>(value: T) -> T {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        value
+    }
+    fn calls() -> usize {
+        CALLS.swap(0, Ordering::SeqCst)
+    }
+
+    // receiver
+    one_assert::assert!(std::env::var(counted("ONE_ASSERT_NOT_SET")).is_err());
+    assert_eq!(calls(), 1);
+    one_assert::assert!(counted(vec![1, 2, 3]).contains(&2));
+    assert_eq!(calls(), 1);
+
+    // arguments
+    one_assert::assert!(vec![1, 2, 3].contains(counted(&2)));
+    assert_eq!(calls(), 1);
+    one_assert::assert!(counted("abc").starts_with(counted("a")));
+    assert_eq!(calls(), 2);
+
+    // method chains
+    one_assert::assert!(counted("a,b").split(counted(',')).count() == 2);
+    assert_eq!(calls(), 2);
+
+    // all/any
+    one_assert::assert!(counted([1, 2, 3]).into_iter().all(|x| counted(x) > 0));
+    assert_eq!(calls(), 4);
+    one_assert::assert!(counted([1, 2, 3]).into_iter().any(|x| counted(x) == 2));
+    assert_eq!(calls(), 3);
+
+    // failing assertions print the value from the single evaluation
+    assert_throws!(
+        one_assert::assert!(counted(String::from("abc")).ends_with(counted("b"))),
+        r#"assertion `counted(String::from("abc")).ends_with(counted("b"))` failed
+     self: "abc"
+    arg 0: "b""#,
+    );
+    assert_eq!(calls(), 2);
+}
+
 #[test]
 fn test_crazy_nonsense() {
     #[derive(Debug)]
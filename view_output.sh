@@ -2,8 +2,8 @@
 OUT_FILE="target/out.rs"
 echo "fn main() {" > "$OUT_FILE"
 
-echo "" >> src/lib.rs # Force cargo to recompile
-rustfmt src/lib.rs
+echo "" >> one_assert_macro/src/lib.rs # Force cargo to recompile
+rustfmt one_assert_macro/src/lib.rs
 
 "$@" >> "$OUT_FILE"
 